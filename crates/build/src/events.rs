@@ -0,0 +1,83 @@
+use serde::Serialize;
+use std::{io::Write, time::Duration};
+use subprocess::ExitStatus;
+
+/// A build progress event, reported as a line of JSON on stdout when
+/// [`BuildOptions::json_output`](crate::BuildOptions::json_output) is set.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum BuildEvent<'a> {
+    /// A build command is about to run.
+    StepStarted {
+        component: &'a str,
+        command: &'a str,
+        workdir: Option<&'a str>,
+    },
+    /// A build command has completed.
+    StepFinished {
+        component: &'a str,
+        command: &'a str,
+        duration_ms: u128,
+        /// None if the command did not exit normally, e.g. it was killed by a signal.
+        exit_code: Option<u32>,
+        success: bool,
+    },
+    /// A component was not built because it is unchanged since its last
+    /// successful build.
+    Skipped { component: &'a str },
+    /// All components were built.
+    Finished { duration_ms: u128 },
+}
+
+impl BuildEvent<'_> {
+    pub fn step_finished<'a>(
+        component: &'a str,
+        command: &'a str,
+        duration: Duration,
+        exit_status: &ExitStatus,
+    ) -> BuildEvent<'a> {
+        let exit_code = match exit_status {
+            ExitStatus::Exited(code) => Some(*code),
+            _ => None,
+        };
+        BuildEvent::StepFinished {
+            component,
+            command,
+            duration_ms: duration.as_millis(),
+            exit_code,
+            success: exit_status.success(),
+        }
+    }
+
+    /// Writes the event to stdout as a single line of JSON.
+    pub fn emit(&self) {
+        let mut stdout = std::io::stdout().lock();
+        match serde_json::to_string(self) {
+            Ok(json) => {
+                let _ = writeln!(stdout, "{json}");
+            }
+            Err(e) => terminal::warn!("Failed to serialize build event {self:?}: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_tagged_with_their_kind() {
+        let event = BuildEvent::step_finished(
+            "hello",
+            "cargo build",
+            Duration::from_millis(1500),
+            &ExitStatus::Exited(2),
+        );
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!("step_finished", json["event"]);
+        assert_eq!("hello", json["component"]);
+        assert_eq!(1500, json["duration_ms"]);
+        assert_eq!(2, json["exit_code"]);
+        assert_eq!(Some(false), json["success"].as_bool());
+    }
+}
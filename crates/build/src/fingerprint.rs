@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use spin_common::sha256::{hex_digest_from_bytes, hex_digest_from_file};
+use spin_manifest::schema::v2::ComponentBuildConfig;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The directory, relative to the application directory, in which Spin keeps
+/// local state.
+const STATE_DIR: &str = ".spin";
+/// The file, in the state directory, containing the fingerprints of the last
+/// successful build of each component.
+const FINGERPRINTS_FILE: &str = "build-fingerprints.json";
+
+/// The fingerprints of the last successful build of each component.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Fingerprints {
+    #[serde(default)]
+    components: BTreeMap<String, ComponentFingerprint>,
+}
+
+/// Hashes of a component's build inputs and output.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ComponentFingerprint {
+    inputs: String,
+    output: String,
+}
+
+impl Fingerprints {
+    /// Loads the fingerprints for the application in `app_dir`. If there are
+    /// no fingerprints, or they can't be read, every component is treated as
+    /// needing to be built.
+    pub fn load(app_dir: &Path) -> Self {
+        std::fs::read(fingerprints_file(app_dir))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the fingerprints for the application in `app_dir`.
+    pub fn save(&self, app_dir: &Path) -> Result<()> {
+        let path = fingerprints_file(app_dir);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write build fingerprints to {}", path.display()))
+    }
+
+    /// Whether the component's last successful build had the given fingerprint.
+    pub fn is_up_to_date(&self, id: &str, current: &ComponentFingerprint) -> bool {
+        self.components.get(id) == Some(current)
+    }
+
+    /// Records the fingerprint of a successful build, or forgets the
+    /// component's fingerprint if it could not be determined.
+    pub fn record(&mut self, id: &str, fingerprint: Option<ComponentFingerprint>) {
+        match fingerprint {
+            Some(fingerprint) => self.components.insert(id.to_owned(), fingerprint),
+            None => self.components.remove(id),
+        };
+    }
+}
+
+impl ComponentFingerprint {
+    /// Computes the fingerprint of a component. The inputs are the build
+    /// commands and environment, and the files matching the `watch` globs;
+    /// the output is the component's local Wasm source. Returns None if the
+    /// component does not declare its inputs, does not have a local source,
+    /// or has not been built.
+    pub fn compute(
+        build: &ComponentBuildConfig,
+        source: Option<&str>,
+        app_dir: &Path,
+    ) -> Result<Option<Self>> {
+        let Some(source) = source else {
+            return Ok(None);
+        };
+        if build.watch.is_empty() {
+            return Ok(None);
+        }
+        let output_path = app_dir.join(source);
+        if !output_path.is_file() {
+            return Ok(None);
+        }
+
+        let workdir = match &build.workdir {
+            Some(workdir) => app_dir.join(workdir),
+            None => app_dir.to_owned(),
+        };
+
+        let mut inputs = vec![];
+        for command in build.commands() {
+            inputs.push(format!("command:{command}"));
+        }
+        if let Some(workdir) = &build.workdir {
+            inputs.push(format!("workdir:{workdir}"));
+        }
+        for (key, value) in &build.environment {
+            inputs.push(format!("env:{key}={value}"));
+        }
+        for file in input_files(&workdir, &build.watch)? {
+            let relative = file.strip_prefix(&workdir).unwrap_or(&file);
+            let digest = hex_digest_from_file(&file)
+                .with_context(|| format!("Failed to read build input {}", file.display()))?;
+            inputs.push(format!("file:{}:{digest}", relative.display()));
+        }
+
+        let output = hex_digest_from_file(&output_path)
+            .with_context(|| format!("Failed to read build output {}", output_path.display()))?;
+
+        Ok(Some(Self {
+            inputs: hex_digest_from_bytes(inputs.join("\n")),
+            output,
+        }))
+    }
+}
+
+fn fingerprints_file(app_dir: &Path) -> PathBuf {
+    app_dir.join(STATE_DIR).join(FINGERPRINTS_FILE)
+}
+
+/// The files matching the `watch` globs, relative to `workdir`, in a stable order.
+fn input_files(workdir: &Path, globs: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for pattern in globs {
+        let full_pattern = workdir.join(pattern);
+        let full_pattern = full_pattern.to_string_lossy();
+        let paths = glob::glob(&full_pattern)
+            .with_context(|| format!("Invalid watch pattern '{pattern}'"))?;
+        for path in paths {
+            let path = path?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_config(watch: &[&str]) -> ComponentBuildConfig {
+        let watch = watch
+            .iter()
+            .map(|w| format!("{w:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        toml::from_str(&format!("command = \"make\"\nwatch = [{watch}]")).unwrap()
+    }
+
+    #[test]
+    fn fingerprint_changes_when_inputs_or_output_change() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let app_dir = app_dir.path();
+        std::fs::create_dir_all(app_dir.join("src")).unwrap();
+        std::fs::write(app_dir.join("src/lib.rs"), "fn main() {}").unwrap();
+        std::fs::write(app_dir.join("app.wasm"), "wasm").unwrap();
+
+        let build = build_config(&["src/**/*.rs"]);
+        let compute = || {
+            ComponentFingerprint::compute(&build, Some("app.wasm"), app_dir)
+                .unwrap()
+                .unwrap()
+        };
+
+        let original = compute();
+        assert_eq!(original, compute());
+
+        std::fs::write(app_dir.join("src/lib.rs"), "fn main() { }").unwrap();
+        let after_input_change = compute();
+        assert_ne!(original.inputs, after_input_change.inputs);
+
+        std::fs::write(app_dir.join("app.wasm"), "tampered").unwrap();
+        assert_ne!(after_input_change.output, compute().output);
+    }
+
+    #[test]
+    fn no_fingerprint_without_watch_globs_or_output() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let app_dir = app_dir.path();
+
+        let fingerprint =
+            ComponentFingerprint::compute(&build_config(&[]), Some("app.wasm"), app_dir).unwrap();
+        assert!(fingerprint.is_none());
+
+        let fingerprint =
+            ComponentFingerprint::compute(&build_config(&["*.rs"]), Some("app.wasm"), app_dir)
+                .unwrap();
+        assert!(fingerprint.is_none());
+    }
+
+    #[test]
+    fn fingerprints_round_trip() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let fingerprint = ComponentFingerprint {
+            inputs: "abc".to_owned(),
+            output: "def".to_owned(),
+        };
+
+        let mut fingerprints = Fingerprints::load(app_dir.path());
+        assert!(!fingerprints.is_up_to_date("hello", &fingerprint));
+        fingerprints.record("hello", Some(fingerprint.clone()));
+        fingerprints.save(app_dir.path()).unwrap();
+
+        let reloaded = Fingerprints::load(app_dir.path());
+        assert!(reloaded.is_up_to_date("hello", &fingerprint));
+    }
+}
@@ -2,23 +2,63 @@
 
 //! A library for building Spin components.
 
+mod events;
+mod fingerprint;
 mod manifest;
+mod schedule;
 
 use anyhow::{anyhow, bail, Context, Result};
+use events::BuildEvent;
+use fingerprint::{ComponentFingerprint, Fingerprints};
 use manifest::ComponentBuildInfo;
 use spin_common::{paths::parent_dir, ui::quoted_path};
 use std::{
     collections::HashSet,
+    io::Write,
     path::{Path, PathBuf},
+    sync::Mutex,
+    time::Instant,
 };
 use subprocess::{Exec, Redirection};
 
 use crate::manifest::component_build_configs;
 
+/// Options controlling how components are built.
+#[derive(Clone, Debug, Default)]
+pub struct BuildOptions {
+    /// Build components even if their inputs and output are unchanged since
+    /// their last successful build.
+    pub force: bool,
+    /// The maximum number of components to build at the same time. If this
+    /// is more than 1, the output of each build command is captured and
+    /// printed, prefixed with the component ID, when the command completes.
+    /// Otherwise, components are built one at a time with the output shown
+    /// as it is produced.
+    pub jobs: usize,
+    /// The build profile to use. Components that have this profile are
+    /// built with the profile's commands and environment, and its source
+    /// path is checked for changes; other components are built as usual.
+    pub profile: Option<String>,
+    /// Report progress as newline-delimited JSON events on stdout, instead
+    /// of as text. The output of the build commands is sent to stderr.
+    pub json_output: bool,
+}
+
 /// If present, run the build command of each component.
-pub async fn build(manifest_file: &Path, component_ids: &[String]) -> Result<()> {
+///
+/// Components are built after any components whose output they use as a
+/// local dependency.
+///
+/// A component which declares its build inputs (with `watch` globs) is skipped
+/// if neither its inputs nor its output have changed since its last successful
+/// build, unless `options.force` is set.
+pub async fn build(
+    manifest_file: &Path,
+    component_ids: &[String],
+    options: &BuildOptions,
+) -> Result<()> {
     let (components, manifest_err) =
-        component_build_configs(manifest_file)
+        component_build_configs(manifest_file, options.profile.as_deref())
             .await
             .with_context(|| {
                 format!(
@@ -28,7 +68,7 @@ pub async fn build(manifest_file: &Path, component_ids: &[String]) -> Result<()>
             })?;
     let app_dir = parent_dir(manifest_file)?;
 
-    let build_result = build_components(component_ids, components, app_dir);
+    let build_result = build_components(component_ids, components, app_dir, options);
 
     if let Some(e) = manifest_err {
         terminal::warn!("The manifest has errors not related to the Wasm component build. Error details:\n{e:#}");
@@ -41,6 +81,7 @@ fn build_components(
     component_ids: &[String],
     components: Vec<ComponentBuildInfo>,
     app_dir: PathBuf,
+    options: &BuildOptions,
 ) -> Result<(), anyhow::Error> {
     let components_to_build = if component_ids.is_empty() {
         components
@@ -63,46 +104,142 @@ fn build_components(
     };
 
     if components_to_build.iter().all(|c| c.build.is_none()) {
+        if options.json_output {
+            BuildEvent::Finished { duration_ms: 0 }.emit();
+            return Ok(());
+        }
         println!("None of the components have a build command.");
         println!("For information on specifying a build command, see https://developer.fermyon.com/spin/build#setting-up-for-spin-build.");
         return Ok(());
     }
 
-    components_to_build
-        .into_iter()
-        .map(|c| build_component(c, &app_dir))
-        .collect::<Result<Vec<_>, _>>()?;
+    let dependencies = schedule::build_dependencies(&components_to_build, &app_dir)?;
+    let fingerprints = Mutex::new(Fingerprints::load(&app_dir));
+    let started = Instant::now();
 
-    terminal::step!("Finished", "building all Spin components");
+    schedule::run_in_dependency_order(&dependencies, options.jobs, |index| {
+        build_component(
+            &components_to_build[index],
+            &app_dir,
+            options,
+            &fingerprints,
+        )
+    })?;
+
+    if options.json_output {
+        BuildEvent::Finished {
+            duration_ms: started.elapsed().as_millis(),
+        }
+        .emit();
+    } else {
+        terminal::step!("Finished", "building all Spin components");
+    }
     Ok(())
 }
 
-/// Run the build command of the component.
-fn build_component(build_info: ComponentBuildInfo, app_dir: &Path) -> Result<()> {
-    match build_info.build {
+/// Run the build command of the component, unless it is up to date.
+fn build_component(
+    build_info: &ComponentBuildInfo,
+    app_dir: &Path,
+    options: &BuildOptions,
+    fingerprints: &Mutex<Fingerprints>,
+) -> Result<()> {
+    let capture_output = options.jobs > 1;
+    match &build_info.build {
         Some(b) => {
+            if !options.force {
+                let fingerprint =
+                    component_fingerprint(&build_info.id, b, &build_info.source, app_dir);
+                let up_to_date = match &fingerprint {
+                    Some(f) => fingerprints
+                        .lock()
+                        .unwrap()
+                        .is_up_to_date(&build_info.id, f),
+                    None => false,
+                };
+                if up_to_date && options.json_output {
+                    BuildEvent::Skipped {
+                        component: &build_info.id,
+                    }
+                    .emit();
+                    return Ok(());
+                }
+                if up_to_date {
+                    let _stdout = std::io::stdout().lock();
+                    terminal::step!(
+                        "Skipping",
+                        "component {} (unchanged since last build)",
+                        build_info.id
+                    );
+                    return Ok(());
+                }
+            }
+
             for command in b.commands() {
-                terminal::step!("Building", "component {} with `{}`", build_info.id, command);
                 let workdir = construct_workdir(app_dir, b.workdir.as_ref())?;
-                if b.workdir.is_some() {
-                    println!("Working directory: {}", quoted_path(&workdir));
+                if options.json_output {
+                    BuildEvent::StepStarted {
+                        component: &build_info.id,
+                        command,
+                        workdir: b.workdir.as_deref(),
+                    }
+                    .emit();
+                } else {
+                    // Hold the lock so that parallel builds don't interleave output.
+                    let _stdout = std::io::stdout().lock();
+                    terminal::step!("Building", "component {} with `{}`", build_info.id, command);
+                    if b.workdir.is_some() {
+                        println!("Working directory: {}", quoted_path(&workdir));
+                    }
                 }
+                let step_started = Instant::now();
+
+                let spawn_error = |err| {
+                    anyhow!(
+                        "Cannot spawn build process '{:?}' for component {}: {}",
+                        &b.command,
+                        build_info.id,
+                        err
+                    )
+                };
 
-                let exit_status = Exec::shell(command)
-                    .cwd(workdir)
-                    .stdout(Redirection::None)
-                    .stderr(Redirection::None)
-                    .stdin(Redirection::None)
-                    .popen()
-                    .map_err(|err| {
-                        anyhow!(
-                            "Cannot spawn build process '{:?}' for component {}: {}",
-                            &b.command,
-                            build_info.id,
-                            err
-                        )
-                    })?
-                    .wait()?;
+                let exit_status = if capture_output {
+                    let capture = with_environment(Exec::shell(command), b)
+                        .cwd(workdir)
+                        .stdout(Redirection::Pipe)
+                        .stderr(Redirection::Merge)
+                        .stdin(Redirection::None)
+                        .capture()
+                        .map_err(spawn_error)?;
+                    print_prefixed(&build_info.id, &capture.stdout, options.json_output);
+                    capture.exit_status
+                } else {
+                    // In JSON mode, stdout is reserved for events, so merge the
+                    // command's output into stderr.
+                    let stdout = if options.json_output {
+                        Redirection::Merge
+                    } else {
+                        Redirection::None
+                    };
+                    with_environment(Exec::shell(command), b)
+                        .cwd(workdir)
+                        .stdout(stdout)
+                        .stderr(Redirection::None)
+                        .stdin(Redirection::None)
+                        .popen()
+                        .map_err(spawn_error)?
+                        .wait()?
+                };
+
+                if options.json_output {
+                    BuildEvent::step_finished(
+                        &build_info.id,
+                        command,
+                        step_started.elapsed(),
+                        &exit_status,
+                    )
+                    .emit();
+                }
 
                 if !exit_status.success() {
                     bail!(
@@ -113,12 +250,57 @@ fn build_component(build_info: ComponentBuildInfo, app_dir: &Path) -> Result<()>
                 }
             }
 
+            let fingerprint = component_fingerprint(&build_info.id, b, &build_info.source, app_dir);
+            let mut fingerprints = fingerprints.lock().unwrap();
+            fingerprints.record(&build_info.id, fingerprint);
+            if let Err(e) = fingerprints.save(app_dir) {
+                terminal::warn!("{e:#}. Components will be rebuilt on the next build.");
+            }
+
             Ok(())
         }
         _ => Ok(()),
     }
 }
 
+/// Adds the component's build environment to the build command.
+fn with_environment(exec: Exec, build: &spin_manifest::schema::v2::ComponentBuildConfig) -> Exec {
+    build
+        .environment
+        .iter()
+        .fold(exec, |exec, (key, value)| exec.env(key, value))
+}
+
+/// Prints captured build output with each line prefixed by the component ID,
+/// to stderr if stdout is reserved for JSON events.
+fn print_prefixed(id: &str, output: &[u8], to_stderr: bool) {
+    let mut out: Box<dyn Write> = if to_stderr {
+        Box::new(std::io::stderr().lock())
+    } else {
+        Box::new(std::io::stdout().lock())
+    };
+    for line in String::from_utf8_lossy(output).lines() {
+        let _ = writeln!(out, "[{id}] {line}");
+    }
+}
+
+/// Computes the fingerprint of a component's build. A failure to compute it
+/// is not an error: the component is simply always built.
+fn component_fingerprint(
+    id: &str,
+    build: &spin_manifest::schema::v2::ComponentBuildConfig,
+    source: &Option<String>,
+    app_dir: &Path,
+) -> Option<ComponentFingerprint> {
+    match ComponentFingerprint::compute(build, source.as_deref(), app_dir) {
+        Ok(fingerprint) => fingerprint,
+        Err(e) => {
+            terminal::warn!("Unable to determine whether component {id} is up to date: {e:#}");
+            None
+        }
+    }
+}
+
 /// Constructs the absolute working directory in which to run the build command.
 fn construct_workdir(app_dir: &Path, workdir: Option<impl AsRef<Path>>) -> Result<PathBuf> {
     let mut cwd = app_dir.to_owned();
@@ -148,6 +330,8 @@ mod tests {
     #[tokio::test]
     async fn can_load_even_if_trigger_invalid() {
         let bad_trigger_file = test_data_root().join("bad_trigger.toml");
-        build(&bad_trigger_file, &[]).await.unwrap();
+        build(&bad_trigger_file, &[], &BuildOptions::default())
+            .await
+            .unwrap();
     }
 }
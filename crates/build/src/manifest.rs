@@ -1,6 +1,9 @@
 use anyhow::Result;
 use serde::Deserialize;
-use std::{collections::BTreeMap, path::Path};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use spin_manifest::{schema::v2, ManifestVersion};
 
@@ -8,13 +11,17 @@ use spin_manifest::{schema::v2, ManifestVersion};
 /// given (v1 or v2) manifest path. If the manifest cannot be loaded, the
 /// function attempts fallback: if fallback succeeds, result is Ok but the load error
 /// is also returned via the second part of the return value tuple.
+///
+/// If `profile` is given, the build configurations (and sources) are those
+/// selected by that build profile.
 pub async fn component_build_configs(
     manifest_file: impl AsRef<Path>,
+    profile: Option<&str>,
 ) -> Result<(Vec<ComponentBuildInfo>, Option<spin_manifest::Error>)> {
     let manifest = spin_manifest::manifest_from_file(&manifest_file);
     match manifest {
-        Ok(manifest) => Ok((build_configs_from_manifest(manifest), None)),
-        Err(e) => fallback_load_build_configs(&manifest_file)
+        Ok(manifest) => Ok((build_configs_from_manifest(manifest, profile)?, None)),
+        Err(e) => fallback_load_build_configs(&manifest_file, profile)
             .await
             .map(|bc| (bc, Some(e))),
     }
@@ -22,24 +29,42 @@ pub async fn component_build_configs(
 
 fn build_configs_from_manifest(
     mut manifest: spin_manifest::schema::v2::AppManifest,
-) -> Vec<ComponentBuildInfo> {
+    profile: Option<&str>,
+) -> Result<Vec<ComponentBuildInfo>> {
     spin_manifest::normalize::normalize_manifest(&mut manifest);
+    if let Some(profile) = profile {
+        spin_manifest::normalize::apply_build_profile(&mut manifest, profile)?;
+    }
 
-    manifest
+    Ok(manifest
         .components
         .into_iter()
         .map(|(id, c)| ComponentBuildInfo {
             id: id.to_string(),
             build: c.build,
+            source: match c.source {
+                v2::ComponentSource::Local(path) => Some(path),
+                _ => None,
+            },
+            dependency_paths: c
+                .dependencies
+                .inner
+                .into_values()
+                .filter_map(|d| match d {
+                    v2::ComponentDependency::Local { path, .. } => Some(path),
+                    _ => None,
+                })
+                .collect(),
         })
-        .collect()
+        .collect())
 }
 
 async fn fallback_load_build_configs(
     manifest_file: impl AsRef<Path>,
+    profile: Option<&str>,
 ) -> Result<Vec<ComponentBuildInfo>> {
     let manifest_text = tokio::fs::read_to_string(manifest_file).await?;
-    Ok(match ManifestVersion::detect(&manifest_text)? {
+    let mut components = match ManifestVersion::detect(&manifest_text)? {
         ManifestVersion::V1 => {
             let v1: ManifestV1BuildInfo = toml::from_str(&manifest_text)?;
             v1.components
@@ -54,7 +79,23 @@ async fn fallback_load_build_configs(
                 })
                 .collect()
         }
-    })
+    };
+
+    if let Some(profile) = profile {
+        let mut found = false;
+        for build in components.iter_mut().filter_map(|c| c.build.as_mut()) {
+            if build.profile.contains_key(profile) {
+                found = true;
+                // The source is not available in a partially loaded manifest.
+                _ = build.apply_profile(profile);
+            }
+        }
+        if !found {
+            return Err(spin_manifest::Error::UnknownBuildProfile(profile.to_owned()).into());
+        }
+    }
+
+    Ok(components)
 }
 
 #[derive(Deserialize)]
@@ -62,6 +103,14 @@ pub struct ComponentBuildInfo {
     #[serde(default)]
     pub id: String,
     pub build: Option<v2::ComponentBuildConfig>,
+    /// The local path of the component's Wasm source, which is the output of
+    /// the build. Not available if the manifest could only be partially loaded.
+    #[serde(skip)]
+    pub source: Option<String>,
+    /// The paths of the component's local dependencies. If one of these is
+    /// the source of another component, that component must be built first.
+    #[serde(skip)]
+    pub dependency_paths: Vec<PathBuf>,
 }
 
 #[derive(Deserialize)]
@@ -0,0 +1,199 @@
+use anyhow::{bail, Result};
+use std::path::{Component, Path, PathBuf};
+
+use crate::manifest::ComponentBuildInfo;
+
+/// For each component, the indexes of the components that must be built
+/// before it: those whose Wasm source is used as a local dependency of the
+/// component. Fails if the dependencies form a cycle.
+pub(crate) fn build_dependencies(
+    components: &[ComponentBuildInfo],
+    app_dir: &Path,
+) -> Result<Vec<Vec<usize>>> {
+    let outputs: Vec<_> = components
+        .iter()
+        .map(|c| c.source.as_ref().map(|s| normalize(&app_dir.join(s))))
+        .collect();
+
+    let dependencies: Vec<Vec<usize>> = components
+        .iter()
+        .enumerate()
+        .map(|(index, c)| {
+            let mut deps: Vec<_> = c
+                .dependency_paths
+                .iter()
+                .map(|p| normalize(&app_dir.join(p)))
+                .filter_map(|p| outputs.iter().position(|o| o.as_ref() == Some(&p)))
+                .filter(|dep| *dep != index)
+                .collect();
+            deps.sort_unstable();
+            deps.dedup();
+            deps
+        })
+        .collect();
+
+    let unordered = unorderable(&dependencies);
+    if !unordered.is_empty() {
+        let ids: Vec<_> = unordered
+            .iter()
+            .map(|i| components[*i].id.as_str())
+            .collect();
+        bail!(
+            "Cannot build components {} as their dependencies on each other form a cycle",
+            ids.join(", ")
+        );
+    }
+
+    Ok(dependencies)
+}
+
+/// Runs `task` for each index, with up to `jobs` tasks running at a time, and
+/// not running a task until the tasks it depends on have succeeded. Tasks are
+/// started in index order where dependencies allow. If a task fails, no more
+/// tasks are started, and the first error is returned once running tasks finish.
+pub(crate) fn run_in_dependency_order(
+    dependencies: &[Vec<usize>],
+    jobs: usize,
+    task: impl Fn(usize) -> Result<()> + Sync,
+) -> Result<()> {
+    let count = dependencies.len();
+    let jobs = jobs.max(1);
+    let mut started = vec![false; count];
+    let mut done = vec![false; count];
+    let mut running = 0;
+    let mut first_error = None;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let task = &task;
+
+    std::thread::scope(|scope| loop {
+        if first_error.is_none() {
+            for index in 0..count {
+                if running >= jobs {
+                    break;
+                }
+                if !started[index] && dependencies[index].iter().all(|d| done[*d]) {
+                    started[index] = true;
+                    running += 1;
+                    let tx = tx.clone();
+                    scope.spawn(move || {
+                        // The receiver outlives the scope, so this can't fail.
+                        let _ = tx.send((index, task(index)));
+                    });
+                }
+            }
+        }
+        if running == 0 {
+            break;
+        }
+        let (index, result) = rx.recv().expect("build tasks always report completion");
+        running -= 1;
+        match result {
+            Ok(()) => done[index] = true,
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    });
+
+    match first_error {
+        Some(e) => Err(e),
+        None if done.iter().all(|d| *d) => Ok(()),
+        None => bail!("Some components could not be built because of dependency cycles"),
+    }
+}
+
+/// The indexes that cannot be ordered because they are in, or depend on,
+/// a dependency cycle.
+fn unorderable(dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut ordered = vec![false; dependencies.len()];
+    loop {
+        let mut progressed = false;
+        for (index, deps) in dependencies.iter().enumerate() {
+            if !ordered[index] && deps.iter().all(|d| ordered[*d]) {
+                ordered[index] = true;
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+    (0..dependencies.len()).filter(|i| !ordered[*i]).collect()
+}
+
+/// Removes `.` components so that equivalent relative paths compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn component(id: &str, source: &str, dependency_paths: &[&str]) -> ComponentBuildInfo {
+        ComponentBuildInfo {
+            id: id.to_owned(),
+            build: None,
+            source: Some(source.to_owned()),
+            dependency_paths: dependency_paths.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn dependencies_are_matched_to_component_sources() {
+        let components = [
+            component("app", "app.wasm", &["./lib/lib.wasm", "external.wasm"]),
+            component("lib", "lib/lib.wasm", &[]),
+        ];
+        let deps = build_dependencies(&components, Path::new("/app")).unwrap();
+        assert_eq!(vec![vec![1], vec![]], deps);
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let components = [
+            component("a", "a.wasm", &["b.wasm"]),
+            component("b", "b.wasm", &["a.wasm"]),
+            component("c", "c.wasm", &[]),
+        ];
+        let err = build_dependencies(&components, Path::new("/app")).unwrap_err();
+        assert!(err.to_string().contains("a, b"));
+    }
+
+    #[test]
+    fn tasks_run_after_their_dependencies() {
+        let dependencies = vec![vec![2], vec![], vec![1], vec![]];
+        let completed = Mutex::new(vec![]);
+        run_in_dependency_order(&dependencies, 4, |index| {
+            completed.lock().unwrap().push(index);
+            Ok(())
+        })
+        .unwrap();
+
+        let completed = completed.into_inner().unwrap();
+        let position = |i| completed.iter().position(|c| *c == i).unwrap();
+        assert_eq!(4, completed.len());
+        assert!(position(1) < position(2));
+        assert!(position(2) < position(0));
+    }
+
+    #[test]
+    fn no_tasks_start_after_a_failure() {
+        let dependencies = vec![vec![], vec![0], vec![1]];
+        let completed = Mutex::new(vec![]);
+        let result = run_in_dependency_order(&dependencies, 1, |index| {
+            completed.lock().unwrap().push(index);
+            if index == 1 {
+                bail!("failed");
+            }
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(vec![0, 1], completed.into_inner().unwrap());
+    }
+}
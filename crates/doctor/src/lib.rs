@@ -37,8 +37,11 @@ impl Checkup {
             .add_diagnostic::<manifest::upgrade::UpgradeDiagnostic>()
             .add_diagnostic::<manifest::version::VersionDiagnostic>()
             .add_diagnostic::<manifest::trigger::TriggerDiagnostic>()
+            .add_diagnostic::<manifest::stores::StoreLabelDiagnostic>()
+            .add_diagnostic::<manifest::outbound::OutboundHostsDiagnostic>()
             .add_diagnostic::<rustlang::target::TargetDiagnostic>() // Do toolchain checks _before_ build check
-            .add_diagnostic::<wasm::missing::WasmMissingDiagnostic>();
+            .add_diagnostic::<wasm::missing::WasmMissingDiagnostic>()
+            .add_diagnostic::<wasm::imports::WasmImportsDiagnostic>();
         Ok(checkup)
     }
 
@@ -47,6 +50,13 @@ impl Checkup {
         &self.patient
     }
 
+    /// Use the given runtime config file when checking for problems with
+    /// resources (such as key-value stores) that the runtime config defines.
+    pub fn set_runtime_config_file(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.patient.runtime_config_path = Some(path.into());
+        self
+    }
+
     /// Add a detectable problem to this checkup.
     pub fn add_diagnostic<D: Diagnostic + Default + 'static>(&mut self) -> &mut Self {
         self.diagnostics.push_back(Box::<D>::default());
@@ -82,6 +92,8 @@ pub struct PatientApp {
     pub manifest_path: PathBuf,
     /// Parsed app manifest TOML document.
     pub manifest_doc: DocumentMut,
+    /// Path to the runtime config file the app will be run with, if known.
+    pub runtime_config_path: Option<PathBuf>,
 }
 
 impl PatientApp {
@@ -110,6 +122,7 @@ impl PatientApp {
         Ok(Self {
             manifest_path: path,
             manifest_doc,
+            runtime_config_path: None,
         })
     }
 }
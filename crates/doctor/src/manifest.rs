@@ -3,10 +3,14 @@ use std::fs;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use spin_common::ui::quoted_path;
-use toml_edit::DocumentMut;
+use toml_edit::{Array, DocumentMut, Item};
 
 use crate::Treatment;
 
+/// Diagnose outbound hosts that don't allow the URLs a component is configured with.
+pub mod outbound;
+/// Diagnose key-value store and SQLite database labels with no runtime config.
+pub mod stores;
 /// Diagnose app manifest trigger config problems.
 pub mod trigger;
 /// Diagnose old app manifest versions.
@@ -52,3 +56,29 @@ impl<T: ManifestTreatment + Sync> Treatment for T {
             .with_context(|| format!("failed to write fixed manifest to {}", quoted_path(path)))
     }
 }
+
+/// Returns true if the manifest document is a version 2 manifest.
+pub(crate) fn is_v2_manifest(doc: &DocumentMut) -> bool {
+    doc.get("spin_manifest_version")
+        .and_then(|version| version.as_integer())
+        == Some(2)
+}
+
+/// Returns the array value of the given key in a (version 2) component
+/// table, inserting an empty array if the key is not present.
+pub(crate) fn component_array_mut<'a>(
+    doc: &'a mut DocumentMut,
+    component_id: &str,
+    key: &str,
+) -> Result<&'a mut Array> {
+    let component = doc
+        .get_mut("component")
+        .and_then(|components| components.get_mut(component_id))
+        .and_then(|component| component.as_table_like_mut())
+        .with_context(|| format!("missing component {component_id:?}"))?;
+    component
+        .entry(key)
+        .or_insert(Item::Value(Array::new().into()))
+        .as_array_mut()
+        .with_context(|| format!("component {component_id:?} {key} is not an array"))
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use spin_outbound_networking_config::allowed_hosts::{
+    AllowedHostConfig, AllowedHostsConfig, OutboundUrl,
+};
+use toml_edit::DocumentMut;
+
+use crate::{Diagnosis, Diagnostic, PatientApp, Treatment};
+
+use super::ManifestTreatment;
+
+/// OutboundHostsDiagnostic detects component variables whose (default)
+/// values are URLs that the component's `allowed_outbound_hosts` don't allow.
+#[derive(Default)]
+pub struct OutboundHostsDiagnostic;
+
+#[async_trait]
+impl Diagnostic for OutboundHostsDiagnostic {
+    type Diagnosis = OutboundHostsDiagnosis;
+
+    async fn diagnose(&self, patient: &PatientApp) -> Result<Vec<Self::Diagnosis>> {
+        if !super::is_v2_manifest(&patient.manifest_doc) {
+            // Not applicable until the manifest has been upgraded
+            return Ok(vec![]);
+        }
+        let manifest = spin_manifest::manifest_from_str(&patient.manifest_doc.to_string())?;
+
+        let defaults: HashMap<String, String> = manifest
+            .variables
+            .iter()
+            .filter_map(|(name, var)| Some((name.to_string(), var.default.clone()?)))
+            .collect();
+
+        let mut diags = vec![];
+        for (id, component) in &manifest.components {
+            let Some(allowed) = literal_allowed_hosts(&component.allowed_outbound_hosts) else {
+                // Templated or invalid hosts can't be checked here
+                continue;
+            };
+            let mut missing_hosts: Vec<String> = vec![];
+            for (variable, value) in &component.variables {
+                let Some(value) = expand_defaults(value, &defaults) else {
+                    continue;
+                };
+                if !value.contains("://") {
+                    continue;
+                }
+                let Ok(url) = OutboundUrl::parse(value.as_str(), "https") else {
+                    continue;
+                };
+                if allowed.allows(&url) {
+                    continue;
+                }
+                let host = format!("{}://{}", url.scheme(), url.authority());
+                if missing_hosts.contains(&host) {
+                    continue;
+                }
+                missing_hosts.push(host.clone());
+                diags.push(OutboundHostsDiagnosis {
+                    component_id: id.to_string(),
+                    variable: variable.to_string(),
+                    url: value,
+                    host,
+                });
+            }
+        }
+        Ok(diags)
+    }
+}
+
+/// Parses the allowed hosts, or returns None if any of them is a template
+/// or is invalid.
+fn literal_allowed_hosts(hosts: &[String]) -> Option<AllowedHostsConfig> {
+    let allowed = hosts
+        .iter()
+        .map(|host| {
+            if host.contains("{{") {
+                None
+            } else {
+                AllowedHostConfig::parse(host.as_str()).ok()
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(AllowedHostsConfig::SpecificHosts(allowed))
+}
+
+/// Expands `{{ variable }}` references to the application variable defaults.
+/// Returns None if a referenced variable has no default.
+fn expand_defaults(template: &str, defaults: &HashMap<String, String>) -> Option<String> {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        expanded.push_str(&rest[..start]);
+        let (name, after) = rest[start + 2..].split_once("}}")?;
+        expanded.push_str(defaults.get(name.trim())?);
+        rest = after;
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+
+/// OutboundHostsDiagnosis represents a component variable URL that the
+/// component is not allowed to connect to.
+#[derive(Debug)]
+pub struct OutboundHostsDiagnosis {
+    component_id: String,
+    variable: String,
+    url: String,
+    host: String,
+}
+
+impl Diagnosis for OutboundHostsDiagnosis {
+    fn description(&self) -> String {
+        let Self {
+            component_id,
+            variable,
+            url,
+            ..
+        } = self;
+        format!(
+            "Component {component_id:?} variable {variable:?} is {url:?}, but the component's allowed_outbound_hosts don't allow it"
+        )
+    }
+
+    fn is_critical(&self) -> bool {
+        // The variable may be overridden at runtime, or may not be used
+        // for outbound requests at all.
+        false
+    }
+
+    fn treatment(&self) -> Option<&dyn Treatment> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ManifestTreatment for OutboundHostsDiagnosis {
+    fn summary(&self) -> String {
+        format!(
+            "Add {:?} to the allowed_outbound_hosts of component {:?}",
+            self.host, self.component_id
+        )
+    }
+
+    async fn treat_manifest(&self, doc: &mut DocumentMut) -> Result<()> {
+        let hosts = super::component_array_mut(doc, &self.component_id, "allowed_outbound_hosts")?;
+        hosts.push(self.host.as_str());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::{assert_single_diagnosis, TestPatient};
+
+    use super::*;
+
+    const MANIFEST: &str = r#"
+            spin_manifest_version = 2
+            [application]
+            name = "outbound-hosts-test"
+            [variables]
+            api_url = { default = "https://api.example.com/v1" }
+            token = { required = true }
+            [[trigger.http]]
+            route = "/..."
+            component = "client"
+            [component.client]
+            source = "client.wasm"
+            allowed_outbound_hosts = ["https://other.example.com"]
+            [component.client.variables]
+            api_url = "{{ api_url }}"
+            token = "{{ token }}"
+            greeting = "hello"
+        "#;
+
+    #[tokio::test]
+    async fn test_variable_url_not_allowed() {
+        let mut patient = TestPatient::from_toml_str(MANIFEST);
+        let diag = assert_single_diagnosis::<OutboundHostsDiagnostic>(&patient).await;
+        assert_eq!("https://api.example.com", diag.host);
+
+        diag.treatment().unwrap().treat(&mut patient).await.unwrap();
+        let diags = OutboundHostsDiagnostic.diagnose(&patient).await.unwrap();
+        assert!(diags.is_empty(), "expected no diagnoses, got {diags:?}");
+    }
+
+    #[test]
+    fn test_expand_defaults() {
+        let defaults = HashMap::from([("host".to_owned(), "example.com".to_owned())]);
+        assert_eq!(
+            Some("https://example.com/x".to_owned()),
+            expand_defaults("https://{{ host }}/x", &defaults)
+        );
+        assert_eq!(None, expand_defaults("https://{{ missing }}", &defaults));
+    }
+}
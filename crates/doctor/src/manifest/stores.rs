@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use spin_common::ui::quoted_path;
+
+use crate::{Diagnosis, Diagnostic, PatientApp};
+
+/// The label of the key-value store and SQLite database that Spin provides
+/// without runtime config.
+const DEFAULT_LABEL: &str = "default";
+
+/// StoreLabelDiagnostic detects key-value stores and SQLite databases that
+/// components use but that are not defined in the runtime config.
+#[derive(Default)]
+pub struct StoreLabelDiagnostic;
+
+#[async_trait]
+impl Diagnostic for StoreLabelDiagnostic {
+    type Diagnosis = StoreLabelDiagnosis;
+
+    async fn diagnose(&self, patient: &PatientApp) -> Result<Vec<Self::Diagnosis>> {
+        if !super::is_v2_manifest(&patient.manifest_doc) {
+            // Not applicable until the manifest has been upgraded
+            return Ok(vec![]);
+        }
+        let manifest = spin_manifest::manifest_from_str(&patient.manifest_doc.to_string())?;
+
+        let runtime_config = match &patient.runtime_config_path {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path).with_context(|| {
+                    format!("Couldn't read runtime config file {}", quoted_path(path))
+                })?;
+                let config: toml::Table = toml::from_str(&contents).with_context(|| {
+                    format!("Couldn't parse runtime config file {}", quoted_path(path))
+                })?;
+                Some(config)
+            }
+            None => None,
+        };
+        let is_defined = |kind: StoreKind, label: &str| {
+            runtime_config
+                .as_ref()
+                .and_then(|config| config.get(kind.runtime_config_key()))
+                .and_then(|stores| stores.get(label))
+                .is_some()
+        };
+
+        let mut diags = vec![];
+        for (id, component) in &manifest.components {
+            let labels = component
+                .key_value_stores
+                .iter()
+                .map(|label| (StoreKind::KeyValue, label))
+                .chain(
+                    component
+                        .sqlite_databases
+                        .iter()
+                        .map(|label| (StoreKind::Sqlite, label)),
+                );
+            for (kind, label) in labels {
+                if label != DEFAULT_LABEL && !is_defined(kind, label) {
+                    diags.push(StoreLabelDiagnosis {
+                        component_id: id.to_string(),
+                        kind,
+                        label: label.clone(),
+                        has_runtime_config: runtime_config.is_some(),
+                    });
+                }
+            }
+        }
+        Ok(diags)
+    }
+}
+
+/// The kind of store a label refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreKind {
+    /// A key-value store
+    KeyValue,
+    /// A SQLite database
+    Sqlite,
+}
+
+impl StoreKind {
+    fn runtime_config_key(&self) -> &'static str {
+        match self {
+            Self::KeyValue => "key_value_store",
+            Self::Sqlite => "sqlite_database",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::KeyValue => "key-value store",
+            Self::Sqlite => "SQLite database",
+        }
+    }
+}
+
+/// StoreLabelDiagnosis represents a store label with no runtime config.
+#[derive(Debug)]
+pub struct StoreLabelDiagnosis {
+    component_id: String,
+    kind: StoreKind,
+    label: String,
+    has_runtime_config: bool,
+}
+
+impl Diagnosis for StoreLabelDiagnosis {
+    fn description(&self) -> String {
+        let Self {
+            component_id,
+            kind,
+            label,
+            ..
+        } = self;
+        let table = format!("[{}.{label}]", kind.runtime_config_key());
+        if self.has_runtime_config {
+            format!(
+                "Component {component_id:?} uses {} {label:?}, but the runtime config has no {table} section",
+                kind.description()
+            )
+        } else {
+            format!(
+                "Component {component_id:?} uses {} {label:?}, which must be defined in a {table} section of a runtime config file",
+                kind.description()
+            )
+        }
+    }
+
+    fn is_critical(&self) -> bool {
+        // Without a runtime config file we can't tell if the app will be run
+        // with one that defines the store.
+        self.has_runtime_config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::{assert_single_diagnosis, TestPatient};
+
+    use super::*;
+
+    const MANIFEST: &str = r#"
+            spin_manifest_version = 2
+            [application]
+            name = "store-label-test"
+            [[trigger.http]]
+            route = "/..."
+            component = "stores"
+            [component.stores]
+            source = "stores.wasm"
+            key_value_stores = ["default", "cache"]
+            sqlite_databases = ["default"]
+        "#;
+
+    #[tokio::test]
+    async fn test_label_without_runtime_config() {
+        let patient = TestPatient::from_toml_str(MANIFEST);
+        let diag = assert_single_diagnosis::<StoreLabelDiagnostic>(&patient).await;
+        assert_eq!(StoreKind::KeyValue, diag.kind);
+        assert_eq!("cache", diag.label);
+        assert!(!diag.is_critical());
+    }
+
+    #[tokio::test]
+    async fn test_label_defined_in_runtime_config() {
+        let mut patient = TestPatient::from_toml_str(MANIFEST);
+        let runtime_config = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            runtime_config.path(),
+            "[key_value_store.cache]\ntype = \"spin\"\npath = \"cache.db\"\n",
+        )
+        .unwrap();
+        patient.runtime_config_path = Some(runtime_config.path().to_owned());
+
+        let diags = StoreLabelDiagnostic.diagnose(&patient).await.unwrap();
+        assert!(diags.is_empty(), "expected no diagnoses, got {diags:?}");
+    }
+}
@@ -1,3 +1,5 @@
+/// Diagnose Wasm imports that the manifest does not permit.
+pub mod imports;
 /// Diagnose missing Wasm sources.
 pub mod missing;
 
@@ -53,6 +55,10 @@ impl PatientWasm {
         }
     }
 
+    pub fn component(&self) -> &v2::Component {
+        &self.component
+    }
+
     pub fn has_build(&self) -> bool {
         self.component.build.is_some()
     }
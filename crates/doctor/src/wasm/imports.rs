@@ -0,0 +1,283 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use toml_edit::DocumentMut;
+use wasmparser::{Parser, Payload};
+
+use crate::{manifest::ManifestTreatment, Diagnosis, PatientApp, Treatment};
+
+use super::{PatientWasm, WasmDiagnostic};
+
+/// WasmImportsDiagnostic detects components that import Spin interfaces
+/// that their manifest configuration does not permit them to use.
+#[derive(Default)]
+pub struct WasmImportsDiagnostic;
+
+#[async_trait]
+impl WasmDiagnostic for WasmImportsDiagnostic {
+    type Diagnosis = WasmImportNotPermitted;
+
+    async fn diagnose_wasm(
+        &self,
+        app: &PatientApp,
+        wasm: PatientWasm,
+    ) -> anyhow::Result<Vec<Self::Diagnosis>> {
+        if !crate::manifest::is_v2_manifest(&app.manifest_doc) {
+            // Not applicable until the manifest has been upgraded
+            return Ok(vec![]);
+        }
+        let Some(abs_path) = wasm.abs_source_path() else {
+            return Ok(vec![]);
+        };
+        let Ok(bytes) = std::fs::read(&abs_path) else {
+            // Missing sources are diagnosed separately
+            return Ok(vec![]);
+        };
+
+        let mut diags: Vec<WasmImportNotPermitted> = vec![];
+        for import in import_names(&bytes)? {
+            let Some(requirement) = Requirement::for_import(&import) else {
+                continue;
+            };
+            if requirement.is_met_by(wasm.component())
+                || diags.iter().any(|d| d.requirement == requirement)
+            {
+                continue;
+            }
+            diags.push(WasmImportNotPermitted {
+                component_id: wasm.component_id().to_owned(),
+                import,
+                requirement,
+            });
+        }
+        Ok(diags)
+    }
+}
+
+/// The names of the interfaces imported by a component, or of the modules
+/// imported from by a core module.
+fn import_names(bytes: &[u8]) -> Result<Vec<String>> {
+    let mut names = vec![];
+    for payload in Parser::new(0).parse_all(bytes) {
+        match payload? {
+            Payload::ComponentImportSection(reader) => {
+                for import in reader {
+                    names.push(import?.name.0.to_owned());
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    names.push(import?.module.to_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(names)
+}
+
+/// What the manifest must configure for a component to use an interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    /// An `allowed_outbound_hosts` entry with one of the given schemes
+    OutboundHost(&'static [&'static str]),
+    /// A `key_value_stores` entry
+    KeyValueStore,
+    /// A `sqlite_databases` entry
+    SqliteDatabase,
+    /// An `ai_models` entry
+    AiModel,
+}
+
+impl Requirement {
+    fn for_import(name: &str) -> Option<Self> {
+        let name = name.split('@').next().unwrap_or(name);
+        let (package, interface) = name.rsplit_once('/')?;
+        let (namespace, _) = package.split_once(':')?;
+        match (namespace, interface) {
+            ("wasi", "outgoing-handler") => Some(Self::OutboundHost(&["http", "https"])),
+            ("wasi", "store") if package == "wasi:keyvalue" => Some(Self::KeyValueStore),
+            ("fermyon" | "spin", interface) => match interface {
+                "http" => Some(Self::OutboundHost(&["http", "https"])),
+                "mysql" => Some(Self::OutboundHost(&["mysql"])),
+                "postgres" => Some(Self::OutboundHost(&["postgres"])),
+                "redis" => Some(Self::OutboundHost(&["redis"])),
+                "mqtt" => Some(Self::OutboundHost(&["mqtt"])),
+                "key-value" => Some(Self::KeyValueStore),
+                "sqlite" => Some(Self::SqliteDatabase),
+                "llm" => Some(Self::AiModel),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_met_by(&self, component: &spin_manifest::schema::v2::Component) -> bool {
+        match self {
+            Self::OutboundHost(schemes) => {
+                let allows_scheme = |host: &String| match host.split_once("://") {
+                    // A templated scheme could be anything, so assume the best
+                    Some((scheme, _)) => {
+                        scheme == "*" || scheme.contains("{{") || schemes.contains(&scheme)
+                    }
+                    None => false,
+                };
+                component.allowed_outbound_hosts.iter().any(allows_scheme)
+                    || (schemes.contains(&"http") && !component.allowed_http_hosts.is_empty())
+            }
+            Self::KeyValueStore => !component.key_value_stores.is_empty(),
+            Self::SqliteDatabase => !component.sqlite_databases.is_empty(),
+            Self::AiModel => !component.ai_models.is_empty(),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Self::OutboundHost(schemes) => {
+                let schemes = schemes
+                    .iter()
+                    .map(|s| format!("{s}://"))
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                format!("no allowed_outbound_hosts for {schemes}")
+            }
+            Self::KeyValueStore => "no key_value_stores".into(),
+            Self::SqliteDatabase => "no sqlite_databases".into(),
+            Self::AiModel => "no ai_models".into(),
+        }
+    }
+}
+
+/// WasmImportNotPermitted represents a component importing an interface
+/// that the manifest does not permit it to use.
+#[derive(Debug)]
+pub struct WasmImportNotPermitted {
+    component_id: String,
+    import: String,
+    requirement: Requirement,
+}
+
+impl WasmImportNotPermitted {
+    /// The manifest field and value that would permit the import, if there
+    /// is a value that is reasonable to add without asking.
+    fn fix(&self) -> Option<(&'static str, &'static str)> {
+        match self.requirement {
+            Requirement::KeyValueStore => Some(("key_value_stores", "default")),
+            Requirement::SqliteDatabase => Some(("sqlite_databases", "default")),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnosis for WasmImportNotPermitted {
+    fn description(&self) -> String {
+        format!(
+            "Component {:?} imports {}, but has {}",
+            self.component_id,
+            self.import,
+            self.requirement.description()
+        )
+    }
+
+    fn treatment(&self) -> Option<&dyn Treatment> {
+        self.fix().map(|_| self as &dyn Treatment)
+    }
+}
+
+#[async_trait]
+impl ManifestTreatment for WasmImportNotPermitted {
+    fn summary(&self) -> String {
+        match self.fix() {
+            Some((key, value)) => format!(
+                "Add {value:?} to the {key} of component {:?}",
+                self.component_id
+            ),
+            None => "[invalid treatment]".into(),
+        }
+    }
+
+    async fn treat_manifest(&self, doc: &mut DocumentMut) -> Result<()> {
+        let Some((key, value)) = self.fix() else {
+            bail!("cannot be fixed");
+        };
+        crate::manifest::component_array_mut(doc, &self.component_id, key)?.push(value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test::{assert_single_diagnosis, TestPatient},
+        Diagnostic,
+    };
+
+    use super::*;
+
+    /// A core module importing a function from the given module.
+    fn module_importing(module: &str) -> Vec<u8> {
+        let mut import_section = vec![1, module.len() as u8];
+        import_section.extend(module.as_bytes());
+        import_section.extend([4, b'f', b'u', b'n', b'c', 0x00, 0]);
+
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend([1, 4, 1, 0x60, 0, 0]);
+        bytes.extend([2, import_section.len() as u8]);
+        bytes.extend(import_section);
+        bytes
+    }
+
+    fn patient_importing(module: &str, extra: &str) -> (TestPatient, tempfile::TempDir) {
+        let wasm_dir = tempfile::tempdir().unwrap();
+        let wasm_path = wasm_dir.path().join("app.wasm");
+        std::fs::write(&wasm_path, module_importing(module)).unwrap();
+        let manifest = format!(
+            r#"
+            spin_manifest_version = 2
+            [application]
+            name = "imports-test"
+            [[trigger.http]]
+            route = "/..."
+            component = "app"
+            [component.app]
+            source = {wasm_path:?}
+            {extra}
+            "#
+        );
+        (TestPatient::from_toml_str(manifest), wasm_dir)
+    }
+
+    #[test]
+    fn test_requirements_for_imports() {
+        assert_eq!(
+            Some(Requirement::OutboundHost(&["mysql"])),
+            Requirement::for_import("fermyon:spin/mysql@2.0.0")
+        );
+        assert_eq!(
+            Some(Requirement::KeyValueStore),
+            Requirement::for_import("wasi:keyvalue/store@0.2.0-draft2")
+        );
+        assert_eq!(None, Requirement::for_import("wasi:cli/environment@0.2.0"));
+        assert_eq!(None, Requirement::for_import("wasi_snapshot_preview1"));
+    }
+
+    #[tokio::test]
+    async fn test_key_value_import_without_stores() {
+        let (mut patient, _dir) = patient_importing("fermyon:spin/key-value", "");
+        let diag = assert_single_diagnosis::<WasmImportsDiagnostic>(&patient).await;
+        assert_eq!(Requirement::KeyValueStore, diag.requirement);
+
+        diag.treatment().unwrap().treat(&mut patient).await.unwrap();
+        let diags = WasmImportsDiagnostic.diagnose(&patient).await.unwrap();
+        assert!(diags.is_empty(), "expected no diagnoses, got {diags:?}");
+    }
+
+    #[tokio::test]
+    async fn test_mysql_import_with_allowed_host() {
+        let (patient, _dir) = patient_importing(
+            "fermyon:spin/mysql@2.0.0",
+            r#"allowed_outbound_hosts = ["mysql://db.example.com"]"#,
+        );
+        let diags = WasmImportsDiagnostic.diagnose(&patient).await.unwrap();
+        assert!(diags.is_empty(), "expected no diagnoses, got {diags:?}");
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
 
@@ -10,3 +10,10 @@ pub trait Provider: Debug + Send + Sync {
     /// Returns the value at the given config path, if it exists.
     async fn get(&self, key: &Key) -> anyhow::Result<Option<String>>;
 }
+
+#[async_trait]
+impl<P: Provider + ?Sized> Provider for Arc<P> {
+    async fn get(&self, key: &Key) -> anyhow::Result<Option<String>> {
+        (**self).get(key).await
+    }
+}
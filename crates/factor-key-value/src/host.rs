@@ -107,6 +107,7 @@ impl key_value::Host for KeyValueDispatch {}
 impl key_value::HostStore for KeyValueDispatch {
     #[instrument(name = "spin_key_value.open", skip(self), err(level = Level::INFO), fields(otel.kind = "client", kv.backend=self.manager.summary(&name).unwrap_or("unknown".to_string())))]
     async fn open(&mut self, name: String) -> Result<Result<Resource<key_value::Store>, Error>> {
+        record_op("open");
         Ok(async {
             if self.allowed_stores.contains(&name) {
                 let store = self
@@ -127,6 +128,7 @@ impl key_value::HostStore for KeyValueDispatch {
         store: Resource<key_value::Store>,
         key: String,
     ) -> Result<Result<Option<Vec<u8>>, Error>> {
+        record_op("get");
         let store = self.get_store(store)?;
         Ok(store.get(&key).await)
     }
@@ -138,6 +140,7 @@ impl key_value::HostStore for KeyValueDispatch {
         key: String,
         value: Vec<u8>,
     ) -> Result<Result<(), Error>> {
+        record_op("set");
         let store = self.get_store(store)?;
         Ok(store.set(&key, &value).await)
     }
@@ -148,6 +151,7 @@ impl key_value::HostStore for KeyValueDispatch {
         store: Resource<key_value::Store>,
         key: String,
     ) -> Result<Result<(), Error>> {
+        record_op("delete");
         let store = self.get_store(store)?;
         Ok(store.delete(&key).await)
     }
@@ -158,6 +162,7 @@ impl key_value::HostStore for KeyValueDispatch {
         store: Resource<key_value::Store>,
         key: String,
     ) -> Result<Result<bool, Error>> {
+        record_op("exists");
         let store = self.get_store(store)?;
         Ok(store.exists(&key).await)
     }
@@ -167,6 +172,7 @@ impl key_value::HostStore for KeyValueDispatch {
         &mut self,
         store: Resource<key_value::Store>,
     ) -> Result<Result<Vec<String>, Error>> {
+        record_op("get_keys");
         let store = self.get_store(store)?;
         Ok(store.get_keys().await)
     }
@@ -282,6 +288,7 @@ impl wasi_keyvalue::batch::Host for KeyValueDispatch {
         bucket: Resource<wasi_keyvalue::batch::Bucket>,
         keys: Vec<String>,
     ) -> std::result::Result<Vec<(String, Option<Vec<u8>>)>, wasi_keyvalue::store::Error> {
+        record_op("get_many");
         let store = self.get_store_wasi(bucket)?;
         store
             .get_many(keys.iter().map(|k| k.to_string()).collect())
@@ -295,6 +302,7 @@ impl wasi_keyvalue::batch::Host for KeyValueDispatch {
         bucket: Resource<wasi_keyvalue::batch::Bucket>,
         key_values: Vec<(String, Vec<u8>)>,
     ) -> std::result::Result<(), wasi_keyvalue::store::Error> {
+        record_op("set_many");
         let store = self.get_store_wasi(bucket)?;
         store.set_many(key_values).await.map_err(to_wasi_err)
     }
@@ -305,6 +313,7 @@ impl wasi_keyvalue::batch::Host for KeyValueDispatch {
         bucket: Resource<wasi_keyvalue::batch::Bucket>,
         keys: Vec<String>,
     ) -> std::result::Result<(), wasi_keyvalue::store::Error> {
+        record_op("delete_many");
         let store = self.get_store_wasi(bucket)?;
         store
             .delete_many(keys.iter().map(|k| k.to_string()).collect())
@@ -362,6 +371,7 @@ impl wasi_keyvalue::atomics::Host for KeyValueDispatch {
         key: String,
         delta: i64,
     ) -> Result<i64, wasi_keyvalue::store::Error> {
+        record_op("increment");
         let store = self.get_store_wasi(bucket)?;
         store.increment(key, delta).await.map_err(to_wasi_err)
     }
@@ -372,6 +382,7 @@ impl wasi_keyvalue::atomics::Host for KeyValueDispatch {
         cas_res: Resource<atomics::Cas>,
         value: Vec<u8>,
     ) -> Result<std::result::Result<(), CasError>> {
+        record_op("swap");
         let cas_rep = cas_res.rep();
         let cas = self
             .get_cas(Resource::<Bucket>::new_own(cas_rep))
@@ -469,3 +480,8 @@ impl spin_world::v1::key_value::Host for KeyValueDispatch {
         <Self as key_value::HostStore>::drop(self, this).await
     }
 }
+
+/// Counts an operation for metrics.
+fn record_op(op: &'static str) {
+    spin_telemetry::metrics::monotonic_counter!(spin.key_value_op_count = 1, op = op);
+}
@@ -98,6 +98,12 @@ impl Factor for KeyValueFactor {
             allowed_stores,
         })
     }
+
+    async fn shutdown(&self, app_state: &Self::AppState) -> anyhow::Result<()> {
+        // Writes are made to backing stores in the background; make sure they land
+        app_state.store_manager.flush().await;
+        Ok(())
+    }
 }
 
 type AppStoreManager = CachingStoreManager<DelegatingStoreManager>;
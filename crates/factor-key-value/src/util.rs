@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 use tokio::{
-    sync::Mutex as AsyncMutex,
+    sync::{watch, Mutex as AsyncMutex},
     task::{self, JoinHandle},
 };
 use tracing::Instrument;
@@ -70,9 +70,14 @@ impl StoreManager for DelegatingStoreManager {
 /// may occur asynchronously after the write operation has returned control to the guest, which may result in the
 /// write being lost without the guest knowing.  In the future, a separate `write-durable` function could be added
 /// to key-value.wit to provide either synchronous or asynchronous feedback on durability for guests which need it.
+///
+/// Writes which are still in progress when the app is shut down can be waited for with
+/// [`CachingStoreManager::flush`].
 pub struct CachingStoreManager<T> {
     capacity: NonZeroUsize,
     inner: T,
+    /// The number of writes to backing stores which have not yet completed.
+    pending_writes: Arc<watch::Sender<usize>>,
 }
 
 const DEFAULT_CACHE_SIZE: usize = 256;
@@ -83,7 +88,20 @@ impl<T> CachingStoreManager<T> {
     }
 
     pub fn new_with_capacity(capacity: NonZeroUsize, inner: T) -> Self {
-        Self { capacity, inner }
+        Self {
+            capacity,
+            inner,
+            pending_writes: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    /// Wait for all writes made through this manager's stores to reach the backing stores.
+    ///
+    /// This does not report whether the writes succeeded.
+    pub async fn flush(&self) {
+        let mut pending_writes = self.pending_writes.subscribe();
+        // The sender is owned by `self`, so this can't fail.
+        _ = pending_writes.wait_for(|pending| *pending == 0).await;
     }
 }
 
@@ -95,6 +113,7 @@ impl<T: StoreManager> StoreManager for CachingStoreManager<T> {
             state: Arc::new(AsyncMutex::new(CachingStoreState {
                 cache: LruCache::new(self.capacity),
                 previous_task: None,
+                pending_writes: self.pending_writes.clone(),
             })),
         }))
     }
@@ -111,6 +130,7 @@ impl<T: StoreManager> StoreManager for CachingStoreManager<T> {
 struct CachingStoreState {
     cache: LruCache<String, Option<Vec<u8>>>,
     previous_task: Option<JoinHandle<Result<(), Error>>>,
+    pending_writes: Arc<watch::Sender<usize>>,
 }
 
 impl CachingStoreState {
@@ -118,14 +138,21 @@ impl CachingStoreState {
     /// the result.  This ensures that write order is preserved.
     fn spawn(&mut self, task: impl Future<Output = Result<(), Error>> + Send + 'static) {
         let previous_task = self.previous_task.take();
+        let pending_writes = self.pending_writes.clone();
+        pending_writes.send_modify(|pending| *pending += 1);
         let task = async move {
-            if let Some(previous_task) = previous_task {
-                previous_task
-                    .await
-                    .map_err(|e| Error::Other(format!("{e:?}")))??
+            let result = async {
+                if let Some(previous_task) = previous_task {
+                    previous_task
+                        .await
+                        .map_err(|e| Error::Other(format!("{e:?}")))??
+                }
+
+                task.await
             }
-
-            task.await
+            .await;
+            pending_writes.send_modify(|pending| *pending -= 1);
+            result
         };
         self.previous_task = Some(task::spawn(task.in_current_span()))
     }
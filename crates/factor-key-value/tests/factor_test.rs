@@ -1,10 +1,15 @@
 use anyhow::bail;
 use spin_core::async_trait;
-use spin_factor_key_value::{Cas, KeyValueFactor, RuntimeConfig, Store, StoreManager};
+use spin_factor_key_value::{
+    CachingStoreManager, Cas, KeyValueFactor, RuntimeConfig, Store, StoreManager,
+};
 use spin_factors::RuntimeFactors;
 use spin_factors_test::{toml, TestEnvironment};
 use spin_world::v2::key_value::{Error, HostStore};
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
 
 #[derive(RuntimeFactors)]
 struct TestFactors {
@@ -98,6 +103,23 @@ async fn errors_when_store_is_not_allowed() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn flush_waits_for_background_writes() -> anyhow::Result<()> {
+    let written = Arc::new(Mutex::new(Vec::new()));
+    let manager = CachingStoreManager::new(RecordingStoreManager(written.clone()));
+
+    let store = manager.get("default").await.unwrap();
+    store.set("key", b"value").await.unwrap();
+    store.delete("other-key").await.unwrap();
+    manager.flush().await;
+
+    assert_eq!(
+        *written.lock().unwrap(),
+        ["set key".to_owned(), "delete other-key".to_owned()]
+    );
+    Ok(())
+}
+
 fn mock_store_manager() -> Arc<dyn StoreManager> {
     Arc::new(MockStoreManager)
 }
@@ -173,3 +195,84 @@ impl Store for MockStore {
         todo!()
     }
 }
+
+/// A store manager whose stores record the writes made to them.
+struct RecordingStoreManager(Arc<Mutex<Vec<String>>>);
+
+#[async_trait]
+impl StoreManager for RecordingStoreManager {
+    async fn get(&self, name: &str) -> Result<Arc<dyn Store>, Error> {
+        let _ = name;
+        Ok(Arc::new(RecordingStore(self.0.clone())))
+    }
+
+    fn is_defined(&self, store_name: &str) -> bool {
+        let _ = store_name;
+        true
+    }
+}
+
+struct RecordingStore(Arc<Mutex<Vec<String>>>);
+
+impl RecordingStore {
+    async fn record(&self, write: String) {
+        // Give the caller a chance to run ahead of the write
+        tokio::task::yield_now().await;
+        self.0.lock().unwrap().push(write);
+    }
+}
+
+#[async_trait]
+impl Store for RecordingStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        let _ = key;
+        Ok(None)
+    }
+    async fn set(&self, key: &str, value: &[u8]) -> Result<(), Error> {
+        let _ = value;
+        self.record(format!("set {key}")).await;
+        Ok(())
+    }
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        self.record(format!("delete {key}")).await;
+        Ok(())
+    }
+    async fn exists(&self, key: &str) -> Result<bool, Error> {
+        let _ = key;
+        Ok(false)
+    }
+    async fn get_keys(&self) -> Result<Vec<String>, Error> {
+        Ok(vec![])
+    }
+
+    async fn get_many(
+        &self,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(String, Option<Vec<u8>>)>, Error> {
+        Ok(keys.into_iter().map(|key| (key, None)).collect())
+    }
+
+    async fn set_many(&self, key_values: Vec<(String, Vec<u8>)>) -> anyhow::Result<(), Error> {
+        let _ = key_values;
+        todo!()
+    }
+
+    async fn delete_many(&self, keys: Vec<String>) -> anyhow::Result<(), Error> {
+        let _ = keys;
+        todo!()
+    }
+
+    async fn increment(&self, key: String, delta: i64) -> anyhow::Result<i64, Error> {
+        let (_, _) = (key, delta);
+        todo!()
+    }
+
+    async fn new_compare_and_swap(
+        &self,
+        bucket_rep: u32,
+        key: &str,
+    ) -> anyhow::Result<Arc<dyn Cas>, Error> {
+        let (_, _) = (key, bucket_rep);
+        todo!()
+    }
+}
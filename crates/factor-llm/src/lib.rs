@@ -106,6 +106,7 @@ pub struct InstanceState {
 }
 
 /// The runtime configuration for the LLM factor.
+#[derive(Clone)]
 pub struct RuntimeConfig {
     engine: Arc<Mutex<dyn LlmEngine>>,
 }
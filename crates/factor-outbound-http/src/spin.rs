@@ -16,6 +16,10 @@ impl spin_http::Host for crate::InstanceState {
         fields(otel.kind = "client", url.full = Empty, http.request.method = Empty,
         http.response.status_code = Empty, otel.name = Empty, server.address = Empty, server.port = Empty))]
     async fn send_request(&mut self, req: Request) -> Result<Response, HttpError> {
+        spin_telemetry::metrics::monotonic_counter!(
+            spin.outbound_http_request_count = 1,
+            interface = "spin"
+        );
         let span = Span::current();
         record_request_fields(&span, &req);
 
@@ -87,6 +87,10 @@ impl<'a> WasiHttpView for WasiHttpImplInner<'a> {
         request: Request<wasmtime_wasi_http::body::HyperOutgoingBody>,
         config: wasmtime_wasi_http::types::OutgoingRequestConfig,
     ) -> wasmtime_wasi_http::HttpResult<wasmtime_wasi_http::types::HostFutureIncomingResponse> {
+        spin_telemetry::metrics::monotonic_counter!(
+            spin.outbound_http_request_count = 1,
+            interface = "wasi-http"
+        );
         Ok(HostFutureIncomingResponse::Pending(
             wasmtime_wasi::runtime::spawn(
                 send_request_impl(
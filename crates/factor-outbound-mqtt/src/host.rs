@@ -27,6 +27,20 @@ impl InstanceState {
 #[async_trait]
 pub trait MqttClient: Send + Sync {
     async fn publish_bytes(&self, topic: String, qos: Qos, payload: Vec<u8>) -> Result<(), Error>;
+
+    /// Publishes a message with the given MQTT v5 user properties.
+    ///
+    /// Clients which don't support user properties publish the message without them.
+    async fn publish_bytes_with_properties(
+        &self,
+        topic: String,
+        qos: Qos,
+        payload: Vec<u8>,
+        user_properties: Vec<(String, String)>,
+    ) -> Result<(), Error> {
+        _ = user_properties;
+        self.publish_bytes(topic, qos, payload).await
+    }
 }
 
 impl InstanceState {
@@ -93,6 +107,9 @@ impl v2::HostConnection for InstanceState {
 
     /// Publish a message to the MQTT broker.
     ///
+    /// The current trace context is sent in the MQTT V5 user properties of the message, if the
+    /// connection uses MQTT V5.
+    ///
     /// OTEL trace propagation is not directly supported in MQTT V3. You will need to embed the
     /// current trace context into the payload yourself.
     /// https://w3c.github.io/trace-context-mqtt/#mqtt-v3-recommendation.
@@ -108,7 +125,10 @@ impl v2::HostConnection for InstanceState {
     ) -> Result<(), Error> {
         let conn = self.get_conn(connection).await.map_err(other_error)?;
 
-        conn.publish_bytes(topic, qos, payload).await?;
+        let mut user_properties = vec![];
+        spin_telemetry::inject_trace_context(&mut user_properties);
+        conn.publish_bytes_with_properties(topic, qos, payload, user_properties)
+            .await?;
 
         Ok(())
     }
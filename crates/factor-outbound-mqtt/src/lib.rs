@@ -64,12 +64,25 @@ impl SelfInstanceBuilder for InstanceState {}
 
 // This is a concrete implementation of the MQTT client using rumqttc.
 pub struct NetworkedMqttClient {
-    inner: rumqttc::AsyncClient,
-    event_loop: Mutex<rumqttc::EventLoop>,
+    inner: ClientInner,
+}
+
+enum ClientInner {
+    V3 {
+        client: rumqttc::AsyncClient,
+        event_loop: Mutex<rumqttc::EventLoop>,
+    },
+    V5 {
+        client: rumqttc::v5::AsyncClient,
+        event_loop: Mutex<rumqttc::v5::EventLoop>,
+    },
 }
 
 const MQTT_CHANNEL_CAP: usize = 1000;
 
+/// The address query parameter which selects the MQTT protocol version.
+const PROTOCOL_VERSION_PARAM: &str = "protocol_version";
+
 impl NetworkedMqttClient {
     /// Create a [`ClientCreator`] that creates a [`NetworkedMqttClient`].
     pub fn creator() -> Arc<dyn ClientCreator> {
@@ -84,55 +97,169 @@ impl NetworkedMqttClient {
     }
 
     /// Create a new [`NetworkedMqttClient`] with the given address, username, password, and keep alive interval.
+    ///
+    /// The client uses MQTT v3.1.1 unless the address has a `protocol_version=5` query
+    /// parameter, in which case it uses MQTT v5.
     pub fn create(
         address: String,
         username: String,
         password: String,
         keep_alive_interval: Duration,
     ) -> Result<Self, Error> {
-        let mut conn_opts = rumqttc::MqttOptions::parse_url(address).map_err(|e| {
-            tracing::error!("MQTT URL parse error: {e:?}");
-            Error::InvalidAddress
-        })?;
-        conn_opts.set_credentials(username, password);
-        conn_opts.set_keep_alive(keep_alive_interval);
-        let (client, event_loop) = AsyncClient::new(conn_opts, MQTT_CHANNEL_CAP);
-        Ok(Self {
-            inner: client,
-            event_loop: Mutex::new(event_loop),
-        })
+        let (address, use_v5) = take_protocol_version(&address)?;
+        let inner = if use_v5 {
+            let mut conn_opts =
+                rumqttc::v5::MqttOptions::parse_url(address).map_err(parse_error)?;
+            conn_opts.set_credentials(username, password);
+            conn_opts.set_keep_alive(keep_alive_interval);
+            let (client, event_loop) = rumqttc::v5::AsyncClient::new(conn_opts, MQTT_CHANNEL_CAP);
+            ClientInner::V5 {
+                client,
+                event_loop: Mutex::new(event_loop),
+            }
+        } else {
+            let mut conn_opts = rumqttc::MqttOptions::parse_url(address).map_err(parse_error)?;
+            conn_opts.set_credentials(username, password);
+            conn_opts.set_keep_alive(keep_alive_interval);
+            let (client, event_loop) = AsyncClient::new(conn_opts, MQTT_CHANNEL_CAP);
+            ClientInner::V3 {
+                client,
+                event_loop: Mutex::new(event_loop),
+            }
+        };
+        Ok(Self { inner })
+    }
+}
+
+/// Removes the `protocol_version` query parameter, which rumqttc doesn't recognize, from an
+/// MQTT address, returning the address and whether MQTT v5 was requested.
+fn take_protocol_version(address: &str) -> Result<(String, bool), Error> {
+    let mut url = url::Url::parse(address).map_err(parse_error)?;
+    let mut use_v5 = false;
+    let mut other_params = vec![];
+    for (name, value) in url.query_pairs() {
+        if name == PROTOCOL_VERSION_PARAM {
+            use_v5 = match value.as_ref() {
+                "5" => true,
+                "3" | "4" => false,
+                _ => return Err(Error::InvalidAddress),
+            };
+        } else {
+            other_params.push((name.into_owned(), value.into_owned()));
+        }
+    }
+    if other_params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(other_params);
     }
+    Ok((url.into(), use_v5))
+}
+
+/// Publishes a message with the given user properties to an MQTT v5 client's event loop (not
+/// the MQTT broker).
+async fn queue_publish_v5(
+    client: &rumqttc::v5::AsyncClient,
+    topic: String,
+    qos: rumqttc::v5::mqttbytes::QoS,
+    payload: Vec<u8>,
+    user_properties: Vec<(String, String)>,
+) -> Result<(), Error> {
+    let properties = rumqttc::v5::mqttbytes::v5::PublishProperties {
+        user_properties,
+        ..Default::default()
+    };
+    client
+        .publish_with_properties(topic, qos, false, payload, properties)
+        .await
+        .map_err(other_error)
+}
+
+fn parse_error(e: impl std::fmt::Debug) -> Error {
+    tracing::error!("MQTT URL parse error: {e:?}");
+    Error::InvalidAddress
 }
 
 #[async_trait]
 impl MqttClient for NetworkedMqttClient {
     async fn publish_bytes(&self, topic: String, qos: Qos, payload: Vec<u8>) -> Result<(), Error> {
-        let qos = match qos {
-            Qos::AtMostOnce => rumqttc::QoS::AtMostOnce,
-            Qos::AtLeastOnce => rumqttc::QoS::AtLeastOnce,
-            Qos::ExactlyOnce => rumqttc::QoS::ExactlyOnce,
-        };
-        // Message published to EventLoop (not MQTT Broker)
-        self.inner
-            .publish_bytes(topic, qos, false, payload.into())
+        self.publish_bytes_with_properties(topic, qos, payload, vec![])
             .await
-            .map_err(other_error)?;
-
-        // Poll event loop until outgoing publish event is iterated over to send the message to MQTT broker or capture/throw error.
-        // We may revisit this later to manage long running connections, high throughput use cases and their issues in the connection pool.
-        let mut lock = self.event_loop.lock().await;
-        loop {
-            let event = lock
-                .poll()
-                .await
-                .map_err(|err| v2::Error::ConnectionFailed(err.to_string()))?;
-
-            match (qos, event) {
-                (QoS::AtMostOnce, Event::Outgoing(Outgoing::Publish(_)))
-                | (QoS::AtLeastOnce, Event::Incoming(Incoming::PubAck(_)))
-                | (QoS::ExactlyOnce, Event::Incoming(Incoming::PubComp(_))) => break,
-
-                (_, _) => continue,
+    }
+
+    async fn publish_bytes_with_properties(
+        &self,
+        topic: String,
+        qos: Qos,
+        payload: Vec<u8>,
+        user_properties: Vec<(String, String)>,
+    ) -> Result<(), Error> {
+        match &self.inner {
+            ClientInner::V3 { client, event_loop } => {
+                // MQTT v3 messages have no user properties
+                _ = user_properties;
+                let qos = match qos {
+                    Qos::AtMostOnce => QoS::AtMostOnce,
+                    Qos::AtLeastOnce => QoS::AtLeastOnce,
+                    Qos::ExactlyOnce => QoS::ExactlyOnce,
+                };
+
+                // Message published to EventLoop (not MQTT Broker)
+                client
+                    .publish_bytes(topic, qos, false, payload.into())
+                    .await
+                    .map_err(other_error)?;
+
+                // Poll event loop until outgoing publish event is iterated over to send the message to MQTT broker or capture/throw error.
+                // We may revisit this later to manage long running connections, high throughput use cases and their issues in the connection pool.
+                let mut lock = event_loop.lock().await;
+                loop {
+                    let event = lock
+                        .poll()
+                        .await
+                        .map_err(|err| v2::Error::ConnectionFailed(err.to_string()))?;
+
+                    match (qos, event) {
+                        (QoS::AtMostOnce, Event::Outgoing(Outgoing::Publish(_)))
+                        | (QoS::AtLeastOnce, Event::Incoming(Incoming::PubAck(_)))
+                        | (QoS::ExactlyOnce, Event::Incoming(Incoming::PubComp(_))) => break,
+
+                        (_, _) => continue,
+                    }
+                }
+            }
+            ClientInner::V5 { client, event_loop } => {
+                use rumqttc::v5::mqttbytes::QoS as QoS5;
+
+                let qos = match qos {
+                    Qos::AtMostOnce => QoS5::AtMostOnce,
+                    Qos::AtLeastOnce => QoS5::AtLeastOnce,
+                    Qos::ExactlyOnce => QoS5::ExactlyOnce,
+                };
+                queue_publish_v5(client, topic, qos, payload, user_properties).await?;
+
+                // As for MQTT v3, poll until the message has been sent to the broker
+                let mut lock = event_loop.lock().await;
+                loop {
+                    let event = lock
+                        .poll()
+                        .await
+                        .map_err(|err| v2::Error::ConnectionFailed(err.to_string()))?;
+
+                    match (qos, event) {
+                        (QoS5::AtMostOnce, rumqttc::v5::Event::Outgoing(Outgoing::Publish(..)))
+                        | (
+                            QoS5::AtLeastOnce,
+                            rumqttc::v5::Event::Incoming(rumqttc::v5::Incoming::PubAck(..)),
+                        )
+                        | (
+                            QoS5::ExactlyOnce,
+                            rumqttc::v5::Event::Incoming(rumqttc::v5::Incoming::PubComp(..)),
+                        ) => break,
+
+                        (_, _) => continue,
+                    }
+                }
             }
         }
         Ok(())
@@ -165,3 +292,47 @@ where
         self(address, username, password, keep_alive_interval)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_version_is_taken_from_address() {
+        let (address, use_v5) =
+            take_protocol_version("mqtt://broker:1883?protocol_version=5&client_id=spin").unwrap();
+        assert_eq!(address, "mqtt://broker:1883?client_id=spin");
+        assert!(use_v5);
+
+        let (address, use_v5) = take_protocol_version("mqtt://broker:1883").unwrap();
+        assert_eq!(address, "mqtt://broker:1883");
+        assert!(!use_v5);
+
+        assert!(take_protocol_version("mqtt://broker:1883?protocol_version=6").is_err());
+    }
+
+    #[tokio::test]
+    async fn v5_publishes_carry_user_properties() {
+        let (requests_tx, requests_rx) = flume::bounded(1);
+        let client = rumqttc::v5::AsyncClient::from_senders(requests_tx);
+        let traceparent = (
+            "traceparent".to_owned(),
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_owned(),
+        );
+
+        queue_publish_v5(
+            &client,
+            "topic".to_owned(),
+            rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+            b"hello".to_vec(),
+            vec![traceparent.clone()],
+        )
+        .await
+        .unwrap();
+
+        let rumqttc::v5::Request::Publish(publish) = requests_rx.try_recv().unwrap() else {
+            panic!("expected a publish request");
+        };
+        assert_eq!(publish.properties.unwrap().user_properties, [traceparent]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use anyhow::{bail, Result};
@@ -36,6 +36,47 @@ impl ClientCreator for MockMqttClient {
     }
 }
 
+/// Records the user properties of published messages.
+#[derive(Clone, Default)]
+pub struct RecordingMqttClient {
+    user_properties: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+#[async_trait]
+impl MqttClient for RecordingMqttClient {
+    async fn publish_bytes(
+        &self,
+        _topic: String,
+        _qos: Qos,
+        _payload: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn publish_bytes_with_properties(
+        &self,
+        _topic: String,
+        _qos: Qos,
+        _payload: Vec<u8>,
+        user_properties: Vec<(String, String)>,
+    ) -> Result<(), Error> {
+        self.user_properties.lock().unwrap().extend(user_properties);
+        Ok(())
+    }
+}
+
+impl ClientCreator for RecordingMqttClient {
+    fn create(
+        &self,
+        _address: String,
+        _username: String,
+        _password: String,
+        _keep_alive_interval: Duration,
+    ) -> Result<Arc<dyn MqttClient>, Error> {
+        Ok(Arc::new(self.clone()))
+    }
+}
+
 #[derive(RuntimeFactors)]
 struct TestFactors {
     variables: VariablesFactor,
@@ -130,3 +171,67 @@ async fn exercise_publish() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn publish_sends_trace_context_in_user_properties() -> anyhow::Result<()> {
+    use opentelemetry::trace::{TraceContextExt, TracerProvider};
+    use tracing::Instrument;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    opentelemetry::global::set_text_map_propagator(
+        opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+    );
+    let tracer_provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer("test")));
+    let _subscriber = tracing::subscriber::set_default(subscriber);
+
+    let client = RecordingMqttClient::default();
+    let factors = TestFactors {
+        variables: VariablesFactor::default(),
+        networking: OutboundNetworkingFactor::new(),
+        mqtt: OutboundMqttFactor::new(Arc::new(client.clone())),
+    };
+    let env = TestEnvironment::new(factors).extend_manifest(toml! {
+        [component.test-component]
+        source = "does-not-exist.wasm"
+        allowed_outbound_hosts = ["mqtt://*:*"]
+    });
+    let mut state = env.build_instance_state().await?;
+
+    let trace_id = async {
+        let conn = state
+            .mqtt
+            .open(
+                "mqtt://mqtt.test:1883?protocol_version=5".to_string(),
+                "username".to_string(),
+                "password".to_string(),
+                1,
+            )
+            .await?;
+        state
+            .mqtt
+            .publish(
+                conn,
+                "message".to_string(),
+                b"test message".to_vec(),
+                Qos::AtLeastOnce,
+            )
+            .await?;
+        let context = tracing::Span::current().context();
+        anyhow::Ok(context.span().span_context().trace_id())
+    }
+    .instrument(tracing::info_span!("publish"))
+    .await?;
+
+    let user_properties = client.user_properties.lock().unwrap();
+    let Some((_, traceparent)) = user_properties
+        .iter()
+        .find(|(name, _)| name == "traceparent")
+    else {
+        bail!("expected a traceparent user property, got {user_properties:?}");
+    };
+    assert!(traceparent.contains(&trace_id.to_string()));
+    Ok(())
+}
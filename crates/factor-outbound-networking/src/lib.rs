@@ -16,8 +16,10 @@ use std::{collections::HashMap, sync::Arc};
 
 pub use config::{
     allowed_outbound_hosts, is_service_chaining_host, parse_service_chaining_target,
-    validate_service_chaining_for_components, AllowedHostConfig, AllowedHostsConfig, HostConfig,
-    OutboundUrl, SERVICE_CHAINING_DOMAIN_SUFFIX,
+    validate_service_chaining_for_components, SERVICE_CHAINING_DOMAIN_SUFFIX,
+};
+pub use spin_outbound_networking_config::allowed_hosts::{
+    AllowedHostConfig, AllowedHostsConfig, HostConfig, OutboundUrl,
 };
 
 pub use runtime_config::ComponentTlsConfigs;
@@ -8,7 +8,7 @@ use rustls::{ClientConfig, RootCertStore};
 use rustls_pki_types::{CertificateDer, PrivateKeyDer};
 
 /// Runtime configuration for outbound networking.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RuntimeConfig {
     /// Maps component ID -> HostClientConfigs
     component_host_client_configs: HashMap<String, HostClientConfigs>,
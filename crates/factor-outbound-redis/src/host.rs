@@ -77,6 +77,7 @@ impl v2::HostConnection for crate::InstanceState {
         payload: Vec<u8>,
     ) -> Result<(), Error> {
         let conn = self.get_conn(connection).await.map_err(other_error)?;
+        let payload = spin_telemetry::wrap_payload_with_trace_context(payload);
         // The `let () =` syntax is needed to suppress a warning when the result type is inferred.
         // You can read more about the issue here: <https://github.com/redis-rs/redis-rs/issues/1228>
         let () = conn
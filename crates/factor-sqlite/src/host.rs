@@ -64,6 +64,7 @@ impl v2::Host for InstanceState {
 impl v2::HostConnection for InstanceState {
     #[instrument(name = "spin_sqlite.open", skip(self), err(level = Level::INFO), fields(otel.kind = "client", db.system = "sqlite", sqlite.backend = Empty))]
     async fn open(&mut self, database: String) -> Result<Resource<v2::Connection>, v2::Error> {
+        record_op("open");
         if !self.allowed_databases.contains(&database) {
             return Err(v2::Error::AccessDenied);
         }
@@ -83,13 +84,14 @@ impl v2::HostConnection for InstanceState {
             .map(Resource::new_own)
     }
 
-    #[instrument(name = "spin_sqlite.execute", skip(self, connection, parameters), err(level = Level::INFO), fields(otel.kind = "client", db.system = "sqlite", otel.name = query, sqlite.backend = Empty))]
+    #[instrument(name = "spin_sqlite.execute", skip(self, connection, query, parameters), err(level = Level::INFO), fields(otel.kind = "client", db.system = "sqlite", db.query.text = query, sqlite.backend = Empty))]
     async fn execute(
         &mut self,
         connection: Resource<v2::Connection>,
         query: String,
         parameters: Vec<v2::Value>,
     ) -> Result<v2::QueryResult, v2::Error> {
+        record_op("execute");
         let conn = match self.get_connection(connection) {
             Ok(c) => c,
             Err(err) => return Err(err),
@@ -182,3 +184,8 @@ fn from_legacy_value(value: v1::Value) -> v2::Value {
         v1::Value::Null => v2::Value::Null,
     }
 }
+
+/// Counts an operation for metrics.
+fn record_op(op: &'static str) {
+    spin_telemetry::metrics::monotonic_counter!(spin.sqlite_op_count = 1, op = op);
+}
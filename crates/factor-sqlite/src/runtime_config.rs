@@ -5,7 +5,7 @@ use crate::ConnectionCreator;
 /// A runtime configuration for SQLite databases.
 ///
 /// Maps database labels to connection creators.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct RuntimeConfig {
     pub connection_creators: HashMap<String, Arc<dyn ConnectionCreator>>,
 }
@@ -1,15 +1,22 @@
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
+use opentelemetry::trace::{TraceResult, TracerProvider as _};
+use opentelemetry_sdk::{
+    export::trace::SpanData,
+    trace::{Span, SpanProcessor},
+};
 use spin_factor_sqlite::{RuntimeConfig, SqliteFactor};
 use spin_factors::{
     anyhow::{self, bail, Context as _},
     RuntimeFactors,
 };
 use spin_factors_test::{toml, TestEnvironment};
+use spin_telemetry::redaction::{RedactingSpanProcessor, Redactions};
 use spin_world::{async_trait, v2::sqlite as v2};
+use tracing_subscriber::layer::SubscriberExt as _;
 use v2::HostConnection as _;
 
 #[derive(RuntimeFactors)]
@@ -63,6 +70,72 @@ async fn errors_when_database_not_allowed() -> anyhow::Result<()> {
 
 #[tokio::test]
 async fn it_works_when_database_is_configured() -> anyhow::Result<()> {
+    let mut state = mock_database_env()?
+        .build_instance_state()
+        .await
+        .context("build_instance_state failed")?;
+
+    assert_eq!(
+        state.sqlite.allowed_databases(),
+        &["foo".into()].into_iter().collect::<HashSet<_>>()
+    );
+
+    assert!(state.sqlite.open("foo".into()).await.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn execute_span_statement_can_be_redacted() -> anyhow::Result<()> {
+    let spans = CollectingSpanProcessor::default();
+    let tracer_provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_span_processor(RedactingSpanProcessor::new(
+            spans.clone(),
+            Redactions {
+                attributes: vec!["db.query.text".to_owned()],
+                ..Default::default()
+            },
+        ))
+        .build();
+    let collector = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer("test")));
+    let _collector = tracing::subscriber::set_default(collector);
+
+    let statement = "SELECT * FROM users WHERE email = 'me@example.com'";
+    let mut state = mock_database_env()?
+        .build_instance_state()
+        .await
+        .context("build_instance_state failed")?;
+    let connection = match state.sqlite.open("foo".into()).await {
+        Ok(connection) => connection,
+        Err(e) => bail!("failed to open database: {e:?}"),
+    };
+    // The mock connection fails every query, but the span is still recorded
+    assert!(state
+        .sqlite
+        .execute(connection, statement.into(), vec![])
+        .await
+        .is_err());
+
+    let spans = spans.0.lock().unwrap();
+    let span = spans
+        .iter()
+        .find(|span| span.name == "spin_sqlite.execute")
+        .context("no execute span")?;
+    let query_text = span
+        .attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == "db.query.text")
+        .context("no db.query.text attribute")?;
+    assert_eq!(query_text.value.as_str(), "[REDACTED]");
+    assert!(!span
+        .attributes
+        .iter()
+        .any(|kv| kv.value.as_str().contains("me@example.com")));
+    Ok(())
+}
+
+/// A test environment with a database "foo" backed by a [`MockConnection`].
+fn mock_database_env() -> anyhow::Result<TestEnvironment<TestFactors>> {
     let factors = TestFactors {
         sqlite: SqliteFactor::new(),
     };
@@ -73,26 +146,33 @@ async fn it_works_when_database_is_configured() -> anyhow::Result<()> {
             connection_creators,
         }),
     };
-    let env = TestEnvironment::new(factors)
+    TestEnvironment::new(factors)
         .extend_manifest(toml! {
             [component.test-component]
             source = "does-not-exist.wasm"
             sqlite_databases = ["foo"]
         })
-        .runtime_config(runtime_config)?;
+        .runtime_config(runtime_config)
+}
 
-    let mut state = env
-        .build_instance_state()
-        .await
-        .context("build_instance_state failed")?;
+/// Collects ended spans.
+#[derive(Clone, Debug, Default)]
+struct CollectingSpanProcessor(Arc<Mutex<Vec<SpanData>>>);
 
-    assert_eq!(
-        state.sqlite.allowed_databases(),
-        &["foo".into()].into_iter().collect::<HashSet<_>>()
-    );
+impl SpanProcessor for CollectingSpanProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &opentelemetry::Context) {}
 
-    assert!(state.sqlite.open("foo".into()).await.is_ok());
-    Ok(())
+    fn on_end(&self, span: SpanData) {
+        self.0.lock().unwrap().push(span);
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        Ok(())
+    }
 }
 
 /// A connection creator that returns a mock connection.
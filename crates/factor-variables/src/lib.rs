@@ -52,7 +52,7 @@ impl Factor for VariablesFactor {
 
         let providers = ctx.take_runtime_config().unwrap_or_default();
         for provider in providers {
-            expression_resolver.add_provider(provider);
+            expression_resolver.add_provider(Box::new(provider));
         }
 
         Ok(AppState {
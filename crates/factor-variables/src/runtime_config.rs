@@ -1,14 +1,16 @@
+use std::sync::Arc;
+
 use spin_expressions::Provider;
 
 /// The runtime configuration for the variables factor.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct RuntimeConfig {
-    pub providers: Vec<Box<dyn Provider>>,
+    pub providers: Vec<Arc<dyn Provider>>,
 }
 
 impl IntoIterator for RuntimeConfig {
-    type Item = Box<dyn Provider>;
-    type IntoIter = std::vec::IntoIter<Box<dyn Provider>>;
+    type Item = Arc<dyn Provider>;
+    type IntoIter = std::vec::IntoIter<Arc<dyn Provider>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.providers.into_iter()
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use spin_expressions::{Key, Provider};
 use spin_factor_variables::{runtime_config::RuntimeConfig, VariablesFactor};
 use spin_factors::{anyhow, RuntimeFactors};
@@ -14,7 +16,7 @@ async fn provider_works() -> anyhow::Result<()> {
     let factors = TestFactors {
         variables: VariablesFactor::default(),
     };
-    let providers = vec![Box::new(MockProvider) as _];
+    let providers = vec![Arc::new(MockProvider) as _];
     let runtime_config = TestFactorsRuntimeConfig {
         variables: Some(RuntimeConfig { providers }),
     };
@@ -57,6 +57,9 @@ fn expand_factors(input: &DeriveInput) -> syn::Result<TokenStream> {
         );
         factor_types.push(&field.ty);
     }
+    // Factors are shut down in reverse order, after any factors that depend on them
+    let shutdown_factor_names = factor_names.iter().rev().collect::<Vec<_>>();
+    let shutdown_factor_types = factor_types.iter().rev().collect::<Vec<_>>();
 
     let Any = quote!(::std::any::Any);
     let Send = quote!(::std::marker::Send);
@@ -171,6 +174,28 @@ fn expand_factors(input: &DeriveInput) -> syn::Result<TokenStream> {
                 })
             }
 
+            fn shutdown(
+                &self,
+                configured_app: &#ConfiguredApp<Self>,
+            ) -> impl ::std::future::Future<Output = #Result<()>> + #Send {
+                async move {
+                    #[allow(unused_mut)]
+                    let mut result = Ok(());
+                    #(
+                        let shutdown = #Factor::shutdown(
+                            &self.#shutdown_factor_names,
+                            configured_app.app_state::<#shutdown_factor_types>().unwrap(),
+                        ).await;
+                        if let Err(err) = shutdown {
+                            if result.is_ok() {
+                                result = Err(#Error::factor_shutdown_error::<#shutdown_factor_types>(err));
+                            }
+                        }
+                    )*
+                    result
+                }
+            }
+
             fn app_state<F: #Factor>(app_state: &Self::AppState) -> Option<&F::AppState> {
                 #(
                     if let Some(state) = &app_state.#factor_names {
@@ -98,6 +98,30 @@ where
         let _ = builder;
         Ok(())
     }
+
+    /// After instantiate hooks run immediately after
+    /// [`FactorsInstanceBuilder::instantiate`] instantiates a component.
+    fn after_instantiate(
+        &self,
+        component_id: &str,
+        instance_state: &mut InstanceState<T::InstanceState, U>,
+    ) -> anyhow::Result<()> {
+        let _ = (component_id, instance_state);
+        Ok(())
+    }
+
+    /// After execute hooks run when a trigger has finished executing an
+    /// instance, with the outcome of the execution. See
+    /// [`InstanceHooks::after_execute`].
+    fn after_execute(
+        &self,
+        component_id: &str,
+        instance_state: &mut InstanceState<T::InstanceState, U>,
+        outcome: Result<(), &anyhow::Error>,
+    ) -> anyhow::Result<()> {
+        let _ = (component_id, instance_state, outcome);
+        Ok(())
+    }
 }
 
 /// A ComponentLoader is responsible for loading Wasmtime [`Component`]s.
@@ -168,7 +192,7 @@ impl<T: RuntimeFactors, U: Send + 'static> FactorsExecutorApp<T, U> {
             factor_builders,
             instance_pre,
             app_component,
-            factors: &self.executor.factors,
+            executor: &self.executor,
         };
 
         for hooks in &self.executor.hooks {
@@ -177,6 +201,18 @@ impl<T: RuntimeFactors, U: Send + 'static> FactorsExecutorApp<T, U> {
 
         Ok(builder)
     }
+
+    /// Shuts down the app's factors, e.g. flushing writes buffered by them.
+    ///
+    /// This should be called once, after no further instances of the app
+    /// will be prepared and all instances in flight have finished.
+    pub async fn shutdown(&self) -> anyhow::Result<()> {
+        self.executor
+            .factors
+            .shutdown(&self.configured_app)
+            .await
+            .context("failed to shut down app")
+    }
 }
 
 /// A FactorsInstanceBuilder manages the instantiation of a Spin component instance.
@@ -188,7 +224,7 @@ pub struct FactorsInstanceBuilder<'a, F: RuntimeFactors, U> {
     store_builder: spin_core::StoreBuilder,
     factor_builders: F::InstanceBuilders,
     instance_pre: &'a InstancePre<F, U>,
-    factors: &'a F,
+    executor: &'a Arc<FactorsExecutor<F, U>>,
 }
 
 impl<'a, T: RuntimeFactors, U> FactorsInstanceBuilder<'a, T, U> {
@@ -221,6 +257,15 @@ impl<'a, T: RuntimeFactors, U> FactorsInstanceBuilder<'a, T, U> {
     pub fn component(&self) -> &Component {
         self.instance_pre.component()
     }
+
+    /// Returns the [`InstanceHooks`] for the instance, which triggers use to
+    /// run hooks once the instance has finished executing.
+    pub fn instance_hooks(&self) -> InstanceHooks<T, U> {
+        InstanceHooks {
+            executor: self.executor.clone(),
+            component_id: self.app_component.id().to_string(),
+        }
+    }
 }
 
 impl<'a, T: RuntimeFactors, U: Send> FactorsInstanceBuilder<'a, T, U> {
@@ -234,15 +279,54 @@ impl<'a, T: RuntimeFactors, U: Send> FactorsInstanceBuilder<'a, T, U> {
     )> {
         let instance_state = InstanceState {
             core: Default::default(),
-            factors: self.factors.build_instance_state(self.factor_builders)?,
+            factors: self
+                .executor
+                .factors
+                .build_instance_state(self.factor_builders)?,
             executor: executor_instance_state,
         };
         let mut store = self.store_builder.build(instance_state)?;
         let instance = self.instance_pre.instantiate_async(&mut store).await?;
+
+        for hooks in &self.executor.hooks {
+            hooks.after_instantiate(self.app_component.id(), store.data_mut())?;
+        }
+
         Ok((instance, store))
     }
 }
 
+/// InstanceHooks runs the [`ExecutorHooks`] that apply once an instance has
+/// been executed.
+///
+/// Obtain one from [`FactorsInstanceBuilder::instance_hooks`] before
+/// instantiating. It is independent of the builder, so it can be moved along
+/// with the instance's store, e.g. into a task.
+pub struct InstanceHooks<T: RuntimeFactors, U> {
+    executor: Arc<FactorsExecutor<T, U>>,
+    component_id: String,
+}
+
+impl<T: RuntimeFactors, U: Send + 'static> InstanceHooks<T, U> {
+    /// Runs the [`ExecutorHooks::after_execute`] hooks with the outcome of
+    /// executing the instance. Errors from hooks are logged; they don't
+    /// change the outcome.
+    pub fn after_execute(
+        &self,
+        store: &mut spin_core::Store<InstanceState<T::InstanceState, U>>,
+        outcome: Result<(), &anyhow::Error>,
+    ) {
+        for hooks in &self.executor.hooks {
+            if let Err(err) = hooks.after_execute(&self.component_id, store.data_mut(), outcome) {
+                tracing::warn!(
+                    "After execute hook failed for component {:?}: {err:?}",
+                    self.component_id
+                );
+            }
+        }
+    }
+}
+
 /// InstanceState is the [`spin_core::Store`] `data` for an instance.
 ///
 /// It is generic over the [`RuntimeFactors::InstanceState`] and any ad-hoc
@@ -341,6 +425,70 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn instance_lifecycle_hooks_run() -> anyhow::Result<()> {
+        let factors = TestFactors {
+            wasi: WasiFactor::new(DummyFilesMounter),
+        };
+        let env = TestEnvironment::new(factors);
+        let locked = env.build_locked_app().await?;
+        let app = App::new("test-app", locked);
+
+        let engine_builder = spin_core::Engine::builder(&Default::default())?;
+        let mut executor = FactorsExecutor::new(engine_builder, env.factors)?;
+        let hooks = LifecycleHooks::default();
+        executor.add_hooks(hooks.clone());
+        let executor = Arc::new(executor);
+
+        let factors_app = executor
+            .load_app(app, Default::default(), &DummyComponentLoader)
+            .await?;
+
+        let instance_builder = factors_app.prepare("empty")?;
+        let instance_hooks = instance_builder.instance_hooks();
+        let (_instance, mut store) = instance_builder.instantiate(()).await?;
+        instance_hooks.after_execute(&mut store, Err(&anyhow::anyhow!("oops")));
+
+        assert_eq!(
+            *hooks.events.lock().unwrap(),
+            ["instantiated empty", "executed empty: failure"]
+        );
+        Ok(())
+    }
+
+    #[derive(Clone, Default)]
+    struct LifecycleHooks {
+        events: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl<T: RuntimeFactors, U> ExecutorHooks<T, U> for LifecycleHooks {
+        fn after_instantiate(
+            &self,
+            component_id: &str,
+            _instance_state: &mut InstanceState<T::InstanceState, U>,
+        ) -> anyhow::Result<()> {
+            let event = format!("instantiated {component_id}");
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+
+        fn after_execute(
+            &self,
+            component_id: &str,
+            _instance_state: &mut InstanceState<T::InstanceState, U>,
+            outcome: Result<(), &anyhow::Error>,
+        ) -> anyhow::Result<()> {
+            let outcome = if outcome.is_ok() {
+                "success"
+            } else {
+                "failure"
+            };
+            let event = format!("executed {component_id}: {outcome}");
+            self.events.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
     struct DummyComponentLoader;
 
     #[async_trait]
@@ -1,4 +1,4 @@
-use std::any::Any;
+use std::{any::Any, future::Future};
 
 use wasmtime::component::{Linker, ResourceTable};
 
@@ -64,6 +64,22 @@ pub trait Factor: Any + Sized {
         &self,
         ctx: PrepareContext<T, Self>,
     ) -> anyhow::Result<Self::InstanceBuilder>;
+
+    /// Shuts down this factor's state for an app that is no longer being
+    /// served.
+    ///
+    /// This is called once, after the runtime has stopped creating instances
+    /// of the app and instances already in flight have finished. It is the
+    /// place to flush buffered writes and close connections that would
+    /// otherwise simply be dropped. A runtime may give up waiting on this
+    /// after a deadline, so it should not wait indefinitely.
+    fn shutdown(
+        &self,
+        app_state: &Self::AppState,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        _ = app_state;
+        async { Ok(()) }
+    }
 }
 
 /// The instance state of the given [`Factor`] `F`.
@@ -48,6 +48,11 @@ pub enum Error {
         factor: &'static str,
         source: anyhow::Error,
     },
+    #[error("{factor}::shutdown failed: {source}")]
+    FactorShutdownError {
+        factor: &'static str,
+        source: anyhow::Error,
+    },
     #[error("no such factor: {0}")]
     NoSuchFactor(&'static str),
     #[error("{factor} requested already-consumed key {key:?}")]
@@ -97,4 +102,10 @@ impl Error {
         let factor = std::any::type_name::<T>();
         Self::FactorBuildError { factor, source }
     }
+
+    #[doc(hidden)]
+    pub fn factor_shutdown_error<T: Factor>(source: anyhow::Error) -> Self {
+        let factor = std::any::type_name::<T>();
+        Self::FactorShutdownError { factor, source }
+    }
 }
@@ -1,3 +1,5 @@
+use std::future::Future;
+
 use wasmtime::component::{Linker, ResourceTable};
 
 use crate::{factor::FactorInstanceState, App, ConfiguredApp, Factor};
@@ -29,6 +31,9 @@ use crate::{factor::FactorInstanceState, App, ConfiguredApp, Factor};
 /// let mut store = wasmtime::Store::new(&engine, data);
 /// // Instantiate the component
 /// let instance = linker.instantiate_async(&mut store, &component).await?;
+/// // ...
+/// // Shut down the factors once the app is no longer being served
+/// factors.shutdown(&configured_app).await?;
 /// ```
 pub trait RuntimeFactors: Send + Sync + Sized + 'static {
     /// The per application state of all the factors.
@@ -69,6 +74,17 @@ pub trait RuntimeFactors: Send + Sync + Sized + 'static {
         builders: Self::InstanceBuilders,
     ) -> crate::Result<Self::InstanceState>;
 
+    /// Shut down the factors' state for the given configured app.
+    ///
+    /// Each factor's `shutdown` is called in the reverse of the order they
+    /// were configured in, so that factors are shut down before any factors
+    /// they depend on. All factors are shut down even if some fail; the first
+    /// error is returned.
+    fn shutdown(
+        &self,
+        configured_app: &ConfiguredApp<Self>,
+    ) -> impl Future<Output = crate::Result<()>> + Send;
+
     /// Get the app state related to a particular factor.
     fn app_state<F: Factor>(app_state: &Self::AppState) -> Option<&F::AppState>;
 
@@ -11,6 +11,9 @@ pub struct HttpTriggerConfig {
     /// The HTTP executor the component requires
     #[serde(default)]
     pub executor: Option<HttpExecutorType>,
+    /// Instantiate the component ahead of requests, and optionally reuse instances
+    #[serde(default)]
+    pub instance_pool: Option<InstancePoolConfig>,
 }
 
 /// An HTTP trigger route
@@ -41,6 +44,35 @@ impl<T: Into<String>> From<T> for HttpTriggerRouteConfig {
     }
 }
 
+/// Pre-instantiation and reuse of instances of an HTTP component.
+///
+/// This takes instantiation, and any initialization the guest does when
+/// instantiated, out of the request path. It is intended for components with
+/// expensive startup, such as those embedding a language runtime.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstancePoolConfig {
+    /// The number of instances to keep ready ahead of requests.
+    pub size: usize,
+    /// Whether an instance may go on to handle further requests, one at a
+    /// time, after successfully handling one. Only set this for components
+    /// which don't rely on starting each request from fresh state.
+    pub reusable: bool,
+    /// The maximum number of requests a reusable instance handles before it
+    /// is discarded.
+    pub max_uses: usize,
+}
+
+impl Default for InstancePoolConfig {
+    fn default() -> Self {
+        Self {
+            size: 2,
+            reusable: false,
+            max_uses: 1000,
+        }
+    }
+}
+
 /// The executor for the HTTP component.
 /// The component can either implement the Spin HTTP interface,
 /// the `wasi-http` interface, or the Wagi CGI interface.
@@ -105,4 +137,19 @@ mod tests {
         assert_eq!(config.entrypoint, "_start");
         assert_eq!(config.argv, "${SCRIPT_NAME} ${ARGS}");
     }
+
+    #[test]
+    fn instance_pool_config_defaults() {
+        let config: HttpTriggerConfig = toml::toml! {
+            component = "js"
+            route = "/..."
+            instance_pool = { reusable = true }
+        }
+        .try_into()
+        .unwrap();
+        let pool = config.instance_pool.expect("should have instance pool");
+        assert_eq!(pool.size, 2);
+        assert!(pool.reusable);
+        assert_eq!(pool.max_uses, 1000);
+    }
 }
@@ -40,6 +40,22 @@ pub async fn from_file(
     loader.load_file(path).await
 }
 
+/// Load a Spin locked app from a spin.toml manifest file, as [`from_file`],
+/// but with the component sources selected by the named build profile.
+pub async fn from_file_with_build_profile(
+    manifest_path: impl AsRef<Path>,
+    profile: &str,
+    files_mount_strategy: FilesMountStrategy,
+    cache_root: Option<PathBuf>,
+) -> Result<LockedApp> {
+    let path = manifest_path.as_ref();
+    let app_root = parent_dir(path).context("manifest path has no parent directory")?;
+    let loader = LocalLoader::new(&app_root, files_mount_strategy, cache_root)
+        .await?
+        .with_build_profile(profile);
+    loader.load_file(path).await
+}
+
 /// Load a Spin locked app from a standalone Wasm file.
 pub async fn from_wasm_file(wasm_path: impl AsRef<Path>) -> Result<LockedApp> {
     let app_root = std::env::current_dir()?;
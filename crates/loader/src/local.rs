@@ -25,6 +25,7 @@ pub struct LocalLoader {
     files_mount_strategy: FilesMountStrategy,
     cache: Cache,
     file_loading_permits: Semaphore,
+    build_profile: Option<String>,
 }
 
 impl LocalLoader {
@@ -41,9 +42,16 @@ impl LocalLoader {
             cache: Cache::new(cache_root).await?,
             // Limit concurrency to avoid hitting system resource limits
             file_loading_permits: Semaphore::new(crate::MAX_FILE_LOADING_CONCURRENCY),
+            build_profile: None,
         })
     }
 
+    // Use the component sources selected by the named build profile.
+    pub fn with_build_profile(mut self, profile: impl Into<String>) -> Self {
+        self.build_profile = Some(profile.into());
+        self
+    }
+
     // Load the manifest file (spin.toml) at the given path into a LockedApp,
     // preparing all its content for execution.
     pub async fn load_file(&self, path: impl AsRef<Path>) -> Result<LockedApp> {
@@ -71,6 +79,9 @@ impl LocalLoader {
     // Load the given manifest into a LockedApp, ready for execution.
     pub(crate) async fn load_manifest(&self, mut manifest: AppManifest) -> Result<LockedApp> {
         spin_manifest::normalize::normalize_manifest(&mut manifest);
+        if let Some(profile) = &self.build_profile {
+            spin_manifest::normalize::apply_build_profile(&mut manifest, profile)?;
+        }
 
         manifest.validate_dependencies()?;
 
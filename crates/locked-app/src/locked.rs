@@ -115,7 +115,8 @@ where
     }
 }
 
-const SUPPORTED_HOST_REQS: &[&str] = &[SERVICE_CHAINING_KEY];
+/// Host requirements that this version of Spin knows how to process.
+pub const SUPPORTED_HOST_REQS: &[&str] = &[SERVICE_CHAINING_KEY];
 
 impl Serialize for LockedApp {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -50,6 +50,10 @@ pub enum Error {
     #[error(transparent)]
     TomlParse(#[from] toml::de::Error),
 
+    /// No component has the requested build profile
+    #[error("no component has a build profile named `{0}`")]
+    UnknownBuildProfile(String),
+
     /// Validation error
     #[error(transparent)]
     ValidationError(anyhow::Error),
@@ -2,7 +2,9 @@
 
 use std::collections::HashSet;
 
-use crate::schema::v2::{AppManifest, ComponentSpec, KebabId};
+use crate::Error;
+
+use crate::schema::v2::{AppManifest, ComponentSource, ComponentSpec, KebabId};
 
 /// Normalizes some optional [`AppManifest`] features into a canonical form:
 /// - Inline components in trigger configs are moved into top-level
@@ -13,6 +15,33 @@ pub fn normalize_manifest(manifest: &mut AppManifest) {
     normalize_inline_components(manifest);
 }
 
+/// Applies the named build profile to every component that has it, replacing
+/// the component's build command, build environment and (if the profile
+/// names one) local source. The manifest should already be normalized, so
+/// that inline components are included.
+///
+/// Returns an error if no component has the profile.
+pub fn apply_build_profile(manifest: &mut AppManifest, profile: &str) -> Result<(), Error> {
+    let mut found = false;
+    for component in manifest.components.values_mut() {
+        let Some(build) = &mut component.build else {
+            continue;
+        };
+        if !build.profile.contains_key(profile) {
+            continue;
+        }
+        found = true;
+        if let Some(source) = build.apply_profile(profile) {
+            component.source = ComponentSource::Local(source);
+        }
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(Error::UnknownBuildProfile(profile.to_owned()))
+    }
+}
+
 fn normalize_inline_components(manifest: &mut AppManifest) {
     // Normalize inline components
     let components = &mut manifest.components;
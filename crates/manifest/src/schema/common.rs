@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use wasm_pkg_common::{package::PackageRef, registry::Registry};
 
+use super::v2::Map;
+
 /// Variable definition
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -90,6 +92,12 @@ pub struct ComponentBuildConfig {
     /// watch = ["src/**/*.rs"]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub watch: Vec<String>,
+    /// `[component.<id>.build.environment]`
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub environment: Map<String, String>,
+    /// `[component.<id>.build.profile.<name>]`
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub profile: Map<String, ComponentBuildProfile>,
 }
 
 impl ComponentBuildConfig {
@@ -101,6 +109,41 @@ impl ComponentBuildConfig {
         };
         as_vec.into_iter()
     }
+
+    /// Applies the named build profile, if the component has it: the
+    /// profile's command replaces the default command, and the profile's
+    /// environment is added to the default environment. Returns the path of
+    /// the Wasm file the profile builds, if it differs from the component
+    /// source.
+    pub fn apply_profile(&mut self, name: &str) -> Option<String> {
+        let profile = self.profile.get(name)?.clone();
+        if let Some(command) = profile.command {
+            self.command = command;
+        }
+        if let Some(workdir) = profile.workdir {
+            self.workdir = Some(workdir);
+        }
+        self.environment.extend(profile.environment);
+        profile.source
+    }
+}
+
+/// A named alternative build configuration, selected with `spin build --profile <name>`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentBuildProfile {
+    /// `command = "cargo build --release"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Commands>,
+    /// `workdir = "components/main"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+    /// `source = "target/wasm32-wasip1/release/main.wasm"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// `[component.<id>.build.profile.<name>.environment]`
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub environment: Map<String, String>,
 }
 
 /// Component build command or commands
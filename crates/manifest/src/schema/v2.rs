@@ -4,7 +4,9 @@ use spin_serde::{DependencyName, DependencyPackageName, FixedVersion, LowerSnake
 pub use spin_serde::{KebabId, SnakeId};
 use std::path::PathBuf;
 
-pub use super::common::{ComponentBuildConfig, ComponentSource, Variable, WasiFilesMount};
+pub use super::common::{
+    ComponentBuildConfig, ComponentBuildProfile, ComponentSource, Variable, WasiFilesMount,
+};
 
 pub(crate) type Map<K, V> = indexmap::IndexMap<K, V>;
 
@@ -739,4 +741,43 @@ mod tests {
         .validate()
         .is_err());
     }
+
+    #[test]
+    fn build_profiles_replace_command_and_source() {
+        let mut manifest = AppManifest::deserialize(toml! {
+            spin_manifest_version = 2
+            [application]
+            name = "build-profiles"
+            [component.profiled]
+            source = "debug.wasm"
+            [component.profiled.build]
+            command = "cargo build"
+            environment = { COLOR = "always" }
+            [component.profiled.build.profile.release]
+            command = "cargo build --release"
+            source = "release.wasm"
+            environment = { RUSTFLAGS = "-C opt-level=3" }
+            [component.unprofiled]
+            source = "other.wasm"
+            [component.unprofiled.build]
+            command = "make"
+        })
+        .unwrap();
+
+        assert!(crate::normalize::apply_build_profile(&mut manifest.clone(), "nope").is_err());
+        crate::normalize::apply_build_profile(&mut manifest, "release").unwrap();
+
+        let id = |id: &str| -> KebabId { id.to_owned().try_into().unwrap() };
+        let profiled = &manifest.components[&id("profiled")];
+        assert!(matches!(&profiled.source, ComponentSource::Local(s) if s == "release.wasm"));
+        let build = profiled.build.as_ref().unwrap();
+        assert_eq!(
+            vec!["cargo build --release"],
+            build.commands().collect::<Vec<_>>()
+        );
+        assert_eq!(2, build.environment.len());
+
+        let unprofiled = &manifest.components[&id("unprofiled")];
+        assert!(matches!(&unprofiled.source, ComponentSource::Local(s) if s == "other.wasm"));
+    }
 }
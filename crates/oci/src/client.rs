@@ -3,14 +3,18 @@
 use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use docker_credential::DockerCredential;
 use futures_util::future;
 use futures_util::stream::{self, StreamExt, TryStreamExt};
 use itertools::Itertools;
 use oci_distribution::{
-    client::ImageLayer, config::ConfigFile, manifest::OciImageManifest, secrets::RegistryAuth,
-    token_cache::RegistryTokenType, Reference, RegistryOperation,
+    client::ImageLayer,
+    config::ConfigFile,
+    manifest::{ImageIndexEntry, OciImageIndex, OciImageManifest, OciManifest},
+    secrets::RegistryAuth,
+    token_cache::RegistryTokenType,
+    Reference, RegistryOperation,
 };
 use reqwest::Url;
 use spin_common::sha256;
@@ -23,6 +27,10 @@ use tokio::fs;
 use walkdir::WalkDir;
 
 use crate::auth::AuthConfig;
+use crate::layout::{
+    ImageLayout, LayoutDescriptor, OCI_IMAGE_INDEX_MEDIA_TYPE, OCI_IMAGE_MEDIA_TYPE,
+    REF_NAME_ANNOTATION,
+};
 
 // TODO: the media types for application, data and archive layer are not final
 /// Media type for a layer representing a locked Spin application configuration
@@ -31,12 +39,32 @@ pub const SPIN_APPLICATION_MEDIA_TYPE: &str = "application/vnd.fermyon.spin.appl
 pub const DATA_MEDIATYPE: &str = "application/vnd.wasm.content.layer.v1+data";
 /// Media type for a layer representing a compressed archive of one or more files used by a Spin application
 pub const ARCHIVE_MEDIATYPE: &str = "application/vnd.wasm.content.bundle.v1.tar+gzip";
+/// Media type for a layer representing a compressed archive of Spin templates
+pub const SPIN_TEMPLATES_MEDIA_TYPE: &str = "application/vnd.fermyon.spin.templates.v1.tar+gzip";
 // Note: this will be updated with a canonical value once defined upstream
 const WASM_LAYER_MEDIA_TYPE: &str = "application/vnd.wasm.content.layer.v1+wasm";
+/// Manifest media types accepted when pulling.
+const MANIFEST_MEDIA_TYPES: &[&str] = &[
+    OCI_IMAGE_MEDIA_TYPE,
+    OCI_IMAGE_INDEX_MEDIA_TYPE,
+    oci_distribution::manifest::IMAGE_MANIFEST_MEDIA_TYPE,
+    oci_distribution::manifest::IMAGE_MANIFEST_LIST_MEDIA_TYPE,
+];
 
 const CONFIG_FILE: &str = "config.json";
 const LATEST_TAG: &str = "latest";
 const MANIFEST_FILE: &str = "manifest.json";
+/// Annotation on an image index entry naming the application variant
+const VARIANT_NAME_ANNOTATION: &str = "com.fermyon.spin.variant";
+/// Annotation on an image index entry listing the (comma-separated) host
+/// requirements of the application variant
+const VARIANT_HOST_REQUIREMENTS_ANNOTATION: &str = "com.fermyon.spin.hostRequirements";
+/// Reference to record as the origin of an app loaded from an OCI image layout
+/// that does not record the reference it was saved from.
+const LAYOUT_FALLBACK_REFERENCE: &str = "localhost/spin-oci-layout:latest";
+/// Registry under which apps loaded from OCI image layouts are cached, so that
+/// they don't replace apps pulled from the registry they were saved from.
+const LAYOUT_CACHE_REGISTRY: &str = "oci-layout.spin.local";
 
 /// Env var to force use of archive layers when publishing a Spin app
 const SPIN_OCI_ARCHIVE_LAYERS_OPT: &str = "SPIN_OCI_ARCHIVE_LAYERS";
@@ -87,7 +115,7 @@ pub struct ClientOpts {
 /// Controls whether predefined annotations are generated when pushing an application.
 /// If an explicit annotation has the same name as a predefined one, the explicit
 /// one takes precedence.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum InferPredefinedAnnotations {
     /// Infer annotations for created, authors, version, name and description.
     All,
@@ -170,6 +198,186 @@ impl Client {
         annotations: Option<BTreeMap<String, String>>,
         infer_annotations: InferPredefinedAnnotations,
     ) -> Result<Option<String>> {
+        let (layers, oci_config, manifest) = self
+            .assemble_image(locked, annotations, infer_annotations)
+            .await?;
+
+        let response = self
+            .oci
+            .push(&reference, &layers, oci_config, &auth, Some(manifest))
+            .await
+            .map(|push_response| push_response.manifest_url)
+            .context("cannot push Spin application")?;
+
+        tracing::info!("Pushed {:?}", response);
+
+        let digest = digest_from_url(&response);
+        Ok(digest)
+    }
+
+    /// Push several variants of a Spin application, each given by the path to
+    /// its application manifest, to an OCI registry as a single image index.
+    ///
+    /// See [`Client::push_locked_variants`] for how variants are selected on pull.
+    pub async fn push_variants(
+        &mut self,
+        variants: &[(String, PathBuf)],
+        reference: impl AsRef<str>,
+        annotations: Option<BTreeMap<String, String>>,
+        infer_annotations: InferPredefinedAnnotations,
+    ) -> Result<Option<String>> {
+        // The working directories hold copies of the variants' files, so
+        // must live until the push has completed.
+        let mut working_dirs = Vec::with_capacity(variants.len());
+        let mut locked_variants = Vec::with_capacity(variants.len());
+        for (name, manifest_path) in variants {
+            let working_dir = tempfile::tempdir()?;
+            let locked = spin_loader::from_file(
+                manifest_path,
+                FilesMountStrategy::Copy(working_dir.path().into()),
+                None,
+            )
+            .await
+            .with_context(|| format!("cannot load variant {name:?}"))?;
+            working_dirs.push(working_dir);
+            locked_variants.push((name.clone(), locked));
+        }
+
+        self.push_locked_variants(locked_variants, reference, annotations, infer_annotations)
+            .await
+    }
+
+    /// Push several variants of a Spin application to an OCI registry as a
+    /// single image index, and return the digest of the index (or None if
+    /// the digest cannot be determined).
+    ///
+    /// Each variant is pushed as an untagged image manifest, annotated with the
+    /// host requirements of its locked application. When the index is pulled, the
+    /// first variant whose host requirements are supported by the host is used,
+    /// so variants should be given in order of preference.
+    pub async fn push_locked_variants(
+        &mut self,
+        variants: Vec<(String, LockedApp)>,
+        reference: impl AsRef<str>,
+        annotations: Option<BTreeMap<String, String>>,
+        infer_annotations: InferPredefinedAnnotations,
+    ) -> Result<Option<String>> {
+        let reference: Reference = reference
+            .as_ref()
+            .parse()
+            .with_context(|| format!("cannot parse reference {}", reference.as_ref()))?;
+        let auth = Self::auth(&reference).await?;
+
+        ensure!(!variants.is_empty(), "no application variants to push");
+
+        let mut index_annotations = None;
+        let mut entries = Vec::with_capacity(variants.len());
+        for (name, locked) in variants {
+            let host_requirements = required_host_requirements(&locked).join(",");
+            let (layers, oci_config, manifest) = self
+                .assemble_image(locked, annotations.clone(), infer_annotations)
+                .await
+                .with_context(|| format!("could not assemble variant {name:?}"))?;
+            if index_annotations.is_none() {
+                index_annotations = Some(manifest.annotations.clone());
+            }
+
+            // Variants are pushed by digest so that they do not occupy a tag. The
+            // manifest is pushed as the exact bytes it is digested from, so that
+            // the index refers to the manifest as stored by the registry.
+            let manifest_bytes = serde_json::to_vec(&manifest)?;
+            let manifest_digest =
+                format!("sha256:{}", sha256::hex_digest_from_bytes(&manifest_bytes));
+            let variant_reference = Reference::with_digest(
+                reference.registry().to_owned(),
+                reference.repository().to_owned(),
+                manifest_digest.clone(),
+            );
+            self.push_image_raw(
+                &variant_reference,
+                &layers,
+                &oci_config,
+                &auth,
+                manifest_bytes.clone(),
+            )
+            .await
+            .with_context(|| format!("cannot push variant {name:?}"))?;
+            tracing::info!("Pushed variant {name:?} as {manifest_digest}");
+
+            entries.push(ImageIndexEntry {
+                media_type: OCI_IMAGE_MEDIA_TYPE.to_owned(),
+                digest: manifest_digest,
+                size: manifest_bytes.len().try_into()?,
+                platform: None,
+                annotations: Some(
+                    [
+                        (VARIANT_NAME_ANNOTATION.to_owned(), name),
+                        (
+                            VARIANT_HOST_REQUIREMENTS_ANNOTATION.to_owned(),
+                            host_requirements,
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+            });
+        }
+
+        let index = OciImageIndex {
+            schema_version: 2,
+            media_type: Some(OCI_IMAGE_INDEX_MEDIA_TYPE.to_owned()),
+            manifests: entries,
+            annotations: index_annotations.flatten().map(|a| a.into_iter().collect()),
+        };
+        let response = self
+            .oci
+            .push_manifest_list(&reference, &auth, index)
+            .await
+            .context("cannot push Spin application index")?;
+
+        tracing::info!("Pushed {:?}", response);
+
+        let digest = digest_from_url(&response);
+        Ok(digest)
+    }
+
+    /// Push the blobs of an image followed by its manifest, given as the exact
+    /// bytes to store in the registry.
+    async fn push_image_raw(
+        &mut self,
+        reference: &Reference,
+        layers: &[ImageLayer],
+        config: &oci_distribution::client::Config,
+        auth: &RegistryAuth,
+        manifest_bytes: Vec<u8>,
+    ) -> Result<()> {
+        self.oci
+            .auth(reference, auth, RegistryOperation::Push)
+            .await?;
+        for (data, digest) in layers
+            .iter()
+            .map(|layer| (&layer.data, layer.sha256_digest()))
+            .chain(std::iter::once((&config.data, config.sha256_digest())))
+        {
+            self.oci.push_blob(reference, data, &digest).await?;
+        }
+        self.oci
+            .push_manifest_raw(reference, manifest_bytes, OCI_IMAGE_MEDIA_TYPE.parse()?)
+            .await?;
+        Ok(())
+    }
+
+    /// Assemble the layers, config and image manifest for a locked application.
+    async fn assemble_image(
+        &mut self,
+        locked: LockedApp,
+        annotations: Option<BTreeMap<String, String>>,
+        infer_annotations: InferPredefinedAnnotations,
+    ) -> Result<(
+        Vec<ImageLayer>,
+        oci_distribution::client::Config,
+        OciImageManifest,
+    )> {
         let mut locked_app = locked.clone();
         let mut layers = self
             .assemble_layers(&mut locked_app, AssemblyMode::Simple)
@@ -227,17 +435,7 @@ impl Client {
             oci_distribution::client::Config::oci_v1_from_config_file(oci_config_file, None)?;
         let manifest = OciImageManifest::build(&layers, &oci_config, annotations);
 
-        let response = self
-            .oci
-            .push(&reference, &layers, oci_config, &auth, Some(manifest))
-            .await
-            .map(|push_response| push_response.manifest_url)
-            .context("cannot push Spin application")?;
-
-        tracing::info!("Pushed {:?}", response);
-
-        let digest = digest_from_url(&response);
-        Ok(digest)
+        Ok((layers, oci_config, manifest))
     }
 
     /// Assemble ImageLayers for a locked application using the provided
@@ -409,7 +607,7 @@ impl Client {
         let auth = Self::auth(&reference).await?;
 
         // Pull the manifest from the registry.
-        let (manifest, digest) = self.oci.pull_image_manifest(&reference, &auth).await?;
+        let (manifest, digest) = self.pull_image_manifest(&reference, &auth).await?;
 
         let manifest_json = serde_json::to_string(&manifest)?;
         tracing::debug!("Pulled manifest: {}", manifest_json);
@@ -447,23 +645,8 @@ impl Client {
                     tracing::debug!("Pulling layer {}", &layer.digest);
                     let mut bytes = Vec::with_capacity(layer.size.try_into()?);
                     this.oci.pull_blob(&reference, &layer, &mut bytes).await?;
-                    match layer.media_type.as_str() {
-                        SPIN_APPLICATION_MEDIA_TYPE => {
-                            this.write_locked_app_config(&reference.to_string(), &bytes)
-                                .await
-                                .with_context(|| "unable to write locked app config to cache")?;
-                        }
-                        WASM_LAYER_MEDIA_TYPE => {
-                            this.cache.write_wasm(&bytes, &layer.digest).await?;
-                        }
-                        ARCHIVE_MEDIATYPE => {
-                            unpack_archive_layer(&this.cache, &bytes, &layer.digest).await?;
-                        }
-                        _ => {
-                            this.cache.write_data(&bytes, &layer.digest).await?;
-                        }
-                    }
-                    Ok(())
+                    this.write_layer_to_cache(&reference, &layer.media_type, &layer.digest, &bytes)
+                        .await
                 }
             })
             .buffer_unordered(MAX_PARALLEL_PULL)
@@ -474,6 +657,273 @@ impl Client {
         Ok(())
     }
 
+    /// Pull an artifact consisting of a single compressed archive layer, such as
+    /// a set of Spin templates, from an OCI registry and unpack it into `dest`.
+    /// The layer must have the given media type or the generic archive media type.
+    /// Returns the digest of the pulled manifest.
+    pub async fn pull_archive(
+        &mut self,
+        reference: &str,
+        media_type: &str,
+        dest: &Path,
+    ) -> Result<String> {
+        let reference: Reference = reference.parse().context("cannot parse reference")?;
+        let auth = Self::auth(&reference).await?;
+
+        let (manifest, digest) = self.oci.pull_image_manifest(&reference, &auth).await?;
+        let layer = manifest
+            .layers
+            .iter()
+            .find(|layer| layer.media_type == media_type)
+            .or_else(|| {
+                manifest
+                    .layers
+                    .iter()
+                    .find(|layer| layer.media_type == ARCHIVE_MEDIATYPE)
+            })
+            .with_context(|| format!("{reference} does not contain a {media_type} layer"))?;
+
+        tracing::debug!("Pulling archive layer {}", &layer.digest);
+        let mut bytes = Vec::with_capacity(layer.size.try_into()?);
+        self.oci.pull_blob(&reference, layer, &mut bytes).await?;
+        let actual_digest = format!("sha256:{}", sha256::hex_digest_from_bytes(&bytes));
+        ensure!(
+            actual_digest == layer.digest,
+            "archive layer of {reference} has digest {actual_digest} but expected {}",
+            layer.digest
+        );
+
+        let staging_dir = tempfile::tempdir()?;
+        let archive_path = staging_dir.path().join("archive.tar.gz");
+        fs::write(&archive_path, &bytes).await?;
+        crate::utils::unarchive(&archive_path, dest)
+            .await
+            .with_context(|| format!("cannot unpack archive layer of {reference}"))?;
+        tracing::info!("Pulled {}@{}", reference, digest);
+
+        Ok(digest)
+    }
+
+    /// Pull the image manifest for a reference. If the reference is to an image
+    /// index of application variants, this selects the first variant whose host
+    /// requirements are supported by this version of Spin.
+    async fn pull_image_manifest(
+        &mut self,
+        reference: &Reference,
+        auth: &RegistryAuth,
+    ) -> Result<(OciImageManifest, String)> {
+        let (manifest, _, digest) = self.pull_image_manifest_raw(reference, auth).await?;
+        Ok((manifest, digest))
+    }
+
+    /// Like [`Client::pull_image_manifest`], but also returns the manifest
+    /// exactly as served by the registry.
+    async fn pull_image_manifest_raw(
+        &mut self,
+        reference: &Reference,
+        auth: &RegistryAuth,
+    ) -> Result<(OciImageManifest, Vec<u8>, String)> {
+        self.oci
+            .auth(reference, auth, RegistryOperation::Pull)
+            .await?;
+        let (manifest_bytes, digest) = self
+            .oci
+            .pull_manifest_raw(reference, auth, MANIFEST_MEDIA_TYPES)
+            .await?;
+        let manifest: OciManifest = serde_json::from_slice(&manifest_bytes)
+            .with_context(|| format!("cannot parse manifest of {reference}"))?;
+        let index = match manifest {
+            OciManifest::Image(manifest) => return Ok((manifest, manifest_bytes, digest)),
+            OciManifest::ImageIndex(index) => index,
+        };
+
+        let supported = spin_locked_app::locked::SUPPORTED_HOST_REQS;
+        let entry = index
+            .manifests
+            .iter()
+            .filter(|entry| entry.media_type == OCI_IMAGE_MEDIA_TYPE)
+            .find(|entry| {
+                let requirements = entry
+                    .annotations
+                    .as_ref()
+                    .and_then(|a| a.get(VARIANT_HOST_REQUIREMENTS_ANNOTATION))
+                    .map(|r| r.as_str())
+                    .unwrap_or_default();
+                is_supported_variant(requirements, supported)
+            })
+            .with_context(|| {
+                format!("{reference} has no variant whose host requirements are supported by this version of Spin")
+            })?;
+        tracing::debug!(
+            "Selected variant {:?} ({}) from index {}",
+            entry
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(VARIANT_NAME_ANNOTATION)),
+            entry.digest,
+            digest
+        );
+
+        let variant_reference = Reference::with_digest(
+            reference.registry().to_owned(),
+            reference.repository().to_owned(),
+            entry.digest.clone(),
+        );
+        let (manifest_bytes, digest) = self
+            .oci
+            .pull_manifest_raw(&variant_reference, auth, &[OCI_IMAGE_MEDIA_TYPE])
+            .await?;
+        let manifest = serde_json::from_slice(&manifest_bytes)
+            .with_context(|| format!("cannot parse manifest of {variant_reference}"))?;
+        Ok((manifest, manifest_bytes, digest))
+    }
+
+    /// Write a pulled layer to the cache according to its media type.
+    async fn write_layer_to_cache(
+        &self,
+        reference: &Reference,
+        media_type: &str,
+        digest: &str,
+        bytes: &[u8],
+    ) -> Result<()> {
+        match media_type {
+            SPIN_APPLICATION_MEDIA_TYPE => {
+                self.write_locked_app_config(&reference.to_string(), bytes)
+                    .await
+                    .with_context(|| "unable to write locked app config to cache")?;
+            }
+            WASM_LAYER_MEDIA_TYPE => {
+                self.cache.write_wasm(bytes, digest).await?;
+            }
+            ARCHIVE_MEDIATYPE => {
+                unpack_archive_layer(&self.cache, bytes, digest).await?;
+            }
+            _ => {
+                self.cache.write_data(bytes, digest).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Save a Spin application from an OCI registry to a tar file in the
+    /// OCI image layout format, for transfer to hosts without registry access.
+    pub async fn save(&mut self, reference: &str, output: &Path) -> Result<()> {
+        let reference: Reference = reference.parse().context("cannot parse reference")?;
+        let auth = Self::auth(&reference).await?;
+
+        let (manifest, manifest_bytes, digest) =
+            self.pull_image_manifest_raw(&reference, &auth).await?;
+        tracing::debug!("Saving {}@{}", reference, digest);
+
+        let staging_dir = tempfile::tempdir()?;
+        let layout = ImageLayout::create(staging_dir.path()).await?;
+
+        // The config and layers are stored in the layout exactly as pulled
+        // from the registry, so that their digests are preserved.
+        let blobs = std::iter::once(&manifest.config).chain(manifest.layers.iter());
+        stream::iter(blobs)
+            .map(|descriptor| {
+                let this = &self;
+                let layout = &layout;
+                let reference = &reference;
+                async move {
+                    let mut bytes = Vec::with_capacity(descriptor.size.try_into()?);
+                    this.oci
+                        .pull_blob(reference, descriptor, &mut bytes)
+                        .await?;
+                    let written = layout.write_blob(&bytes).await?;
+                    ensure!(
+                        written == descriptor.digest,
+                        "registry returned content with digest {written} for blob {}",
+                        descriptor.digest
+                    );
+                    anyhow::Ok(())
+                }
+            })
+            .buffer_unordered(MAX_PARALLEL_PULL)
+            .try_for_each(future::ok)
+            .await?;
+
+        // The manifest is also stored exactly as pulled, rather than re-serialized
+        let manifest_digest = layout.write_blob(&manifest_bytes).await?;
+        layout
+            .write_index(vec![LayoutDescriptor {
+                media_type: OCI_IMAGE_MEDIA_TYPE.to_owned(),
+                digest: manifest_digest,
+                size: manifest_bytes.len().try_into()?,
+                annotations: [(REF_NAME_ANNOTATION.to_owned(), reference.to_string())]
+                    .into_iter()
+                    .collect(),
+            }])
+            .await?;
+
+        crate::layout::pack(staging_dir.path(), output).await?;
+        tracing::info!("Saved {} to {}", reference, quoted_path(output));
+
+        Ok(())
+    }
+
+    /// Load a Spin application from an OCI image layout (either a directory
+    /// or a tar file as written by [`Client::save`]) into the cache, as if it
+    /// had been pulled from a registry.
+    ///
+    /// Returns the key under which it was cached, for use with
+    /// [`Client::lockfile_path`], and the reference it was saved from. The key
+    /// is derived from the image's digest, so that loading an image doesn't
+    /// replace a pull of the reference it was saved from.
+    pub async fn load_layout(&mut self, source: &Path) -> Result<(String, String)> {
+        let staging_dir = tempfile::tempdir()?;
+        let layout_dir = if source.is_dir() {
+            source.to_owned()
+        } else {
+            crate::layout::unpack(source, staging_dir.path()).await?;
+            staging_dir.path().to_owned()
+        };
+        let layout = ImageLayout::open(&layout_dir).await?;
+
+        let descriptor = layout.select_manifest().await?;
+        let reference: Reference = descriptor
+            .ref_name()
+            .unwrap_or(LAYOUT_FALLBACK_REFERENCE)
+            .parse()
+            .context("cannot parse reference in OCI image layout")?;
+        let (_, digest_hex) = descriptor
+            .digest
+            .split_once(':')
+            .context("invalid manifest digest in OCI image layout")?;
+        let cache_key: Reference = format!(
+            "{LAYOUT_CACHE_REGISTRY}/{}:{digest_hex}",
+            reference.repository()
+        )
+        .parse()
+        .context("cannot derive cache key for OCI image layout")?;
+
+        let manifest_bytes = layout.read_blob(&descriptor.digest).await?;
+        let manifest: OciImageManifest = serde_json::from_slice(&manifest_bytes)
+            .context("cannot parse image manifest in OCI image layout")?;
+
+        let m = self.manifest_path(&cache_key.to_string()).await?;
+        fs::write(&m, &manifest_bytes).await?;
+
+        // As with pull, the config may be the locked app for older apps.
+        let cfg_bytes = layout.read_blob(&manifest.config.digest).await?;
+        self.write_locked_app_config(&cache_key.to_string(), &cfg_bytes)
+            .await
+            .context("unable to write locked app config to cache")?;
+
+        for layer in &manifest.layers {
+            let bytes = layout
+                .read_blob(&layer.digest)
+                .await
+                .with_context(|| format!("cannot read layer {}", layer.digest))?;
+            self.write_layer_to_cache(&cache_key, &layer.media_type, &layer.digest, &bytes)
+                .await?;
+        }
+        tracing::info!("Loaded {} from {}", reference, quoted_path(source));
+
+        Ok((cache_key.to_string(), reference.to_string()))
+    }
+
     /// Get the file path to an OCI manifest given a reference.
     /// If the directory for the manifest does not exist, this will create it.
     async fn manifest_path(&self, reference: impl AsRef<str>) -> Result<PathBuf> {
@@ -709,6 +1159,26 @@ pub async fn unpack_archive_layer(
     Ok(())
 }
 
+/// Returns the host requirements that a locked application marks as required.
+fn required_host_requirements(locked: &LockedApp) -> Vec<&str> {
+    locked
+        .host_requirements
+        .iter()
+        .filter(|(_, v)| v.as_str() == Some(spin_locked_app::locked::HOST_REQ_REQUIRED))
+        .map(|(k, _)| k.as_str())
+        .collect()
+}
+
+/// Whether a variant with the given comma-separated host requirements can
+/// run on a host supporting the `supported` requirements.
+fn is_supported_variant(requirements: &str, supported: &[&str]) -> bool {
+    requirements
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .all(|r| supported.contains(&r))
+}
+
 fn digest_from_url(manifest_url: &str) -> Option<String> {
     // The URL is in the form "https://host/v2/refname/manifests/sha256:..."
     let manifest_url = Url::parse(manifest_url).ok()?;
@@ -854,6 +1324,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn variant_selection_requires_all_host_requirements() {
+        let supported = &["local_service_chaining"];
+        assert!(is_supported_variant("", supported));
+        assert!(is_supported_variant("local_service_chaining", supported));
+        assert!(!is_supported_variant(
+            "local_service_chaining,gpu",
+            supported
+        ));
+        assert!(!is_supported_variant("local_service_chaining", &[]));
+    }
+
     #[test]
     fn can_derive_registry_from_input() {
         #[derive(Clone)]
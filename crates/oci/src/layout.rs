@@ -0,0 +1,303 @@
+//! Reading and writing Spin applications in the OCI image layout format.
+//!
+//! See https://github.com/opencontainers/image-spec/blob/main/image-layout.md
+//! for the format. Spin uses the same manifest and layer media types in a
+//! layout as when pushing to a registry, so a layout can be thought of as
+//! an offline registry holding a single application.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use spin_common::sha256;
+use spin_common::ui::quoted_path;
+
+/// Name of the file marking a directory as an OCI image layout.
+const OCI_LAYOUT_FILE: &str = "oci-layout";
+/// Name of the image index file at the root of an OCI image layout.
+const INDEX_FILE: &str = "index.json";
+/// Directory containing content-addressable blobs.
+const BLOBS_DIR: &str = "blobs";
+/// The image layout version written by Spin.
+const IMAGE_LAYOUT_VERSION: &str = "1.0.0";
+
+/// Media type of an OCI image index.
+pub(crate) const OCI_IMAGE_INDEX_MEDIA_TYPE: &str = "application/vnd.oci.image.index.v1+json";
+/// Media type of an OCI image manifest.
+pub(crate) const OCI_IMAGE_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+/// Annotation recording the reference an image was saved from.
+pub(crate) const REF_NAME_ANNOTATION: &str = "org.opencontainers.image.ref.name";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImageLayoutMarker {
+    #[serde(rename = "imageLayoutVersion")]
+    image_layout_version: String,
+}
+
+/// The `index.json` of an OCI image layout.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LayoutIndex {
+    schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    media_type: Option<String>,
+    manifests: Vec<LayoutDescriptor>,
+}
+
+/// A descriptor of a manifest in the `index.json` of an OCI image layout.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LayoutDescriptor {
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl LayoutDescriptor {
+    /// The reference the manifest was saved from, if recorded.
+    pub fn ref_name(&self) -> Option<&str> {
+        self.annotations
+            .get(REF_NAME_ANNOTATION)
+            .map(|s| s.as_str())
+    }
+}
+
+/// An OCI image layout directory on disk.
+pub(crate) struct ImageLayout {
+    root: PathBuf,
+}
+
+impl ImageLayout {
+    /// Initialise an empty image layout in the given directory.
+    pub async fn create(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        tokio::fs::create_dir_all(root.join(BLOBS_DIR).join("sha256"))
+            .await
+            .with_context(|| format!("cannot create image layout at {}", quoted_path(&root)))?;
+        let marker = ImageLayoutMarker {
+            image_layout_version: IMAGE_LAYOUT_VERSION.to_owned(),
+        };
+        tokio::fs::write(root.join(OCI_LAYOUT_FILE), serde_json::to_vec(&marker)?).await?;
+        Ok(Self { root })
+    }
+
+    /// Open an existing image layout directory.
+    pub async fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let marker_path = root.join(OCI_LAYOUT_FILE);
+        let marker_bytes = tokio::fs::read(&marker_path).await.with_context(|| {
+            format!(
+                "{} is not an OCI image layout: cannot read {OCI_LAYOUT_FILE}",
+                quoted_path(&root)
+            )
+        })?;
+        let marker: ImageLayoutMarker = serde_json::from_slice(&marker_bytes)
+            .with_context(|| format!("invalid {}", quoted_path(&marker_path)))?;
+        ensure!(
+            marker.image_layout_version == IMAGE_LAYOUT_VERSION,
+            "unsupported OCI image layout version {}",
+            marker.image_layout_version
+        );
+        Ok(Self { root })
+    }
+
+    /// Write a blob into the layout, returning its digest.
+    pub async fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<String> {
+        let digest = format!("sha256:{}", sha256::hex_digest_from_bytes(&bytes));
+        let path = self.blob_path(&digest)?;
+        if !path.exists() {
+            tokio::fs::write(&path, bytes)
+                .await
+                .with_context(|| format!("cannot write blob {}", quoted_path(&path)))?;
+        }
+        Ok(digest)
+    }
+
+    /// Read a blob from the layout, verifying that its content matches the digest.
+    pub async fn read_blob(&self, digest: &str) -> Result<Vec<u8>> {
+        let path = self.blob_path(digest)?;
+        let bytes = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("cannot read blob {}", quoted_path(&path)))?;
+        let actual = format!("sha256:{}", sha256::hex_digest_from_bytes(&bytes));
+        ensure!(
+            actual == digest,
+            "blob {digest} is corrupt: content has digest {actual}"
+        );
+        Ok(bytes)
+    }
+
+    /// Write the layout's `index.json` to list the given manifests.
+    pub async fn write_index(&self, manifests: Vec<LayoutDescriptor>) -> Result<()> {
+        let index = LayoutIndex {
+            schema_version: 2,
+            media_type: Some(OCI_IMAGE_INDEX_MEDIA_TYPE.to_owned()),
+            manifests,
+        };
+        tokio::fs::write(
+            self.root.join(INDEX_FILE),
+            serde_json::to_vec_pretty(&index)?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Read the layout's `index.json`.
+    pub async fn read_index(&self) -> Result<LayoutIndex> {
+        let index_path = self.root.join(INDEX_FILE);
+        let bytes = tokio::fs::read(&index_path)
+            .await
+            .with_context(|| format!("cannot read {}", quoted_path(&index_path)))?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("invalid image index {}", quoted_path(&index_path)))
+    }
+
+    /// Select the application manifest from the index. The layout must contain
+    /// exactly one manifest, as written by [`Client::save`](crate::Client::save).
+    pub async fn select_manifest(&self) -> Result<LayoutDescriptor> {
+        let index = self.read_index().await?;
+        let mut candidates = index
+            .manifests
+            .into_iter()
+            .filter(|m| m.media_type == OCI_IMAGE_MEDIA_TYPE);
+        let first = candidates
+            .next()
+            .context("OCI image layout does not contain any image manifests")?;
+        if candidates.next().is_some() {
+            bail!("OCI image layout contains more than one image; it must contain a single Spin application");
+        }
+        Ok(first)
+    }
+
+    fn blob_path(&self, digest: &str) -> Result<PathBuf> {
+        let (algorithm, hex) = digest
+            .split_once(':')
+            .ok_or_else(|| anyhow!("invalid digest {digest:?}"))?;
+        ensure!(
+            algorithm == "sha256",
+            "unsupported digest algorithm in {digest:?}"
+        );
+        ensure!(
+            !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            "invalid digest {digest:?}"
+        );
+        Ok(self.root.join(BLOBS_DIR).join(algorithm).join(hex))
+    }
+}
+
+/// Package an image layout directory as an (uncompressed) tar file.
+pub(crate) async fn pack(layout_dir: &Path, output: &Path) -> Result<()> {
+    let file = tokio::fs::File::create(output)
+        .await
+        .with_context(|| format!("cannot create {}", quoted_path(output)))?;
+    let mut tar_builder = async_tar::Builder::new(
+        tokio_util::compat::TokioAsyncWriteCompatExt::compat_write(file),
+    );
+    tar_builder
+        .append_dir_all(".", layout_dir)
+        .await
+        .with_context(|| format!("cannot write image layout to {}", quoted_path(output)))?;
+    tar_builder.finish().await?;
+    use tokio::io::AsyncWriteExt;
+    tar_builder
+        .into_inner()
+        .await?
+        .into_inner()
+        .shutdown()
+        .await?;
+    Ok(())
+}
+
+/// Unpack an image layout tar file into the given directory.
+pub(crate) async fn unpack(archive: &Path, dest: &Path) -> Result<()> {
+    let file = tokio::fs::File::open(archive)
+        .await
+        .with_context(|| format!("cannot open {}", quoted_path(archive)))?;
+    let archive_reader =
+        async_tar::Archive::new(tokio_util::compat::TokioAsyncReadCompatExt::compat(file));
+    archive_reader
+        .unpack(dest)
+        .await
+        .with_context(|| format!("cannot unpack OCI image layout {}", quoted_path(archive)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn blobs_round_trip_through_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = ImageLayout::create(dir.path()).await.unwrap();
+        let digest = layout.write_blob(b"spin").await.unwrap();
+        assert_eq!(
+            "sha256:a5a2729ffa0eeacc15323a9168807c72d18d1cb375dbde899c44d6803dad2b19",
+            digest
+        );
+
+        let layout = ImageLayout::open(dir.path()).await.unwrap();
+        assert_eq!(b"spin".to_vec(), layout.read_blob(&digest).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn corrupt_blobs_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = ImageLayout::create(dir.path()).await.unwrap();
+        let digest = layout.write_blob(b"spin").await.unwrap();
+        tokio::fs::write(layout.blob_path(&digest).unwrap(), b"nope")
+            .await
+            .unwrap();
+        assert!(layout.read_blob(&digest).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn layout_must_contain_a_single_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = ImageLayout::create(dir.path()).await.unwrap();
+        let descriptor = |digest: &str, ref_name: &str| LayoutDescriptor {
+            media_type: OCI_IMAGE_MEDIA_TYPE.to_owned(),
+            digest: digest.to_owned(),
+            size: 0,
+            annotations: [(REF_NAME_ANNOTATION.to_owned(), ref_name.to_owned())]
+                .into_iter()
+                .collect(),
+        };
+
+        assert!(layout.select_manifest().await.is_err());
+
+        layout
+            .write_index(vec![descriptor("sha256:aa", "ghcr.io/fermyon/one:1.0")])
+            .await
+            .unwrap();
+        assert_eq!("sha256:aa", layout.select_manifest().await.unwrap().digest);
+
+        layout
+            .write_index(vec![
+                descriptor("sha256:aa", "ghcr.io/fermyon/one:1.0"),
+                descriptor("sha256:bb", "ghcr.io/fermyon/two:1.0"),
+            ])
+            .await
+            .unwrap();
+        assert!(layout.select_manifest().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn layout_survives_packing() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout_dir = dir.path().join("layout");
+        let layout = ImageLayout::create(&layout_dir).await.unwrap();
+        let digest = layout.write_blob(b"spin").await.unwrap();
+
+        let archive = dir.path().join("app.tar");
+        pack(&layout_dir, &archive).await.unwrap();
+        let unpacked_dir = dir.path().join("unpacked");
+        unpack(&archive, &unpacked_dir).await.unwrap();
+
+        let layout = ImageLayout::open(&unpacked_dir).await.unwrap();
+        assert_eq!(b"spin".to_vec(), layout.read_blob(&digest).await.unwrap());
+    }
+}
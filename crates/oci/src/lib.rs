@@ -3,6 +3,7 @@
 
 mod auth;
 pub mod client;
+mod layout;
 mod loader;
 pub mod utils;
 
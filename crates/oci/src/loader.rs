@@ -38,6 +38,28 @@ impl OciLoader {
             .await
     }
 
+    /// Loads an OCI Artifact from an OCI image layout directory or tar file
+    /// and returns a LockedApp, using the given OCI client's cache
+    pub async fn load_app_from_layout(
+        &self,
+        client: &mut Client,
+        layout_path: &Path,
+    ) -> Result<LockedApp> {
+        let (cache_key, reference) = client.load_layout(layout_path).await.with_context(|| {
+            format!(
+                "cannot load Spin application from OCI image layout {}",
+                quoted_path(layout_path)
+            )
+        })?;
+
+        let lockfile_path = client
+            .lockfile_path(&cache_key)
+            .await
+            .context("cannot get path to spin.lock")?;
+        self.load_from_cache(lockfile_path, &reference, &client.cache)
+            .await
+    }
+
     /// Loads an OCI Artifact from the given cache and returns a LockedApp with the given reference
     pub async fn load_from_cache(
         &self,
@@ -0,0 +1,5 @@
+//! Configuration of outbound networking that does not depend on the Spin
+//! runtime, so that tools which only inspect applications can use it without
+//! depending on the outbound networking factor.
+
+pub mod allowed_hosts;
@@ -167,16 +167,26 @@ impl BadgerEvaluator {
     async fn available_upgrades(&self) -> anyhow::Result<AvailableUpgrades> {
         let store = self.plugin_manager.store();
 
-        let latest_version = {
-            let latest_lookup = crate::lookup::PluginLookup::new(&self.plugin_name, None);
-            let latest_manifest = latest_lookup
-                .resolve_manifest_exact(store.get_plugins_directory())
+        // Only offer upgrades from the repository the plugin was installed
+        // from, if known, so that another index cannot hijack the plugin.
+        let source_repository = store.installed_from_repository(&self.plugin_name);
+
+        let (latest_version, repository) = {
+            let latest_lookup = crate::lookup::PluginLookup::new(&self.plugin_name, None)
+                .in_repository(source_repository);
+            match latest_lookup
+                .resolve_manifest_exact_with_source(store.get_plugins_directory())
                 .await
-                .ok();
-            latest_manifest.and_then(|m| semver::Version::parse(m.version()).ok())
+            {
+                Ok((m, repository)) => (semver::Version::parse(m.version()).ok(), Some(repository)),
+                Err(_) => (None, None),
+            }
         };
 
-        let manifests = store.catalogue_manifests()?;
+        let manifests = match &repository {
+            Some(repository) => store.catalogue_manifests_in(repository)?,
+            None => store.catalogue_manifests()?,
+        };
         let relevant_manifests = manifests
             .into_iter()
             .filter(|m| m.name() == self.plugin_name);
@@ -1,9 +1,12 @@
 pub mod badger;
 pub mod error;
 mod git;
+pub mod lockfile;
 pub mod lookup;
 pub mod manager;
 pub mod manifest;
+pub mod repos;
+pub mod signature;
 mod store;
 pub use store::PluginStore;
 
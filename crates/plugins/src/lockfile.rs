@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::{PluginManifest, PluginPackage};
+
+/// Name of the file in which a project pins the plugins it uses.
+pub const PLUGINS_LOCK_FILE_NAME: &str = "spin-plugins.lock";
+
+/// A project's pinned plugins. When running a plugin from within a project
+/// that has a lockfile, Spin runs the pinned version rather than the globally
+/// installed one, installing it alongside the global version if necessary.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PluginsLockfile {
+    #[serde(default, rename = "plugin")]
+    plugins: Vec<PinnedPlugin>,
+}
+
+/// A plugin pinned to a specific version.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PinnedPlugin {
+    /// Name of the plugin.
+    pub name: String,
+    /// Exact version of the plugin.
+    pub version: String,
+    /// Expected package checksums, keyed by `<os>-<arch>` (using Rust
+    /// OS and architecture names, e.g. `linux-x86_64`).
+    #[serde(default)]
+    pub sha256: BTreeMap<String, String>,
+}
+
+impl PluginsLockfile {
+    /// Finds the lockfile for the project containing `dir`, by searching
+    /// `dir` and its ancestors.
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|d| d.join(PLUGINS_LOCK_FILE_NAME))
+            .find(|p| p.is_file())
+    }
+
+    /// Loads a lockfile.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text)
+            .with_context(|| format!("Invalid plugins lockfile {}", path.display()))
+    }
+
+    /// Saves the lockfile.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string_pretty(self)?;
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The pinned plugins.
+    pub fn plugins(&self) -> &[PinnedPlugin] {
+        &self.plugins
+    }
+
+    /// Gets the pin for a plugin, if it is pinned.
+    pub fn get(&self, name: &str) -> Option<&PinnedPlugin> {
+        self.plugins.iter().find(|p| p.name == name)
+    }
+
+    /// Pins the plugin to the version and package checksums in the manifest,
+    /// replacing any existing pin.
+    pub fn pin(&mut self, manifest: &PluginManifest) -> &PinnedPlugin {
+        self.unpin(&manifest.name());
+        let sha256 = manifest
+            .packages
+            .iter()
+            .map(|p| (platform_key(p), p.sha256.clone()))
+            .collect();
+        self.plugins.push(PinnedPlugin {
+            name: manifest.name(),
+            version: manifest.version().to_owned(),
+            sha256,
+        });
+        self.plugins.sort_by(|p, q| p.name.cmp(&q.name));
+        self.get(&manifest.name()).unwrap()
+    }
+
+    /// Removes the pin for a plugin. Returns whether the plugin was pinned.
+    pub fn unpin(&mut self, name: &str) -> bool {
+        let count = self.plugins.len();
+        self.plugins.retain(|p| p.name != name);
+        self.plugins.len() != count
+    }
+}
+
+impl PinnedPlugin {
+    /// The pinned version as a semantic version.
+    pub fn semver(&self) -> Result<semver::Version> {
+        semver::Version::parse(&self.version).with_context(|| {
+            format!(
+                "Plugin '{}' is pinned to invalid version '{}'",
+                self.name, self.version
+            )
+        })
+    }
+
+    /// Checks that a manifest matches the pin: it must be the pinned version,
+    /// and if a checksum is pinned for this platform then the manifest's
+    /// package for this platform must have that checksum.
+    pub fn verify(&self, manifest: &PluginManifest) -> Result<()> {
+        if manifest.name() != self.name || manifest.version() != self.version {
+            bail!(
+                "Plugin '{}' is pinned to version {}, but found {} version {}",
+                self.name,
+                self.version,
+                manifest.name(),
+                manifest.version()
+            );
+        }
+        let Some(package) = manifest
+            .packages
+            .iter()
+            .find(|p| p.matches_current_os_arch())
+        else {
+            return Ok(());
+        };
+        match self.sha256.get(&platform_key(package)) {
+            Some(expected) if expected != &package.sha256 => Err(anyhow!(
+                "Plugin '{}' version {} does not match the checksum pinned in {PLUGINS_LOCK_FILE_NAME}. The package may have been changed since it was pinned.",
+                self.name,
+                self.version
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn platform_key(package: &PluginPackage) -> String {
+    format!("{}-{}", package.os.rust_name(), package.arch.rust_name())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manifest(version: &str, sha256: &str) -> PluginManifest {
+        let packages: Vec<_> = ["linux", "macos", "windows"]
+            .iter()
+            .flat_map(|os| {
+                ["amd64", "aarch64"].iter().map(move |arch| {
+                    serde_json::json!({
+                        "os": os,
+                        "arch": arch,
+                        "url": "https://example.com/plugin.tar.gz",
+                        "sha256": sha256,
+                    })
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "name": "pinned",
+            "version": version,
+            "spinCompatibility": ">= 0.1",
+            "license": "Apache-2.0",
+            "packages": packages,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pinned_manifest_verifies() {
+        let mut lockfile = PluginsLockfile::default();
+        let pin = lockfile.pin(&manifest("1.2.3", "abc")).clone();
+        assert_eq!("1.2.3", pin.version);
+        assert_eq!(6, pin.sha256.len());
+        pin.verify(&manifest("1.2.3", "abc")).unwrap();
+        pin.verify(&manifest("1.2.4", "abc")).unwrap_err();
+        pin.verify(&manifest("1.2.3", "def")).unwrap_err();
+    }
+
+    #[test]
+    fn lockfile_is_found_in_ancestors_and_round_trips() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(PluginsLockfile::find(&nested).is_none());
+
+        let path = temp_dir.path().join(PLUGINS_LOCK_FILE_NAME);
+        let mut lockfile = PluginsLockfile::default();
+        lockfile.pin(&manifest("1.2.3", "abc"));
+        lockfile.save(&path).unwrap();
+
+        assert_eq!(Some(path.clone()), PluginsLockfile::find(&nested));
+        let mut reloaded = PluginsLockfile::load(&path).unwrap();
+        assert_eq!(lockfile.plugins(), reloaded.plugins());
+        assert!(reloaded.unpin("pinned"));
+        assert!(reloaded.get("pinned").is_none());
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{error::*, git::GitSource, manifest::PluginManifest, store::manifest_file_name};
+use crate::{
+    error::*,
+    git::GitSource,
+    manifest::PluginManifest,
+    repos::{PluginRepositories, PluginRepository},
+    store::manifest_file_name,
+};
 use semver::Version;
 use std::{
     fs::File,
@@ -15,10 +21,13 @@ pub(crate) const PLUGINS_REPO_MANIFESTS_DIRECTORY: &str = "manifests";
 
 pub(crate) const SPIN_PLUGINS_REPO: &str = "https://github.com/fermyon/spin-plugins/";
 
-/// Looks up plugin manifests in centralized spin plugin repository.
+/// Looks up plugin manifests in the registered plugins repositories, in
+/// priority order. By default this is only the centralized spin plugin repository.
 pub struct PluginLookup {
     pub name: String,
     pub version: Option<Version>,
+    /// If set, only the repository with this name is searched.
+    pub repository: Option<String>,
 }
 
 impl PluginLookup {
@@ -26,16 +35,23 @@ impl PluginLookup {
         Self {
             name: name.to_lowercase(),
             version,
+            repository: None,
         }
     }
 
+    /// Restricts the lookup to the named repository.
+    pub fn in_repository(mut self, repository: Option<String>) -> Self {
+        self.repository = repository;
+        self
+    }
+
     pub async fn resolve_manifest(
         &self,
         plugins_dir: &Path,
         skip_compatibility_check: bool,
         spin_version: &str,
     ) -> PluginLookupResult<PluginManifest> {
-        let exact = self.resolve_manifest_exact(plugins_dir).await?;
+        let (exact, repository) = self.resolve_manifest_exact_with_source(plugins_dir).await?;
         if skip_compatibility_check
             || self.version.is_some()
             || exact.is_compatible_spin_version(spin_version)
@@ -45,8 +61,10 @@ impl PluginLookup {
 
         let store = crate::store::PluginStore::new(plugins_dir.to_owned());
 
+        // Only consider other versions from the repository in which the plugin
+        // was found, so that a lower priority repository cannot shadow it.
         // TODO: This is very similar to some logic in the badger module - look for consolidation opportunities.
-        let manifests = store.catalogue_manifests()?;
+        let manifests = store.catalogue_manifests_in(&repository)?;
         let relevant_manifests = manifests.into_iter().filter(|m| m.name() == self.name);
         let compatible_manifests = relevant_manifests
             .filter(|m| m.has_compatible_package() && m.is_compatible_spin_version(spin_version));
@@ -60,25 +78,76 @@ impl PluginLookup {
         &self,
         plugins_dir: &Path,
     ) -> PluginLookupResult<PluginManifest> {
-        let url = plugins_repo_url()?;
-        tracing::info!("Pulling manifest for plugin {} from {url}", self.name);
-        fetch_plugins_repo(&url, plugins_dir, false)
-            .await
-            .map_err(|e| {
-                Error::ConnectionFailed(ConnectionFailedError::new(url.to_string(), e.to_string()))
-            })?;
+        let (manifest, _) = self.resolve_manifest_exact_with_source(plugins_dir).await?;
+        Ok(manifest)
+    }
+
+    /// Finds the manifest in the highest priority repository that contains
+    /// the plugin, returning the manifest and that repository.
+    pub async fn resolve_manifest_exact_with_source(
+        &self,
+        plugins_dir: &Path,
+    ) -> PluginLookupResult<(PluginManifest, PluginRepository)> {
+        let repositories = self.repositories_to_search(plugins_dir)?;
+
+        let mut first_error = None;
+        for repository in repositories {
+            tracing::info!(
+                "Pulling manifest for plugin {} from {}",
+                self.name,
+                repository.source
+            );
+            // An existing checkout is used as is, so this only fails if the
+            // repository has never been fetched. Falling through to a lower
+            // priority repository would let it serve a plugin that the
+            // unavailable repository is meant to provide.
+            if let Err(e) = repository.fetch(plugins_dir, false).await {
+                return Err(Error::ConnectionFailed(ConnectionFailedError::new(
+                    repository.source.to_string(),
+                    format!(
+                        "plugins repository '{}' is unavailable: {e:#}",
+                        repository.name
+                    ),
+                )));
+            }
+
+            let manifests_dir = repository.manifests_dir(plugins_dir)?;
+            match self.resolve_manifest_exact_from_manifests_dir(&manifests_dir) {
+                Ok(manifest) => return Ok((manifest, repository)),
+                Err(e @ Error::NotFound(_)) => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(first_error.unwrap_or_else(|| {
+            Error::NotFound(NotFoundError::new(
+                Some(self.name.clone()),
+                self.repository.clone().unwrap_or_default(),
+                "no such plugins repository".to_owned(),
+            ))
+        }))
+    }
 
-        self.resolve_manifest_exact_from_good_repo(plugins_dir)
+    /// Reads the manifest from an already-fetched centralized plugins repository.
+    pub fn resolve_manifest_exact_from_good_repo(
+        &self,
+        plugins_dir: &Path,
+    ) -> PluginLookupResult<PluginManifest> {
+        self.resolve_manifest_exact_from_manifests_dir(&spin_plugins_repo_manifest_dir(plugins_dir))
     }
 
     // This is split from resolve_manifest_exact because it may recurse (once) and that makes
     // Rust async sad. So we move the potential recursion to a sync helper.
     #[allow(clippy::let_and_return)]
-    pub fn resolve_manifest_exact_from_good_repo(
+    fn resolve_manifest_exact_from_manifests_dir(
         &self,
-        plugins_dir: &Path,
+        manifests_dir: &Path,
     ) -> PluginLookupResult<PluginManifest> {
-        let expected_path = spin_plugins_repo_manifest_path(&self.name, &self.version, plugins_dir);
+        let expected_path = manifests_dir
+            .join(&self.name)
+            .join(manifest_file_name_version(&self.name, &self.version));
 
         let not_found = |e: std::io::Error| {
             Err(Error::NotFound(NotFoundError::new(
@@ -100,7 +169,7 @@ impl PluginLookup {
                 // If a user has asked for a version by number, and the path doesn't exist,
                 // it _might_ be because it's the latest version. This checks for that case.
                 let latest = Self::new(&self.name, None);
-                match latest.resolve_manifest_exact_from_good_repo(plugins_dir) {
+                match latest.resolve_manifest_exact_from_manifests_dir(manifests_dir) {
                     Ok(manifest) if manifest.try_version().ok() == self.version => Ok(manifest),
                     _ => not_found(e),
                 }
@@ -110,6 +179,20 @@ impl PluginLookup {
 
         manifest
     }
+
+    fn repositories_to_search(
+        &self,
+        plugins_dir: &Path,
+    ) -> PluginLookupResult<Vec<PluginRepository>> {
+        let repositories = PluginRepositories::load(plugins_dir)?.in_priority_order();
+        Ok(match &self.repository {
+            Some(name) => repositories
+                .into_iter()
+                .filter(|r| &r.name == name)
+                .collect(),
+            None => repositories,
+        })
+    }
 }
 
 pub fn plugins_repo_url() -> Result<Url, url::ParseError> {
@@ -131,9 +214,18 @@ pub async fn fetch_plugins_repo(
     plugins_dir: &Path,
     update: bool,
 ) -> anyhow::Result<()> {
-    let git_root = plugin_manifests_repo_path(plugins_dir);
-    let git_source = GitSource::new(repo_url, None, &git_root);
-    if accept_as_repo(&git_root) {
+    fetch_git_repo(repo_url, &plugin_manifests_repo_path(plugins_dir), update).await
+}
+
+/// Clones the git repository at `repo_url` into `git_root`, or updates an
+/// existing clone if `update` is true.
+pub(crate) async fn fetch_git_repo(
+    repo_url: &Url,
+    git_root: &Path,
+    update: bool,
+) -> anyhow::Result<()> {
+    let git_source = GitSource::new(repo_url, None, git_root);
+    if accept_as_repo(git_root) {
         if update {
             git_source.pull().await?;
         }
@@ -143,7 +235,7 @@ pub async fn fetch_plugins_repo(
     Ok(())
 }
 
-fn plugin_manifests_repo_path(plugins_dir: &Path) -> PathBuf {
+pub(crate) fn plugin_manifests_repo_path(plugins_dir: &Path) -> PathBuf {
     plugins_dir.join(PLUGINS_REPO_LOCAL_DIRECTORY)
 }
 
@@ -155,18 +247,6 @@ fn manifest_file_name_version(plugin_name: &str, version: &Option<semver::Versio
     }
 }
 
-/// Get expected path to the manifest of a plugin with a given name
-/// and version within the spin-plugins repository
-fn spin_plugins_repo_manifest_path(
-    plugin_name: &str,
-    plugin_version: &Option<Version>,
-    plugins_dir: &Path,
-) -> PathBuf {
-    spin_plugins_repo_manifest_dir(plugins_dir)
-        .join(plugin_name)
-        .join(manifest_file_name_version(plugin_name, plugin_version))
-}
-
 pub fn spin_plugins_repo_manifest_dir(plugins_dir: &Path) -> PathBuf {
     plugins_dir
         .join(PLUGINS_REPO_LOCAL_DIRECTORY)
@@ -251,4 +331,69 @@ mod tests {
             .expect_err("Should have errored because plugin v177.7.7 does not exist");
         Ok(())
     }
+
+    fn write_manifest(repo_root: &Path, version: &str) {
+        let dir = repo_root
+            .join(PLUGINS_REPO_MANIFESTS_DIRECTORY)
+            .join("multi");
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = serde_json::json!({
+            "name": "multi",
+            "version": version,
+            "spinCompatibility": ">= 0.1",
+            "license": "Apache-2.0",
+            "packages": []
+        });
+        std::fs::write(dir.join("multi.json"), manifest.to_string()).unwrap();
+    }
+
+    fn add_local_repository(plugins_dir: &Path, name: &str, priority: i32) -> PathBuf {
+        let root = plugins_dir.join(format!("{name}-index"));
+        let mut repositories = PluginRepositories::load(plugins_dir).unwrap();
+        repositories
+            .add(PluginRepository {
+                name: name.to_owned(),
+                source: crate::repos::PluginRepositorySource::Local { path: root.clone() },
+                priority,
+            })
+            .unwrap();
+        repositories.save(plugins_dir).unwrap();
+        root
+    }
+
+    #[tokio::test]
+    async fn highest_priority_repository_wins() -> PluginLookupResult<()> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plugins_dir = temp_dir.path();
+        write_manifest(&add_local_repository(plugins_dir, "low", 10), "1.0.0");
+        write_manifest(&add_local_repository(plugins_dir, "high", 20), "2.0.0");
+
+        let lookup = PluginLookup::new("multi", None);
+        let (resolved, repository) = lookup
+            .resolve_manifest_exact_with_source(plugins_dir)
+            .await?;
+        assert_eq!("2.0.0", resolved.version);
+        assert_eq!("high", repository.name);
+
+        let lookup = PluginLookup::new("multi", None).in_repository(Some("low".to_owned()));
+        let resolved = lookup.resolve_manifest_exact(plugins_dir).await?;
+        assert_eq!("1.0.0", resolved.version);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unavailable_repository_does_not_fall_through_to_lower_priority() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plugins_dir = temp_dir.path();
+        write_manifest(&add_local_repository(plugins_dir, "public", 10), "1.0.0");
+        // The higher priority repository's directory is never created
+        add_local_repository(plugins_dir, "internal", 20);
+
+        let lookup = PluginLookup::new("multi", None);
+        let err = lookup
+            .resolve_manifest_exact_with_source(plugins_dir)
+            .await
+            .expect_err("should not have resolved from the lower priority repository");
+        assert!(matches!(err, Error::ConnectionFailed(_)), "{err:?}");
+    }
 }
@@ -2,6 +2,7 @@ use crate::{
     error::*,
     lookup::PluginLookup,
     manifest::{warn_unsupported_version, PluginManifest, PluginPackage},
+    signature::{SignatureVerification, TrustedPublishers},
     store::PluginStore,
     SPIN_INTERNAL_COMMANDS,
 };
@@ -9,7 +10,7 @@ use crate::{
 use anyhow::{anyhow, bail, Context, Result};
 use path_absolutize::Absolutize;
 use reqwest::{header::HeaderMap, Client};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use spin_common::sha256;
 use std::{
     fs::{self, File},
@@ -28,12 +29,12 @@ pub enum ManifestLocation {
     Local(PathBuf),
     /// Plugin manifest should be pulled from a specific address.
     Remote(Url),
-    /// Plugin manifest lives in the centralized plugins repository
+    /// Plugin manifest lives in one of the registered plugins repositories
     PluginsRepository(PluginLookup),
 }
 
 impl ManifestLocation {
-    pub(crate) fn to_install_record(&self) -> RawInstallRecord {
+    pub(crate) fn to_install_record(&self, repository: Option<String>) -> RawInstallRecord {
         match self {
             Self::Local(path) => {
                 // Plugin commands don't absolutise on the way in, so do it now.
@@ -47,17 +48,26 @@ impl ManifestLocation {
             Self::Remote(url) => RawInstallRecord::Remote {
                 url: url.to_owned(),
             },
-            Self::PluginsRepository(_) => RawInstallRecord::PluginsRepository,
+            Self::PluginsRepository(_) => RawInstallRecord::PluginsRepository { repository },
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename = "snake_case", tag = "source")]
 pub(crate) enum RawInstallRecord {
-    PluginsRepository,
-    Remote { url: Url },
-    Local { file: PathBuf },
+    PluginsRepository {
+        /// The plugins repository containing the manifest. This is absent
+        /// for plugins installed before multiple repositories were supported.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        repository: Option<String>,
+    },
+    Remote {
+        url: Url,
+    },
+    Local {
+        file: PathBuf,
+    },
 }
 
 /// Provides accesses to functionality to inspect and manage the installation of plugins.
@@ -81,7 +91,8 @@ impl PluginManager {
     /// Spin plugins repository, it fetches the latest contents of the repository and searches for
     /// the appropriately named and versioned plugin manifest. Parses the plugin manifest to get the
     /// appropriate source for the machine OS and architecture. Verifies the checksum of the source,
-    /// unpacks and installs it into the plugins directory.
+    /// unpacks and installs it into the plugins directory. If any publishers are trusted, the manifest
+    /// must be signed by one of them (see [`TrustedPublishers`]).
     /// Returns name of plugin that was successfully installed.
     pub async fn install(
         &self,
@@ -90,6 +101,63 @@ impl PluginManager {
         source: &ManifestLocation,
         auth_header_value: &Option<String>,
     ) -> Result<String> {
+        self.check_signature(plugin_manifest)?;
+        self.install_into(
+            &self.store,
+            plugin_manifest,
+            plugin_package,
+            auth_header_value,
+        )
+        .await?;
+        self.write_install_record(plugin_manifest, source);
+
+        Ok(plugin_manifest.name())
+    }
+
+    /// Installs a specific version of a plugin, as pinned by a project, alongside
+    /// the globally installed plugins (see [`PluginStore::pinned_store`]). The
+    /// signature and checksum are verified in the same way as for [`Self::install`].
+    /// Returns the store into which the plugin was installed.
+    pub async fn install_pinned(
+        &self,
+        plugin_manifest: &PluginManifest,
+        plugin_package: &PluginPackage,
+        auth_header_value: &Option<String>,
+    ) -> Result<PluginStore> {
+        self.check_signature(plugin_manifest)?;
+        let pinned_store = self
+            .store
+            .pinned_store(&plugin_manifest.name(), plugin_manifest.version());
+        self.install_into(
+            &pinned_store,
+            plugin_manifest,
+            plugin_package,
+            auth_header_value,
+        )
+        .await?;
+        Ok(pinned_store)
+    }
+
+    fn check_signature(&self, plugin_manifest: &PluginManifest) -> Result<()> {
+        match self.verify_signature(plugin_manifest)? {
+            SignatureVerification::Verified { publisher } => {
+                tracing::info!("Manifest signature from '{publisher}' verified successfully")
+            }
+            SignatureVerification::Unsigned => tracing::info!(
+                "Manifest for plugin '{}' is not signed",
+                plugin_manifest.name()
+            ),
+        }
+        Ok(())
+    }
+
+    async fn install_into(
+        &self,
+        store: &PluginStore,
+        plugin_manifest: &PluginManifest,
+        plugin_package: &PluginPackage,
+        auth_header_value: &Option<String>,
+    ) -> Result<()> {
         let target = plugin_package.url.to_owned();
         let target_url = Url::parse(&target)?;
         let temp_dir = tempdir()?;
@@ -119,15 +187,22 @@ impl PluginManager {
         };
         verify_checksum(&plugin_tarball_path, &plugin_package.sha256)?;
 
-        self.store
+        store
             .untar_plugin(&plugin_tarball_path, &plugin_manifest.name())
             .with_context(|| format!("Failed to untar {}", plugin_tarball_path.display()))?;
 
         // Save manifest to installed plugins directory
-        self.store.add_manifest(plugin_manifest)?;
-        self.write_install_record(&plugin_manifest.name(), source);
+        store.add_manifest(plugin_manifest)?;
+        Ok(())
+    }
 
-        Ok(plugin_manifest.name())
+    /// Verifies the publisher signature on a plugin manifest against the
+    /// trusted publishers configured for this manager's store.
+    pub fn verify_signature(
+        &self,
+        plugin_manifest: &PluginManifest,
+    ) -> Result<SignatureVerification> {
+        TrustedPublishers::load(&self.store)?.verify(plugin_manifest)
     }
 
     /// Uninstalls a plugin with a given name, removing it and it's manifest from the local plugins
@@ -272,11 +347,21 @@ impl PluginManager {
         Ok(locker)
     }
 
-    fn write_install_record(&self, plugin_name: &str, source: &ManifestLocation) {
-        let install_record_path = self.store.installation_record_file(plugin_name);
+    fn write_install_record(&self, plugin_manifest: &PluginManifest, source: &ManifestLocation) {
+        let install_record_path = self.store.installation_record_file(&plugin_manifest.name());
+
+        // Record which repository the plugin came from, so that upgrades
+        // come from the same repository.
+        let repository = match source {
+            ManifestLocation::PluginsRepository(_) => self
+                .store
+                .repository_containing(plugin_manifest)
+                .map(|r| r.name),
+            _ => None,
+        };
 
         // A failure here shouldn't fail the install
-        let install_record = source.to_install_record();
+        let install_record = source.to_install_record(repository);
         if let Ok(record_text) = serde_json::to_string_pretty(&install_record) {
             _ = std::fs::write(install_record_path, record_text);
         }
@@ -2,16 +2,20 @@ use std::io::IsTerminal;
 
 use anyhow::{anyhow, Context, Result};
 use semver::{Version, VersionReq};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use url::Url;
 
-use crate::PluginStore;
+use crate::{signature::ManifestSignature, PluginStore};
 
 /// Expected schema of a plugin manifest. Should match the latest Spin plugin
 /// manifest JSON schema:
 /// https://github.com/fermyon/spin-plugins/tree/main/json-schema
-#[derive(Serialize, Debug, Deserialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
+///
+/// A deserialized manifest keeps the JSON it was read from, which is what its
+/// signature covers and what is written when the manifest is serialized, so
+/// that fields unknown to this version of Spin are neither lost nor unsigned.
+#[derive(Serialize, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", remote = "Self")]
 pub struct PluginManifest {
     /// Name of the plugin.
     name: String,
@@ -29,6 +33,45 @@ pub struct PluginManifest {
     license: String,
     /// Points to source package[s] of the plugin..
     pub(crate) packages: Vec<PluginPackage>,
+    /// Publisher signature over the rest of the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) signature: Option<ManifestSignature>,
+    /// The JSON the manifest was deserialized from.
+    #[serde(skip)]
+    raw: Option<serde_json::Value>,
+}
+
+impl<'de> Deserialize<'de> for PluginManifest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        let mut manifest = PluginManifest::deserialize(&raw).map_err(serde::de::Error::custom)?;
+        manifest.raw = Some(raw);
+        Ok(manifest)
+    }
+}
+
+impl Serialize for PluginManifest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.raw {
+            Some(raw) => raw.serialize(serializer),
+            None => PluginManifest::serialize(self, serializer),
+        }
+    }
+}
+
+// Manifests are compared by their parsed fields only: the same manifest may be
+// read from differently formatted JSON, or constructed without any.
+impl PartialEq for PluginManifest {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.description == other.description
+            && self.homepage == other.homepage
+            && self.version == other.version
+            && self.spin_compatibility == other.spin_compatibility
+            && self.license == other.license
+            && self.packages == other.packages
+            && self.signature == other.signature
+    }
 }
 
 impl PluginManifest {
@@ -70,6 +113,44 @@ impl PluginManifest {
     pub fn try_version(&self) -> Result<semver::Version, semver::Error> {
         semver::Version::parse(&self.version)
     }
+
+    pub fn signature(&self) -> Option<&ManifestSignature> {
+        self.signature.as_ref()
+    }
+
+    /// The bytes covered by the manifest signature: the canonical JSON of
+    /// every field except `signature`, with keys sorted and no whitespace.
+    ///
+    /// This is computed from the JSON the manifest was read from, so it
+    /// includes any fields which this version of Spin does not know about.
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("signature");
+        }
+        Ok(serde_json::to_vec(&canonicalize(value))?)
+    }
+}
+
+/// Sorts the keys of every object in the value, whether or not the JSON map
+/// type preserves insertion order.
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => {
+            let mut fields = fields.into_iter().collect::<Vec<_>>();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(canonicalize).collect())
+        }
+        value => value,
+    }
 }
 
 /// Describes compatibility and location of a plugin source.
@@ -298,6 +379,20 @@ mod test {
         assert_eq!(deserialized_plugin.packages.len(), 3);
     }
 
+    #[test]
+    fn equality_ignores_unknown_fields() {
+        let manifest = generate_test_manifest("name", "0.1.1", "Mit", None, None);
+        let mut json = serde_json::to_value(&manifest).unwrap();
+        json.as_object_mut()
+            .unwrap()
+            .insert("futureField".to_string(), serde_json::json!(true));
+        let extended: PluginManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(manifest, extended);
+
+        let upgraded = generate_test_manifest("name", "0.1.2", "Mit", None, None);
+        assert_ne!(manifest, upgraded);
+    }
+
     #[test]
     fn test_plugin_json_empty_options() {
         let deserialized_plugin = generate_test_manifest("name", "0.1.1", "Mit", None, None);
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::lookup::{
+    fetch_git_repo, plugin_manifests_repo_path, plugins_repo_url, PLUGINS_REPO_MANIFESTS_DIRECTORY,
+};
+
+/// Name of the centralized Spin plugins repository, which is always registered.
+pub const DEFAULT_REPOSITORY_NAME: &str = "spin-plugins";
+
+/// Priority of the centralized Spin plugins repository.
+const DEFAULT_REPOSITORY_PRIORITY: i32 = 0;
+
+/// Priority given to additional repositories if none is specified, so that
+/// they are searched before the centralized repository.
+pub const DEFAULT_ADDITIONAL_REPOSITORY_PRIORITY: i32 = 10;
+
+/// Name of the file in the plugins directory that lists additional repositories.
+const REPOSITORIES_FILE_NAME: &str = "repositories.json";
+
+/// Name of the directory containing clones of additional git repositories.
+const ADDITIONAL_REPOS_LOCAL_DIRECTORY: &str = ".spin-plugins-repos";
+
+/// Checks that a repository name is a plain identifier: ASCII letters, digits,
+/// `-` and `_`. Names are used as directory names within the plugins directory,
+/// so they must not contain path separators or be `.` or `..`.
+pub fn validate_repository_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Plugins repository name must not be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Invalid plugins repository name '{name}': names may contain only ASCII letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+/// Where the contents of a plugins repository come from.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum PluginRepositorySource {
+    /// A git repository, which is cloned into the plugins directory.
+    Git { url: Url },
+    /// A directory on the local file system.
+    Local { path: PathBuf },
+}
+
+impl std::fmt::Display for PluginRepositorySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Git { url } => write!(f, "{url}"),
+            Self::Local { path } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A repository of plugin manifests (a plugins index). Repositories have the
+/// same layout as the centralized Spin plugins repository: a `manifests`
+/// directory containing a subdirectory per plugin.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRepository {
+    /// Name by which the repository is managed.
+    pub name: String,
+    /// Where the repository contents come from.
+    pub source: PluginRepositorySource,
+    /// Repositories with higher priority are searched first.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl PluginRepository {
+    /// The centralized Spin plugins repository.
+    pub fn default_repository() -> Self {
+        Self {
+            name: DEFAULT_REPOSITORY_NAME.to_owned(),
+            source: PluginRepositorySource::Git {
+                url: plugins_repo_url().expect("default plugins repository URL should be valid"),
+            },
+            priority: DEFAULT_REPOSITORY_PRIORITY,
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        self.name == DEFAULT_REPOSITORY_NAME
+    }
+
+    /// The local directory containing the repository contents. This fails if
+    /// the repository name is not valid, as could happen if the repositories
+    /// file was edited by hand.
+    pub fn root(&self, plugins_dir: &Path) -> Result<PathBuf> {
+        Ok(match &self.source {
+            PluginRepositorySource::Local { path } => path.clone(),
+            // The centralized repository keeps its historical location so that
+            // existing clones continue to be used.
+            PluginRepositorySource::Git { .. } if self.is_default() => {
+                plugin_manifests_repo_path(plugins_dir)
+            }
+            PluginRepositorySource::Git { .. } => {
+                validate_repository_name(&self.name)?;
+                plugins_dir
+                    .join(ADDITIONAL_REPOS_LOCAL_DIRECTORY)
+                    .join(&self.name)
+            }
+        })
+    }
+
+    /// The local directory containing the repository's plugin manifests.
+    pub fn manifests_dir(&self, plugins_dir: &Path) -> Result<PathBuf> {
+        Ok(self
+            .root(plugins_dir)?
+            .join(PLUGINS_REPO_MANIFESTS_DIRECTORY))
+    }
+
+    /// Ensures the repository contents are available locally, cloning git
+    /// repositories if needed. If `update` is true, existing clones are
+    /// updated to the latest contents.
+    pub async fn fetch(&self, plugins_dir: &Path, update: bool) -> Result<()> {
+        match &self.source {
+            PluginRepositorySource::Git { url } => {
+                fetch_git_repo(url, &self.root(plugins_dir)?, update).await
+            }
+            PluginRepositorySource::Local { path } => {
+                if path.is_dir() {
+                    Ok(())
+                } else {
+                    bail!(
+                        "Plugins repository '{}' directory {} does not exist",
+                        self.name,
+                        path.display()
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// The plugins repositories registered in a plugins directory. This always
+/// includes the centralized Spin plugins repository.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PluginRepositories {
+    /// Additional repositories, not including the centralized repository.
+    #[serde(default)]
+    repositories: Vec<PluginRepository>,
+}
+
+impl PluginRepositories {
+    /// Loads the repositories registered in the given plugins directory.
+    pub fn load(plugins_dir: &Path) -> Result<Self> {
+        let path = plugins_dir.join(REPOSITORIES_FILE_NAME);
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid plugin repositories file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!(e)).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Saves the repositories to the given plugins directory.
+    pub fn save(&self, plugins_dir: &Path) -> Result<()> {
+        let path = plugins_dir.join(REPOSITORIES_FILE_NAME);
+        std::fs::create_dir_all(plugins_dir)?;
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Registers an additional repository.
+    pub fn add(&mut self, repository: PluginRepository) -> Result<()> {
+        validate_repository_name(&repository.name)?;
+        if self.get(&repository.name).is_some() {
+            bail!(
+                "A plugins repository named '{}' already exists",
+                repository.name
+            );
+        }
+        self.repositories.push(repository);
+        Ok(())
+    }
+
+    /// Unregisters an additional repository. Returns whether the repository
+    /// was registered. The centralized repository cannot be removed.
+    pub fn remove(&mut self, name: &str) -> Result<bool> {
+        if name == DEFAULT_REPOSITORY_NAME {
+            bail!("The '{DEFAULT_REPOSITORY_NAME}' repository cannot be removed");
+        }
+        let count = self.repositories.len();
+        self.repositories.retain(|r| r.name != name);
+        Ok(self.repositories.len() != count)
+    }
+
+    /// Gets a repository by name.
+    pub fn get(&self, name: &str) -> Option<PluginRepository> {
+        self.in_priority_order()
+            .into_iter()
+            .find(|r| r.name == name)
+    }
+
+    /// All repositories, in the order in which they should be searched:
+    /// highest priority first, and in order of registration for equal priorities.
+    pub fn in_priority_order(&self) -> Vec<PluginRepository> {
+        let mut all = self.repositories.clone();
+        all.push(PluginRepository::default_repository());
+        // Stable sort preserves registration order within a priority.
+        all.sort_by_key(|r| std::cmp::Reverse(r.priority));
+        all
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn local(name: &str, priority: i32) -> PluginRepository {
+        PluginRepository {
+            name: name.to_owned(),
+            source: PluginRepositorySource::Local {
+                path: PathBuf::from(format!("/repos/{name}")),
+            },
+            priority,
+        }
+    }
+
+    #[test]
+    fn repositories_are_ordered_by_priority() {
+        let mut repos = PluginRepositories::default();
+        repos.add(local("low", -5)).unwrap();
+        repos.add(local("high", 20)).unwrap();
+        repos.add(local("also-high", 20)).unwrap();
+
+        let names: Vec<_> = repos
+            .in_priority_order()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(
+            vec!["high", "also-high", DEFAULT_REPOSITORY_NAME, "low"],
+            names
+        );
+    }
+
+    #[test]
+    fn cannot_add_duplicate_or_remove_default() {
+        let mut repos = PluginRepositories::default();
+        repos.add(local("internal", 10)).unwrap();
+        repos.add(local("internal", 5)).unwrap_err();
+        repos.add(local(DEFAULT_REPOSITORY_NAME, 5)).unwrap_err();
+        repos.remove(DEFAULT_REPOSITORY_NAME).unwrap_err();
+        assert!(repos.remove("internal").unwrap());
+        assert!(!repos.remove("internal").unwrap());
+    }
+
+    #[test]
+    fn repository_names_must_be_plain_identifiers() {
+        let mut repos = PluginRepositories::default();
+        for name in ["", ".", "..", "../escape", "a/b", "a\\b", "/abs"] {
+            repos.add(local(name, 10)).unwrap_err();
+        }
+        repos.add(local("internal_repo-2", 10)).unwrap();
+
+        let traversal = PluginRepository {
+            name: "..".to_owned(),
+            source: PluginRepositorySource::Git {
+                url: Url::parse("https://example.com/plugins.git").unwrap(),
+            },
+            priority: 10,
+        };
+        traversal.root(Path::new("/plugins")).unwrap_err();
+    }
+
+    #[test]
+    fn repositories_round_trip_through_plugins_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut repos = PluginRepositories::load(temp_dir.path()).unwrap();
+        assert_eq!(1, repos.in_priority_order().len());
+
+        repos.add(local("internal", 10)).unwrap();
+        repos.save(temp_dir.path()).unwrap();
+
+        let reloaded = PluginRepositories::load(temp_dir.path()).unwrap();
+        assert_eq!(Some(local("internal", 10)), reloaded.get("internal"));
+    }
+}
@@ -0,0 +1,320 @@
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::{manifest::PluginManifest, PluginStore};
+
+/// Name of the file in the plugins directory that lists trusted publishers.
+const TRUSTED_PUBLISHERS_FILE_NAME: &str = "trusted-publishers.json";
+
+/// A publisher's signature over a plugin manifest.
+///
+/// The signature is an Ed25519 signature over the manifest's canonical JSON
+/// form: all fields other than `signature`, with object keys sorted and no
+/// insignificant whitespace. See [`PluginManifest::signing_payload`].
+#[derive(Clone, Serialize, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSignature {
+    /// Name of the publisher whose key signed the manifest.
+    pub publisher: String,
+    /// Base64-encoded Ed25519 signature.
+    pub signature: String,
+}
+
+/// A publisher whose plugin manifests are trusted.
+#[derive(Clone, Serialize, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPublisher {
+    /// Name of the publisher, matched against [`ManifestSignature::publisher`].
+    pub name: String,
+    /// Base64-encoded Ed25519 public key.
+    pub public_key: String,
+}
+
+/// The outcome of verifying a plugin manifest's signature.
+#[derive(Debug, PartialEq)]
+pub enum SignatureVerification {
+    /// The manifest was signed by a trusted publisher.
+    Verified { publisher: String },
+    /// The manifest is not signed.
+    Unsigned,
+}
+
+/// The set of publishers trusted to sign plugin manifests, stored in the
+/// plugins directory.
+///
+/// Once any publisher is trusted, every plugin manifest must be signed by a
+/// trusted publisher. Otherwise an index which has been tampered with could
+/// simply drop the signature from a manifest.
+#[derive(Serialize, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPublishers {
+    /// Publishers whose signatures are accepted.
+    #[serde(default)]
+    publishers: Vec<TrustedPublisher>,
+}
+
+impl TrustedPublishers {
+    /// Loads the trusted publishers for the given store. If none have been
+    /// configured, this is empty and does not require signatures.
+    pub fn load(store: &PluginStore) -> Result<Self> {
+        let path = Self::path(store);
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid trusted publishers file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!(e)).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Saves the trusted publishers to the given store.
+    pub fn save(&self, store: &PluginStore) -> Result<()> {
+        let path = Self::path(store);
+        std::fs::create_dir_all(store.get_plugins_directory())?;
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The trusted publishers.
+    pub fn publishers(&self) -> &[TrustedPublisher] {
+        &self.publishers
+    }
+
+    /// Whether plugin manifests must be signed, which is the case once any
+    /// publisher is trusted.
+    pub fn signatures_required(&self) -> bool {
+        !self.publishers.is_empty()
+    }
+
+    /// Trusts a publisher's key, replacing any existing key for that publisher.
+    pub fn add(&mut self, name: &str, public_key: &str) -> Result<()> {
+        let key_bytes = decode_base64(public_key).context("Invalid public key")?;
+        if key_bytes.len() != ED25519_PUBLIC_KEY_LEN {
+            bail!("Invalid public key: expected a {ED25519_PUBLIC_KEY_LEN}-byte Ed25519 key");
+        }
+        self.remove(name);
+        self.publishers.push(TrustedPublisher {
+            name: name.to_owned(),
+            public_key: public_key.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Stops trusting a publisher. Returns whether the publisher was trusted.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.publishers.len();
+        self.publishers.retain(|p| p.name != name);
+        self.publishers.len() != count
+    }
+
+    /// Verifies the signature on a plugin manifest. This fails if the manifest
+    /// is signed by an untrusted publisher, if the signature does not match, or
+    /// if the manifest is unsigned and signatures are required (see
+    /// [`Self::signatures_required`]).
+    pub fn verify(&self, manifest: &PluginManifest) -> Result<SignatureVerification> {
+        let Some(signature) = manifest.signature() else {
+            if self.signatures_required() {
+                bail!(
+                    "Plugin '{}' manifest is not signed, but signatures are required because trusted publishers are configured",
+                    manifest.name()
+                );
+            }
+            return Ok(SignatureVerification::Unsigned);
+        };
+
+        let publisher = self
+            .publishers
+            .iter()
+            .find(|p| p.name == signature.publisher)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Plugin '{}' manifest is signed by '{}', who is not a trusted publisher",
+                    manifest.name(),
+                    signature.publisher
+                )
+            })?;
+        let public_key = decode_base64(&publisher.public_key)
+            .with_context(|| format!("Invalid public key for publisher '{}'", publisher.name))?;
+        let signature_bytes =
+            decode_base64(&signature.signature).context("Invalid manifest signature")?;
+        let payload = manifest.signing_payload()?;
+
+        ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+            .verify(&payload, &signature_bytes)
+            .map_err(|_| {
+                anyhow!(
+                    "Plugin '{}' manifest signature is not valid for publisher '{}'. The manifest may have been tampered with.",
+                    manifest.name(),
+                    publisher.name
+                )
+            })?;
+
+        Ok(SignatureVerification::Verified {
+            publisher: publisher.name.clone(),
+        })
+    }
+
+    fn path(store: &PluginStore) -> std::path::PathBuf {
+        trusted_publishers_file(store.get_plugins_directory())
+    }
+}
+
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+fn trusted_publishers_file(plugins_dir: &Path) -> std::path::PathBuf {
+    plugins_dir.join(TRUSTED_PUBLISHERS_FILE_NAME)
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>> {
+    Ok(base64::engine::general_purpose::STANDARD.decode(text.trim())?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    fn key_pair() -> ring::signature::Ed25519KeyPair {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn manifest_json(version: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": "signed",
+            "version": version,
+            "spinCompatibility": ">= 0.1",
+            "license": "Apache-2.0",
+            "packages": []
+        })
+    }
+
+    fn manifest(version: &str) -> PluginManifest {
+        serde_json::from_value(manifest_json(version)).unwrap()
+    }
+
+    /// Signs the given manifest JSON, returning the signed JSON.
+    fn sign(
+        mut json: serde_json::Value,
+        key_pair: &ring::signature::Ed25519KeyPair,
+        publisher: &str,
+    ) -> serde_json::Value {
+        let manifest: PluginManifest = serde_json::from_value(json.clone()).unwrap();
+        let signature = key_pair.sign(&manifest.signing_payload().unwrap());
+        json["signature"] = serde_json::to_value(ManifestSignature {
+            publisher: publisher.to_owned(),
+            signature: encode(signature.as_ref()),
+        })
+        .unwrap();
+        json
+    }
+
+    fn signed_manifest(
+        key_pair: &ring::signature::Ed25519KeyPair,
+        publisher: &str,
+    ) -> PluginManifest {
+        serde_json::from_value(sign(manifest_json("1.0.0"), key_pair, publisher)).unwrap()
+    }
+
+    fn trusting(name: &str, key_pair: &ring::signature::Ed25519KeyPair) -> TrustedPublishers {
+        let mut trusted = TrustedPublishers::default();
+        trusted
+            .add(name, &encode(key_pair.public_key().as_ref()))
+            .unwrap();
+        trusted
+    }
+
+    #[test]
+    fn signature_from_trusted_publisher_is_verified() {
+        let key_pair = key_pair();
+        let trusted = trusting("fermyon", &key_pair);
+        let verification = trusted
+            .verify(&signed_manifest(&key_pair, "fermyon"))
+            .unwrap();
+        assert_eq!(
+            SignatureVerification::Verified {
+                publisher: "fermyon".to_owned()
+            },
+            verification
+        );
+    }
+
+    #[test]
+    fn tampered_manifest_is_rejected() {
+        let key_pair = key_pair();
+        let trusted = trusting("fermyon", &key_pair);
+        let mut json = sign(manifest_json("1.0.0"), &key_pair, "fermyon");
+        json["version"] = "6.6.6".into();
+        let manifest: PluginManifest = serde_json::from_value(json).unwrap();
+        trusted.verify(&manifest).unwrap_err();
+    }
+
+    #[test]
+    fn unknown_fields_are_signed() {
+        let key_pair = key_pair();
+        let trusted = trusting("fermyon", &key_pair);
+        let mut json = manifest_json("1.0.0");
+        json["futureField"] = serde_json::json!({ "b": 1, "a": [true] });
+        let mut json = sign(json, &key_pair, "fermyon");
+
+        let manifest: PluginManifest = serde_json::from_value(json.clone()).unwrap();
+        trusted.verify(&manifest).unwrap();
+        // The unknown field survives being written to and read from the store
+        let manifest: PluginManifest =
+            serde_json::from_slice(&serde_json::to_vec(&manifest).unwrap()).unwrap();
+        trusted.verify(&manifest).unwrap();
+
+        json["futureField"]["b"] = 2.into();
+        let manifest: PluginManifest = serde_json::from_value(json).unwrap();
+        trusted.verify(&manifest).unwrap_err();
+    }
+
+    #[test]
+    fn signature_from_untrusted_publisher_is_rejected() {
+        let trusted = trusting("fermyon", &key_pair());
+        trusted
+            .verify(&signed_manifest(&key_pair(), "mallory"))
+            .unwrap_err();
+        trusted
+            .verify(&signed_manifest(&key_pair(), "fermyon"))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn unsigned_manifest_is_rejected_once_a_publisher_is_trusted() {
+        let mut trusted = TrustedPublishers::default();
+        assert_eq!(
+            SignatureVerification::Unsigned,
+            trusted.verify(&manifest("1.0.0")).unwrap()
+        );
+        trusted
+            .add("fermyon", &encode(key_pair().public_key().as_ref()))
+            .unwrap();
+        trusted.verify(&manifest("1.0.0")).unwrap_err();
+    }
+
+    #[test]
+    fn trusted_publishers_round_trip_through_store() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = PluginStore::new(temp_dir.path());
+        assert!(TrustedPublishers::load(&store)
+            .unwrap()
+            .publishers()
+            .is_empty());
+
+        let key_pair = key_pair();
+        trusting("fermyon", &key_pair).save(&store).unwrap();
+
+        let mut loaded = TrustedPublishers::load(&store).unwrap();
+        assert_eq!(1, loaded.publishers().len());
+        assert!(loaded.remove("fermyon"));
+        assert!(!loaded.remove("fermyon"));
+    }
+}
@@ -8,11 +8,18 @@ use std::{
 };
 use tar::Archive;
 
-use crate::{error::*, manifest::PluginManifest};
+use crate::{
+    error::*,
+    manager::RawInstallRecord,
+    manifest::PluginManifest,
+    repos::{PluginRepositories, PluginRepository},
+};
 
 /// Directory where the manifests of installed plugins are stored.
 pub const PLUGIN_MANIFESTS_DIRECTORY_NAME: &str = "manifests";
 const INSTALLATION_RECORD_FILE_NAME: &str = ".install.json";
+/// Directory where plugin versions pinned by projects are installed.
+const PINNED_PLUGINS_DIRECTORY_NAME: &str = ".pinned";
 
 /// Houses utilities for getting the path to Spin plugin directories.
 pub struct PluginStore {
@@ -57,6 +64,17 @@ impl PluginStore {
         binary
     }
 
+    /// Gets the store in which a specific version of a plugin, pinned by a
+    /// project, is installed. This is separate from the globally installed
+    /// plugins so that different versions can be installed side by side.
+    pub fn pinned_store(&self, plugin_name: &str, version: &str) -> PluginStore {
+        Self::new(
+            self.root
+                .join(PINNED_PLUGINS_DIRECTORY_NAME)
+                .join(format!("{plugin_name}@{version}")),
+        )
+    }
+
     pub fn installation_record_file(&self, plugin_name: &str) -> PathBuf {
         self.root
             .join(plugin_name)
@@ -73,18 +91,41 @@ impl PluginStore {
         Ok(manifests)
     }
 
+    /// The manifests in all registered plugins repositories. If the same
+    /// version of a plugin is in more than one repository, only the manifest
+    /// from the highest priority repository is included.
     // TODO: report errors on individuals
     pub fn catalogue_manifests(&self) -> Result<Vec<PluginManifest>> {
+        let repositories = PluginRepositories::load(self.get_plugins_directory())?;
+        let mut manifests: Vec<PluginManifest> = vec![];
+        for repository in repositories.in_priority_order() {
+            for manifest in self.catalogue_manifests_in(&repository)? {
+                let shadowed = manifests
+                    .iter()
+                    .any(|m| m.name() == manifest.name() && m.version == manifest.version);
+                if !shadowed {
+                    manifests.push(manifest);
+                }
+            }
+        }
+        Ok(manifests)
+    }
+
+    /// The manifests in a single plugins repository.
+    // TODO: report errors on individuals
+    pub fn catalogue_manifests_in(
+        &self,
+        repository: &PluginRepository,
+    ) -> Result<Vec<PluginManifest>> {
         // Structure:
-        // CATALOGUE_DIR (spin/plugins/.spin-plugins/manifests)
+        // CATALOGUE_DIR (e.g. spin/plugins/.spin-plugins/manifests)
         // |- foo
         // |  |- foo@0.1.2.json
         // |  |- foo@1.2.3.json
         // |  |- foo.json
         // |- bar
         //    |- bar.json
-        let catalogue_dir =
-            crate::lookup::spin_plugins_repo_manifest_dir(self.get_plugins_directory());
+        let catalogue_dir = repository.manifests_dir(self.get_plugins_directory())?;
 
         // Catalogue directory doesn't exist so likely nothing has been installed.
         if !catalogue_dir.exists() {
@@ -93,7 +134,7 @@ impl PluginStore {
 
         let plugin_dirs = catalogue_dir
             .read_dir()
-            .context("reading manifest catalogue at {catalogue_dir:?}")?
+            .with_context(|| format!("reading manifest catalogue at {catalogue_dir:?}"))?
             .filter_map(|d| d.ok())
             .map(|d| d.path())
             .filter(|p| p.is_dir());
@@ -104,6 +145,29 @@ impl PluginStore {
         Ok(manifests)
     }
 
+    /// Finds the highest priority plugins repository containing the given manifest.
+    pub(crate) fn repository_containing(
+        &self,
+        manifest: &PluginManifest,
+    ) -> Option<PluginRepository> {
+        let repositories = PluginRepositories::load(self.get_plugins_directory()).ok()?;
+        repositories.in_priority_order().into_iter().find(|r| {
+            self.catalogue_manifests_in(r)
+                .is_ok_and(|manifests| manifests.contains(manifest))
+        })
+    }
+
+    /// The name of the plugins repository from which an installed plugin was
+    /// installed, if it was installed from a repository and the repository was
+    /// recorded.
+    pub fn installed_from_repository(&self, plugin_name: &str) -> Option<String> {
+        let record_text = fs::read_to_string(self.installation_record_file(plugin_name)).ok()?;
+        match serde_json::from_str(&record_text).ok()? {
+            RawInstallRecord::PluginsRepository { repository } => repository,
+            _ => None,
+        }
+    }
+
     fn try_read_manifest_from(manifest_path: &Path) -> Option<PluginManifest> {
         let manifest_file = File::open(manifest_path).ok()?;
         serde_json::from_reader(manifest_file).ok()
@@ -21,7 +21,7 @@ use spin_factors::{
 };
 use spin_key_value_spin::{SpinKeyValueRuntimeConfig, SpinKeyValueStore};
 use spin_sqlite as sqlite;
-use spin_trigger::cli::UserProvidedPath;
+use spin_trigger::cli::{ComponentLogsConfig, UserProvidedPath};
 use toml::Value;
 
 /// The default state directory for the trigger.
@@ -30,6 +30,7 @@ pub const DEFAULT_STATE_DIR: &str = ".spin";
 /// A runtime configuration which has been resolved from a runtime config source.
 ///
 /// Includes other pieces of configuration that are used to resolve the runtime configuration.
+#[derive(Clone)]
 pub struct ResolvedRuntimeConfig<T> {
     /// The resolved runtime configuration.
     pub runtime_config: T,
@@ -45,6 +46,8 @@ pub struct ResolvedRuntimeConfig<T> {
     ///
     /// `None` is used for an "unset" log directory.
     pub log_dir: Option<PathBuf>,
+    /// How component stdio log files are written and rotated.
+    pub component_logs: ComponentLogsConfig,
     /// The input TOML, for informational summaries.
     pub toml: toml::Table,
 }
@@ -149,6 +152,7 @@ where
             sqlite_resolver: sqlite_config_resolver,
             state_dir: toml_resolver.state_dir()?,
             log_dir: toml_resolver.log_dir()?,
+            component_logs: toml_resolver.component_logs()?,
             toml: toml_resolver.toml(),
         })
     }
@@ -162,6 +166,11 @@ where
     pub fn log_dir(&self) -> Option<PathBuf> {
         self.log_dir.clone()
     }
+
+    /// The component stdio log file configuration.
+    pub fn component_logs(&self) -> ComponentLogsConfig {
+        self.component_logs.clone()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -256,6 +265,18 @@ impl<'a> TomlResolver<'a> {
         }
     }
 
+    /// Get the component stdio log file configuration from the
+    /// `[component_logs]` table.
+    pub fn component_logs(&self) -> anyhow::Result<ComponentLogsConfig> {
+        let Some(table) = self.table.get("component_logs") else {
+            return Ok(Default::default());
+        };
+        table
+            .clone()
+            .try_into()
+            .context("failed to parse [component_logs] runtime config")
+    }
+
     /// Validate that all keys in the TOML file have been used.
     pub fn validate_all_keys_used(&self) -> spin_factors::Result<()> {
         self.table.validate_all_keys_used()
@@ -370,6 +391,8 @@ impl FactorRuntimeConfigSource<SqliteFactor> for TomlRuntimeConfigSource<'_, '_>
 
 impl RuntimeConfigSourceFinalizer for TomlRuntimeConfigSource<'_, '_> {
     fn finalize(&mut self) -> anyhow::Result<()> {
+        // Not used by any factor, but validated here so it isn't reported as unused
+        self.toml.component_logs()?;
         Ok(self.toml.validate_all_keys_used()?)
     }
 }
@@ -579,6 +602,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn component_logs_are_configured_correctly() -> anyhow::Result<()> {
+        let toml = toml::toml! {
+            [component_logs]
+            format = "json"
+            max_file_age_secs = 3600
+        };
+        let config = toml_resolver(&toml).component_logs()?;
+        assert_eq!(config.format, spin_trigger::cli::ComponentLogFormat::Json);
+        assert_eq!(config.max_file_age_secs, Some(3600));
+        assert_eq!(config.max_file_size, None);
+
+        let toml = toml::toml! {
+            [component_logs]
+            format = "xml"
+        };
+        assert!(toml_resolver(&toml).component_logs().is_err());
+        Ok(())
+    }
+
     fn toml_resolver(toml: &toml::Table) -> TomlResolver<'_> {
         TomlResolver::new(
             toml,
@@ -49,6 +49,7 @@ impl RuntimeFactorsBuilder for FactorsBuilder {
         executor.add_hooks(StdioLoggingExecutorHooks::new(
             config.follow_components.clone(),
             runtime_config.log_dir(),
+            runtime_config.component_logs(),
         ));
         executor.add_hooks(SqlStatementExecutorHook::new(
             args.sqlite_statements.clone(),
@@ -108,6 +108,26 @@ impl From<ResolvedRuntimeConfig<TriggerFactorsRuntimeConfig>> for TriggerFactors
     }
 }
 
+// Cloned for each app loaded when the application is reloaded, so that the
+// reloaded app shares state such as in-memory key-value stores.
+impl Clone for TriggerFactorsRuntimeConfig {
+    fn clone(&self) -> Self {
+        Self {
+            wasi: self.wasi.clone(),
+            variables: self.variables.clone(),
+            key_value: self.key_value.clone(),
+            outbound_networking: self.outbound_networking.clone(),
+            outbound_http: self.outbound_http.clone(),
+            sqlite: self.sqlite.clone(),
+            redis: self.redis.clone(),
+            mqtt: self.mqtt.clone(),
+            pg: self.pg.clone(),
+            mysql: self.mysql.clone(),
+            llm: self.llm.clone(),
+        }
+    }
+}
+
 impl TryFrom<TomlRuntimeConfigSource<'_, '_>> for TriggerFactorsRuntimeConfig {
     type Error = anyhow::Error;
 
@@ -11,6 +11,12 @@ const OTEL_EXPORTER_OTLP_TRACES_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_TRACES_PROT
 const OTEL_EXPORTER_OTLP_METRICS_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_METRICS_PROTOCOL";
 const OTEL_EXPORTER_OTLP_LOGS_PROTOCOL: &str = "OTEL_EXPORTER_OTLP_LOGS_PROTOCOL";
 const SPIN_DISABLE_LOG_TO_TRACING: &str = "SPIN_DISABLE_LOG_TO_TRACING";
+pub(crate) const SPIN_PROMETHEUS_LISTEN_ADDR: &str = "SPIN_PROMETHEUS_LISTEN_ADDR";
+const SPIN_TRACE_CONTEXT_ENVELOPE: &str = "SPIN_TRACE_CONTEXT_ENVELOPE";
+const SPIN_OTEL_TRACING_DISABLED_SPANS: &str = "SPIN_OTEL_TRACING_DISABLED_SPANS";
+const SPIN_OTEL_TRACING_REDACTED_ATTRIBUTES: &str = "SPIN_OTEL_TRACING_REDACTED_ATTRIBUTES";
+const SPIN_OTEL_TRACING_REDACTED_QUERY_ATTRIBUTES: &str =
+    "SPIN_OTEL_TRACING_REDACTED_QUERY_ATTRIBUTES";
 
 /// Returns a boolean indicating if the OTEL tracing layer should be enabled.
 ///
@@ -63,6 +69,60 @@ pub(crate) fn spin_disable_log_to_tracing() -> bool {
     any_vars_set(&[SPIN_DISABLE_LOG_TO_TRACING])
 }
 
+/// Returns the address on which to serve metrics for Prometheus, if set.
+///
+/// It is set by the environment variable `SPIN_PROMETHEUS_LISTEN_ADDR`, if it is not empty.
+pub(crate) fn prometheus_listen_addr() -> Option<String> {
+    std::env::var(SPIN_PROMETHEUS_LISTEN_ADDR)
+        .ok()
+        .filter(|addr| !addr.is_empty())
+}
+
+/// Returns a boolean indicating if trace context should be wrapped around message payloads for
+/// messaging systems without message headers.
+///
+/// It is enabled by setting the environment variable `SPIN_TRACE_CONTEXT_ENVELOPE`.
+pub(crate) fn trace_context_envelope_enabled() -> bool {
+    any_vars_set(&[SPIN_TRACE_CONTEXT_ENVELOPE])
+}
+
+/// Returns the names of spans which should not be sent to the OTEL collector.
+///
+/// They are set by the comma-separated environment variable `SPIN_OTEL_TRACING_DISABLED_SPANS`. A
+/// name disables both the span with that name and the spans whose names start with it followed by
+/// a `.`, e.g. `spin_key_value` disables `spin_key_value.get` and all other key-value spans.
+pub(crate) fn otel_tracing_disabled_spans() -> Vec<String> {
+    list_var(SPIN_OTEL_TRACING_DISABLED_SPANS)
+}
+
+/// Returns the names of span attributes whose values should be redacted before spans are sent to
+/// the OTEL collector.
+///
+/// They are set by the comma-separated environment variable
+/// `SPIN_OTEL_TRACING_REDACTED_ATTRIBUTES`.
+pub(crate) fn otel_tracing_redacted_attributes() -> Vec<String> {
+    list_var(SPIN_OTEL_TRACING_REDACTED_ATTRIBUTES)
+}
+
+/// Returns the names of URL-valued span attributes, e.g. `url.full`, whose query strings should be
+/// redacted before spans are sent to the OTEL collector. The rest of the URL is kept.
+///
+/// They are set by the comma-separated environment variable
+/// `SPIN_OTEL_TRACING_REDACTED_QUERY_ATTRIBUTES`.
+pub(crate) fn otel_tracing_redacted_query_attributes() -> Vec<String> {
+    list_var(SPIN_OTEL_TRACING_REDACTED_QUERY_ATTRIBUTES)
+}
+
+fn list_var(var: &str) -> Vec<String> {
+    std::env::var(var)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
 fn any_vars_set(enabling_vars: &[&str]) -> bool {
     enabling_vars
         .iter()
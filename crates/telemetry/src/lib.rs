@@ -6,6 +6,7 @@ use std::time::Instant;
 use env::otel_logs_enabled;
 use env::otel_metrics_enabled;
 use env::otel_tracing_enabled;
+use env::prometheus_listen_addr;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use tracing_subscriber::{fmt, prelude::*, registry, EnvFilter, Layer};
 
@@ -13,11 +14,16 @@ pub mod detector;
 mod env;
 pub mod logs;
 pub mod metrics;
+pub mod prometheus;
 mod propagation;
+pub mod redaction;
 mod traces;
 
+pub use propagation::current_trace_id;
 pub use propagation::extract_trace_context;
 pub use propagation::inject_trace_context;
+pub use propagation::unwrap_payload_trace_context;
+pub use propagation::wrap_payload_with_trace_context;
 
 /// Initializes telemetry for Spin using the [tracing] library.
 ///
@@ -76,8 +82,14 @@ pub fn init(spin_version: String) -> anyhow::Result<ShutdownGuard> {
         None
     };
 
-    let otel_metrics_layer = if otel_metrics_enabled() {
-        Some(metrics::otel_metrics_layer(spin_version.clone())?)
+    let prometheus_reader =
+        prometheus_listen_addr().map(|_| prometheus::PrometheusReader::install());
+    let otel_metrics_layer = if otel_metrics_enabled() || prometheus_reader.is_some() {
+        Some(metrics::otel_metrics_layer(
+            spin_version.clone(),
+            otel_metrics_enabled(),
+            prometheus_reader,
+        )?)
     } else {
         None
     };
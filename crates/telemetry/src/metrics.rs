@@ -15,15 +15,18 @@ use tracing::Subscriber;
 use tracing_opentelemetry::MetricsLayer;
 use tracing_subscriber::{registry::LookupSpan, Layer};
 
-use crate::{detector::SpinResourceDetector, env::OtlpProtocol};
+use crate::{detector::SpinResourceDetector, env::OtlpProtocol, prometheus::PrometheusReader};
 
-/// Constructs a layer for the tracing subscriber that sends metrics to an OTEL collector.
+/// Constructs a layer for the tracing subscriber that sends metrics to an OTEL collector, if
+/// `otlp_enabled`, and makes them available to Prometheus, if a `prometheus_reader` is given.
 ///
 /// It pulls OTEL configuration from the environment based on the variables defined
 /// [here](https://opentelemetry.io/docs/specs/otel/protocol/exporter/) and
 /// [here](https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#general-sdk-configuration).
 pub(crate) fn otel_metrics_layer<S: Subscriber + for<'span> LookupSpan<'span>>(
     spin_version: String,
+    otlp_enabled: bool,
+    prometheus_reader: Option<PrometheusReader>,
 ) -> Result<impl Layer<S>> {
     let resource = Resource::from_detectors(
         Duration::from_secs(5),
@@ -38,25 +41,31 @@ pub(crate) fn otel_metrics_layer<S: Subscriber + for<'span> LookupSpan<'span>>(
         ],
     );
 
-    // This will configure the exporter based on the OTEL_EXPORTER_* environment variables. We
-    // currently default to using the HTTP exporter but in the future we could select off of the
-    // combination of OTEL_EXPORTER_OTLP_PROTOCOL and OTEL_EXPORTER_OTLP_TRACES_PROTOCOL to
-    // determine whether we should use http/protobuf or grpc.
-    let exporter_builder: MetricsExporterBuilder = match OtlpProtocol::metrics_protocol_from_env() {
-        OtlpProtocol::Grpc => opentelemetry_otlp::new_exporter().tonic().into(),
-        OtlpProtocol::HttpProtobuf => opentelemetry_otlp::new_exporter().http().into(),
-        OtlpProtocol::HttpJson => bail!("http/json OTLP protocol is not supported"),
-    };
-    let exporter = exporter_builder.build_metrics_exporter(
-        Box::new(DefaultTemporalitySelector::new()),
-        Box::new(DefaultAggregationSelector::new()),
-    )?;
+    let mut builder = SdkMeterProvider::builder().with_resource(resource);
 
-    let reader = PeriodicReader::builder(exporter, runtime::Tokio).build();
-    let meter_provider = SdkMeterProvider::builder()
-        .with_reader(reader)
-        .with_resource(resource)
-        .build();
+    if otlp_enabled {
+        // This will configure the exporter based on the OTEL_EXPORTER_* environment variables. We
+        // currently default to using the HTTP exporter but in the future we could select off of the
+        // combination of OTEL_EXPORTER_OTLP_PROTOCOL and OTEL_EXPORTER_OTLP_TRACES_PROTOCOL to
+        // determine whether we should use http/protobuf or grpc.
+        let exporter_builder: MetricsExporterBuilder =
+            match OtlpProtocol::metrics_protocol_from_env() {
+                OtlpProtocol::Grpc => opentelemetry_otlp::new_exporter().tonic().into(),
+                OtlpProtocol::HttpProtobuf => opentelemetry_otlp::new_exporter().http().into(),
+                OtlpProtocol::HttpJson => bail!("http/json OTLP protocol is not supported"),
+            };
+        let exporter = exporter_builder.build_metrics_exporter(
+            Box::new(DefaultTemporalitySelector::new()),
+            Box::new(DefaultAggregationSelector::new()),
+        )?;
+        builder = builder.with_reader(PeriodicReader::builder(exporter, runtime::Tokio).build());
+    }
+
+    if let Some(prometheus_reader) = prometheus_reader {
+        builder = builder.with_reader(prometheus_reader);
+    }
+
+    let meter_provider = builder.build();
 
     global::set_meter_provider(meter_provider.clone());
 
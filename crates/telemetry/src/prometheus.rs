@@ -0,0 +1,354 @@
+//! Exposes metrics for scraping by Prometheus.
+
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    fmt::{Display, Write},
+    net::SocketAddr,
+    sync::{Arc, OnceLock, Weak},
+};
+
+use anyhow::Context;
+use http_body_util::Full;
+use hyper::{
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+    Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::{
+    metrics::{
+        data::{Gauge, Histogram, Metric, ResourceMetrics, Sum, Temporality},
+        reader::{AggregationSelector, MetricReader, TemporalitySelector},
+        Aggregation, InstrumentKind, ManualReader, Pipeline,
+    },
+    Resource,
+};
+use tokio::net::TcpListener;
+
+use crate::env::prometheus_listen_addr;
+
+/// The environment variable which sets the address to serve Prometheus
+/// metrics on.
+pub use crate::env::SPIN_PROMETHEUS_LISTEN_ADDR;
+
+static READER: OnceLock<PrometheusReader> = OnceLock::new();
+
+/// Returns the address to serve Prometheus metrics on, if enabled.
+///
+/// It is enabled by setting the environment variable `SPIN_PROMETHEUS_LISTEN_ADDR`.
+/// Each trigger process serves its own metrics, so when `spin up` runs an app
+/// with several trigger types it gives each trigger the following port.
+pub fn listen_addr() -> anyhow::Result<Option<SocketAddr>> {
+    let Some(addr) = prometheus_listen_addr() else {
+        return Ok(None);
+    };
+    let addr = addr
+        .parse()
+        .with_context(|| format!("Invalid Prometheus listen address '{addr}'"))?;
+    Ok(Some(addr))
+}
+
+/// Serves metrics in the Prometheus text format at `/metrics` on the given
+/// address.
+///
+/// Metrics must have been enabled when telemetry was initialized.
+pub async fn serve(listen_addr: SocketAddr) -> anyhow::Result<()> {
+    let reader = READER
+        .get()
+        .context("Prometheus metrics were not enabled when telemetry was initialized")?
+        .clone();
+    let listener = TcpListener::bind(listen_addr).await.with_context(|| {
+        format!("Unable to listen on {listen_addr} for Prometheus metrics scrapes")
+    })?;
+    tracing::info!("Serving Prometheus metrics on http://{listen_addr}/metrics");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let reader = reader.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request: Request<Incoming>| {
+                let response = scrape_response(&reader, &request);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            if let Err(err) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!(?err, "Error serving Prometheus scrape");
+            }
+        });
+    }
+}
+
+fn scrape_response(
+    reader: &PrometheusReader,
+    request: &Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    let (status, body) = if request.uri().path() != "/metrics" {
+        (StatusCode::NOT_FOUND, String::new())
+    } else {
+        match reader.encode() {
+            Ok(text) => (StatusCode::OK, text),
+            Err(err) => {
+                tracing::warn!(?err, "Failed to collect metrics for Prometheus");
+                (StatusCode::INTERNAL_SERVER_ERROR, String::new())
+            }
+        }
+    };
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    response
+}
+
+/// A [`MetricReader`] which collects metrics when Prometheus scrapes them.
+#[derive(Clone, Debug)]
+pub(crate) struct PrometheusReader {
+    reader: Arc<ManualReader>,
+}
+
+impl PrometheusReader {
+    /// Creates the reader to add to the meter provider, and keeps a handle to
+    /// it for [`serve`].
+    pub(crate) fn install() -> Self {
+        READER
+            .get_or_init(|| Self {
+                reader: Arc::new(ManualReader::builder().build()),
+            })
+            .clone()
+    }
+
+    /// Collects current metrics in the Prometheus text format.
+    fn encode(&self) -> opentelemetry::metrics::Result<String> {
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: vec![],
+        };
+        self.reader.collect(&mut metrics)?;
+        Ok(encode(&metrics))
+    }
+}
+
+impl TemporalitySelector for PrometheusReader {
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.reader.temporality(kind)
+    }
+}
+
+impl AggregationSelector for PrometheusReader {
+    fn aggregation(&self, kind: InstrumentKind) -> Aggregation {
+        self.reader.aggregation(kind)
+    }
+}
+
+impl MetricReader for PrometheusReader {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.reader.register_pipeline(pipeline)
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> opentelemetry::metrics::Result<()> {
+        self.reader.collect(rm)
+    }
+
+    fn force_flush(&self) -> opentelemetry::metrics::Result<()> {
+        self.reader.force_flush()
+    }
+
+    fn shutdown(&self) -> opentelemetry::metrics::Result<()> {
+        self.reader.shutdown()
+    }
+}
+
+/// Encodes metrics in the Prometheus text format.
+fn encode(metrics: &ResourceMetrics) -> String {
+    let mut encoder = Encoder::default();
+    for scope_metrics in &metrics.scope_metrics {
+        for metric in &scope_metrics.metrics {
+            encoder.encode_metric(metric);
+        }
+    }
+    encoder.out
+}
+
+#[derive(Default)]
+struct Encoder {
+    out: String,
+    /// Metrics whose `# TYPE` line has been written.
+    described: HashSet<String>,
+}
+
+impl Encoder {
+    fn encode_metric(&mut self, metric: &Metric) {
+        let name = sanitize(&metric.name);
+        let data = metric.data.as_any();
+        if let Some(sum) = data.downcast_ref::<Sum<u64>>() {
+            self.encode_sum(&name, metric, sum);
+        } else if let Some(sum) = data.downcast_ref::<Sum<i64>>() {
+            self.encode_sum(&name, metric, sum);
+        } else if let Some(sum) = data.downcast_ref::<Sum<f64>>() {
+            self.encode_sum(&name, metric, sum);
+        } else if let Some(gauge) = data.downcast_ref::<Gauge<u64>>() {
+            self.encode_gauge(&name, metric, gauge);
+        } else if let Some(gauge) = data.downcast_ref::<Gauge<i64>>() {
+            self.encode_gauge(&name, metric, gauge);
+        } else if let Some(gauge) = data.downcast_ref::<Gauge<f64>>() {
+            self.encode_gauge(&name, metric, gauge);
+        } else if let Some(histogram) = data.downcast_ref::<Histogram<u64>>() {
+            self.encode_histogram(&name, metric, histogram);
+        } else if let Some(histogram) = data.downcast_ref::<Histogram<i64>>() {
+            self.encode_histogram(&name, metric, histogram);
+        } else if let Some(histogram) = data.downcast_ref::<Histogram<f64>>() {
+            self.encode_histogram(&name, metric, histogram);
+        }
+    }
+
+    fn encode_sum<T: Display>(&mut self, name: &str, metric: &Metric, sum: &Sum<T>) {
+        let (name, kind) = if sum.is_monotonic {
+            (format!("{name}_total"), "counter")
+        } else {
+            (name.to_owned(), "gauge")
+        };
+        self.describe(&name, kind, metric);
+        for point in &sum.data_points {
+            self.sample(&name, &point.attributes, None, &point.value);
+        }
+    }
+
+    fn encode_gauge<T: Display>(&mut self, name: &str, metric: &Metric, gauge: &Gauge<T>) {
+        self.describe(name, "gauge", metric);
+        for point in &gauge.data_points {
+            self.sample(name, &point.attributes, None, &point.value);
+        }
+    }
+
+    fn encode_histogram<T: Display>(
+        &mut self,
+        name: &str,
+        metric: &Metric,
+        histogram: &Histogram<T>,
+    ) {
+        self.describe(name, "histogram", metric);
+        for point in &histogram.data_points {
+            let mut cumulative_count = 0;
+            for (i, count) in point.bucket_counts.iter().enumerate() {
+                cumulative_count += count;
+                let le = match point.bounds.get(i) {
+                    Some(bound) => bound.to_string(),
+                    None => "+Inf".to_owned(),
+                };
+                self.sample(
+                    &format!("{name}_bucket"),
+                    &point.attributes,
+                    Some(("le", &le)),
+                    &cumulative_count,
+                );
+            }
+            self.sample(&format!("{name}_sum"), &point.attributes, None, &point.sum);
+            self.sample(
+                &format!("{name}_count"),
+                &point.attributes,
+                None,
+                &point.count,
+            );
+        }
+    }
+
+    fn describe(&mut self, name: &str, kind: &str, metric: &Metric) {
+        if !self.described.insert(name.to_owned()) {
+            return;
+        }
+        if !metric.description.is_empty() {
+            let help = metric.description.replace('\\', r"\\").replace('\n', r"\n");
+            _ = writeln!(self.out, "# HELP {name} {help}");
+        }
+        _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    fn sample(
+        &mut self,
+        name: &str,
+        attributes: &[KeyValue],
+        extra_label: Option<(&str, &str)>,
+        value: &dyn Display,
+    ) {
+        let labels = attributes
+            .iter()
+            .map(|kv| (sanitize(kv.key.as_str()), kv.value.as_str().into_owned()))
+            .chain(extra_label.map(|(key, value)| (key.to_owned(), value.to_owned())))
+            .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(&value)))
+            .collect::<Vec<_>>();
+        if labels.is_empty() {
+            _ = writeln!(self.out, "{name} {value}");
+        } else {
+            _ = writeln!(self.out, "{name}{{{}}} {value}", labels.join(","));
+        }
+    }
+}
+
+/// Replaces characters which aren't valid in Prometheus metric and label
+/// names, e.g. `spin.request_count` becomes `spin_request_count`.
+fn sanitize(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+    use super::*;
+
+    #[test]
+    fn encodes_counters_and_histograms() {
+        let reader = PrometheusReader {
+            reader: Arc::new(ManualReader::builder().build()),
+        };
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        let meter = provider.meter("test");
+
+        let attributes = [KeyValue::new("component_id", "hello \"world\"")];
+        let counter = meter.u64_counter("spin.request_count").init();
+        counter.add(2, &attributes);
+        let histogram = meter.f64_histogram("spin.request_duration").init();
+        histogram.record(0.5, &attributes);
+
+        let text = reader.encode().unwrap();
+        assert!(text.contains("# TYPE spin_request_count_total counter\n"));
+        assert!(text.contains("spin_request_count_total{component_id=\"hello \\\"world\\\"\"} 2\n"));
+        assert!(text.contains("# TYPE spin_request_duration histogram\n"));
+        assert!(text.contains(
+            "spin_request_duration_bucket{component_id=\"hello \\\"world\\\"\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(
+            text.contains("spin_request_duration_count{component_id=\"hello \\\"world\\\"\"} 1\n")
+        );
+    }
+
+    #[test]
+    fn sanitizes_names() {
+        assert_eq!(sanitize("spin.kv-op_count"), "spin_kv_op_count");
+        assert_eq!(sanitize("1st"), "_1st");
+    }
+}
@@ -1,9 +1,15 @@
 use opentelemetry::{
     global,
     propagation::{Extractor, Injector},
+    trace::TraceContextExt,
 };
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use crate::env::trace_context_envelope_enabled;
+
+/// Marks the start of a message payload wrapped in a trace context envelope.
+const ENVELOPE_PREFIX: &[u8] = b"\0spin-trace-context\n";
+
 /// Injects the current W3C TraceContext into the provided request.
 pub fn inject_trace_context<'a>(req: impl Into<HeaderInjector<'a>>) {
     let mut injector = req.into();
@@ -22,9 +28,80 @@ pub fn extract_trace_context<'a>(req: impl Into<HeaderExtractor<'a>>) {
     tracing::Span::current().set_parent(parent_context);
 }
 
+/// Wraps a message payload in an envelope carrying the current W3C TraceContext, for messaging
+/// systems without message headers such as Redis pub/sub.
+///
+/// Subscribers which don't expect the envelope would see it as part of the payload, so the payload
+/// is only wrapped if `SPIN_TRACE_CONTEXT_ENVELOPE` is set and there is a current trace.
+///
+/// The envelope is `\0spin-trace-context\n`, followed by a `name: value\n` line for each trace
+/// context field, an empty line and then the original payload.
+pub fn wrap_payload_with_trace_context(payload: Vec<u8>) -> Vec<u8> {
+    if !trace_context_envelope_enabled() {
+        return payload;
+    }
+    let mut fields: Vec<(String, String)> = vec![];
+    inject_trace_context(&mut fields);
+    if fields.is_empty() {
+        return payload;
+    }
+    write_envelope(&fields, &payload)
+}
+
+/// If the message payload is wrapped in a trace context envelope, sets the W3C TraceContext it
+/// carries as the parent of the current span and returns the original payload. Otherwise the
+/// payload is returned as is.
+pub fn unwrap_payload_trace_context(payload: &[u8]) -> &[u8] {
+    match parse_envelope(payload) {
+        Some((fields, payload)) => {
+            extract_trace_context(&fields);
+            payload
+        }
+        None => payload,
+    }
+}
+
+fn write_envelope(fields: &[(String, String)], payload: &[u8]) -> Vec<u8> {
+    let mut envelope = ENVELOPE_PREFIX.to_vec();
+    for (name, value) in fields {
+        envelope.extend_from_slice(format!("{name}: {value}\n").as_bytes());
+    }
+    envelope.push(b'\n');
+    envelope.extend_from_slice(payload);
+    envelope
+}
+
+fn parse_envelope(payload: &[u8]) -> Option<(Vec<(String, String)>, &[u8])> {
+    let mut rest = payload.strip_prefix(ENVELOPE_PREFIX)?;
+    let mut fields = vec![];
+    loop {
+        let line_end = rest.iter().position(|&b| b == b'\n')?;
+        let line = std::str::from_utf8(&rest[..line_end]).ok()?;
+        rest = &rest[line_end + 1..];
+        if line.is_empty() {
+            return Some((fields, rest));
+        }
+        let (name, value) = line.split_once(": ")?;
+        fields.push((name.to_owned(), value.to_owned()));
+    }
+}
+
+/// Returns the ID of the trace the current span belongs to, if any, as a hex string.
+pub fn current_trace_id() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span_context = context.span().span_context().clone();
+    span_context
+        .is_valid()
+        .then(|| span_context.trace_id().to_string())
+}
+
+/// Where trace context is injected, e.g. HTTP headers.
+#[non_exhaustive]
 pub enum HeaderInjector<'a> {
     Http0(&'a mut http0::HeaderMap),
     Http1(&'a mut http1::HeaderMap),
+    /// Name-value pairs such as MQTT v5 user properties.
+    Properties(&'a mut Vec<(String, String)>),
 }
 
 impl<'a> Injector for HeaderInjector<'a> {
@@ -44,6 +121,10 @@ impl<'a> Injector for HeaderInjector<'a> {
                     }
                 }
             }
+            HeaderInjector::Properties(properties) => {
+                properties.retain(|(name, _)| name != key);
+                properties.push((key.to_owned(), value));
+            }
         }
     }
 }
@@ -72,9 +153,19 @@ impl<'a> From<&'a mut http1::HeaderMap> for HeaderInjector<'a> {
     }
 }
 
+impl<'a> From<&'a mut Vec<(String, String)>> for HeaderInjector<'a> {
+    fn from(properties: &'a mut Vec<(String, String)>) -> Self {
+        Self::Properties(properties)
+    }
+}
+
+/// Where trace context is extracted from, e.g. HTTP headers.
+#[non_exhaustive]
 pub enum HeaderExtractor<'a> {
     Http0(&'a http0::HeaderMap),
     Http1(&'a http1::HeaderMap),
+    /// Name-value pairs such as MQTT v5 user properties.
+    Properties(&'a [(String, String)]),
 }
 
 impl<'a> Extractor for HeaderExtractor<'a> {
@@ -86,6 +177,10 @@ impl<'a> Extractor for HeaderExtractor<'a> {
             HeaderExtractor::Http1(headers) => {
                 headers.get(key).map(|v| v.to_str().unwrap_or_default())
             }
+            HeaderExtractor::Properties(properties) => properties
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
         }
     }
 
@@ -93,6 +188,9 @@ impl<'a> Extractor for HeaderExtractor<'a> {
         match self {
             HeaderExtractor::Http0(headers) => headers.keys().map(|k| k.as_str()).collect(),
             HeaderExtractor::Http1(headers) => headers.keys().map(|k| k.as_str()).collect(),
+            HeaderExtractor::Properties(properties) => {
+                properties.iter().map(|(name, _)| name.as_str()).collect()
+            }
         }
     }
 }
@@ -108,3 +206,40 @@ impl<'a, T> From<&'a http1::Request<T>> for HeaderExtractor<'a> {
         Self::Http1(req.headers())
     }
 }
+
+impl<'a> From<&'a Vec<(String, String)>> for HeaderExtractor<'a> {
+    fn from(properties: &'a Vec<(String, String)>) -> Self {
+        Self::Properties(properties)
+    }
+}
+
+impl<'a> From<&'a [(String, String)]> for HeaderExtractor<'a> {
+    fn from(properties: &'a [(String, String)]) -> Self {
+        Self::Properties(properties)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_round_trips() {
+        let fields = vec![(
+            "traceparent".to_owned(),
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_owned(),
+        )];
+        let envelope = write_envelope(&fields, b"hello\n\nworld");
+        let (parsed_fields, payload) = parse_envelope(&envelope).unwrap();
+        assert_eq!(parsed_fields, fields);
+        assert_eq!(payload, b"hello\n\nworld");
+    }
+
+    #[test]
+    fn payload_without_envelope_is_unchanged() {
+        assert!(parse_envelope(b"hello").is_none());
+        // A truncated envelope is treated as a plain payload
+        assert!(parse_envelope(b"\0spin-trace-context\ntraceparent: 00").is_none());
+        assert_eq!(unwrap_payload_trace_context(b"hello"), b"hello");
+    }
+}
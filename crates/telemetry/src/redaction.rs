@@ -0,0 +1,220 @@
+//! Redacts sensitive span attributes before spans are exported.
+
+use std::borrow::Cow;
+
+use opentelemetry::{trace::TraceResult, Context, KeyValue};
+use opentelemetry_sdk::{
+    export::trace::SpanData,
+    trace::{Span, SpanProcessor},
+    Resource,
+};
+
+/// Replaces redacted values.
+const REDACTED: &str = "[REDACTED]";
+
+/// A [`SpanProcessor`] which redacts attribute values before passing spans on to the inner
+/// processor.
+#[derive(Debug)]
+pub struct RedactingSpanProcessor<P> {
+    inner: P,
+    redactions: Redactions,
+}
+
+/// The span attributes to redact.
+#[derive(Clone, Debug, Default)]
+pub struct Redactions {
+    /// Attributes whose values are redacted entirely.
+    pub attributes: Vec<String>,
+    /// URL-valued attributes whose query strings are redacted, keeping the rest of the URL, e.g.
+    /// `url.full`.
+    pub query_attributes: Vec<String>,
+}
+
+impl<P> RedactingSpanProcessor<P> {
+    pub fn new(inner: P, redactions: Redactions) -> Self {
+        Self { inner, redactions }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for RedactingSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx)
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        redact(&mut span.name, &mut span.attributes, &self.redactions);
+        // Events, e.g. logs within the span, can carry the same attributes
+        for event in span.events.events.iter_mut() {
+            redact(&mut event.name, &mut event.attributes, &self.redactions);
+        }
+        self.inner.on_end(span)
+    }
+
+    fn force_flush(&self) -> TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> TraceResult<()> {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource)
+    }
+}
+
+/// Redacts the values of the given attributes.
+///
+/// Spans are sometimes named after an attribute, so a span name equal to a redacted value is
+/// redacted in the same way.
+fn redact(name: &mut Cow<'static, str>, attributes: &mut [KeyValue], redactions: &Redactions) {
+    if redactions.attributes.is_empty() && redactions.query_attributes.is_empty() {
+        return;
+    }
+    for attribute in attributes {
+        let key = attribute.key.as_str();
+        let value = attribute.value.as_str().into_owned();
+        let redacted_value = if redactions.attributes.iter().any(|k| k == key) {
+            REDACTED.to_owned()
+        } else if redactions.query_attributes.iter().any(|k| k == key) {
+            match value.split_once('?') {
+                Some((url, _query)) => format!("{url}?{REDACTED}"),
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        if *name == value {
+            *name = Cow::Owned(redacted_value.clone());
+        }
+        attribute.value = redacted_value.into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::trace::{Span as _, Tracer, TracerProvider};
+
+    use super::*;
+
+    /// Collects ended spans.
+    #[derive(Clone, Debug, Default)]
+    struct CollectingSpanProcessor(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanProcessor for CollectingSpanProcessor {
+        fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            self.0.lock().unwrap().push(span);
+        }
+
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn redacts_span_event_attributes() {
+        let spans = CollectingSpanProcessor::default();
+        let tracer_provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_span_processor(RedactingSpanProcessor::new(
+                spans.clone(),
+                Redactions {
+                    attributes: vec!["password".to_owned()],
+                    ..Default::default()
+                },
+            ))
+            .build();
+
+        let mut span = tracer_provider.tracer("test").start("login");
+        span.add_event(
+            "hunter2",
+            vec![
+                KeyValue::new("password", "hunter2"),
+                KeyValue::new("user", "me"),
+            ],
+        );
+        span.end();
+
+        let spans = spans.0.lock().unwrap();
+        let event = &spans[0].events.events[0];
+        assert_eq!(event.name, REDACTED);
+        assert_eq!(event.attributes[0].value.as_str(), REDACTED);
+        assert_eq!(event.attributes[1].value.as_str(), "me");
+    }
+
+    #[test]
+    fn redacts_attributes_and_span_names() {
+        let statement = "SELECT * FROM users WHERE email = 'me@example.com'";
+        let mut name = Cow::Owned(statement.to_owned());
+        let mut attributes = [
+            KeyValue::new("db.system", "sqlite"),
+            KeyValue::new("db.query.text", statement),
+        ];
+        redact(
+            &mut name,
+            &mut attributes,
+            &Redactions {
+                attributes: vec!["db.query.text".to_owned()],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(name, REDACTED);
+        assert_eq!(attributes[0].value.as_str(), "sqlite");
+        assert_eq!(attributes[1].value.as_str(), REDACTED);
+    }
+
+    #[test]
+    fn redacts_whole_urls_or_only_query_strings() {
+        let url = "https://example.com/search?token=secret";
+        let mut name = Cow::Borrowed("GET");
+        let mut attributes = [KeyValue::new("url.full", url)];
+        redact(
+            &mut name,
+            &mut attributes,
+            &Redactions {
+                query_attributes: vec!["url.full".to_owned()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            attributes[0].value.as_str(),
+            "https://example.com/search?[REDACTED]"
+        );
+
+        let mut attributes = [KeyValue::new("url.full", url)];
+        redact(
+            &mut name,
+            &mut attributes,
+            &Redactions {
+                attributes: vec!["url.full".to_owned()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(attributes[0].value.as_str(), REDACTED);
+    }
+
+    #[test]
+    fn keeps_urls_without_query_strings() {
+        let mut name = Cow::Borrowed("GET");
+        let mut attributes = [KeyValue::new("url.full", "https://example.com/")];
+        redact(
+            &mut name,
+            &mut attributes,
+            &Redactions {
+                query_attributes: vec!["url.full".to_owned()],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(name, "GET");
+        assert_eq!(attributes[0].value.as_str(), "https://example.com/");
+    }
+}
@@ -5,19 +5,35 @@ use opentelemetry::{global, trace::TracerProvider};
 use opentelemetry_otlp::SpanExporterBuilder;
 use opentelemetry_sdk::{
     resource::{EnvResourceDetector, TelemetryResourceDetector},
+    trace::BatchSpanProcessor,
     Resource,
 };
 use tracing::Subscriber;
-use tracing_subscriber::{registry::LookupSpan, EnvFilter, Layer};
+use tracing_subscriber::{
+    filter::{filter_fn, FilterExt},
+    registry::LookupSpan,
+    EnvFilter, Layer,
+};
 
 use crate::detector::SpinResourceDetector;
-use crate::env::OtlpProtocol;
+use crate::env::{
+    otel_tracing_disabled_spans, otel_tracing_redacted_attributes,
+    otel_tracing_redacted_query_attributes, OtlpProtocol,
+};
+use crate::redaction::{RedactingSpanProcessor, Redactions};
 
 /// Constructs a layer for the tracing subscriber that sends spans to an OTEL collector.
 ///
 /// It pulls OTEL configuration from the environment based on the variables defined
 /// [here](https://opentelemetry.io/docs/specs/otel/protocol/exporter/) and
 /// [here](https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#general-sdk-configuration).
+/// This includes sampling, which is configured by `OTEL_TRACES_SAMPLER` and
+/// `OTEL_TRACES_SAMPLER_ARG`, e.g. `parentbased_traceidratio` and `0.1` to sample 10% of traces
+/// which aren't continued from a caller.
+///
+/// Spans can be disabled by name with `SPIN_OTEL_TRACING_DISABLED_SPANS`. Attribute values can be
+/// redacted with `SPIN_OTEL_TRACING_REDACTED_ATTRIBUTES`, or only the query strings of URL-valued
+/// attributes with `SPIN_OTEL_TRACING_REDACTED_QUERY_ATTRIBUTES`.
 pub(crate) fn otel_tracing_layer<S: Subscriber + for<'span> LookupSpan<'span>>(
     spin_version: String,
 ) -> anyhow::Result<impl Layer<S>> {
@@ -41,11 +57,20 @@ pub(crate) fn otel_tracing_layer<S: Subscriber + for<'span> LookupSpan<'span>>(
         OtlpProtocol::HttpJson => bail!("http/json OTLP protocol is not supported"),
     };
 
-    let tracer_provider = opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(exporter_builder)
-        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(resource))
-        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    let exporter = exporter_builder.build_span_exporter()?;
+    let span_processor = RedactingSpanProcessor::new(
+        BatchSpanProcessor::builder(exporter, opentelemetry_sdk::runtime::Tokio).build(),
+        Redactions {
+            attributes: otel_tracing_redacted_attributes(),
+            query_attributes: otel_tracing_redacted_query_attributes(),
+        },
+    );
+
+    // The default config reads the sampler from OTEL_TRACES_SAMPLER and OTEL_TRACES_SAMPLER_ARG
+    let tracer_provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_config(opentelemetry_sdk::trace::Config::default().with_resource(resource))
+        .with_span_processor(span_processor)
+        .build();
 
     global::set_tracer_provider(tracer_provider.clone());
 
@@ -55,8 +80,40 @@ pub(crate) fn otel_tracing_layer<S: Subscriber + for<'span> LookupSpan<'span>>(
         Err(_) => EnvFilter::new("info"),
     };
 
+    // Children of a disabled span are attached to its parent instead
+    let disabled_spans = otel_tracing_disabled_spans();
+    let span_filter = filter_fn(move |metadata: &tracing::Metadata<'_>| {
+        !metadata.is_span() || !is_disabled_span(&disabled_spans, metadata.name())
+    });
+
     Ok(tracing_opentelemetry::layer()
         .with_tracer(tracer_provider.tracer("spin"))
         .with_threads(false)
-        .with_filter(env_filter))
+        .with_filter(env_filter.and(span_filter)))
+}
+
+/// Returns whether the span with the given name, or a prefix of it ending before a `.`, is
+/// disabled.
+fn is_disabled_span(disabled_spans: &[String], name: &str) -> bool {
+    disabled_spans.iter().any(|disabled| {
+        name.strip_prefix(disabled.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disables_spans_by_name_or_prefix() {
+        let disabled = [
+            "spin_key_value".to_owned(),
+            "spin_sqlite.execute".to_owned(),
+        ];
+        assert!(is_disabled_span(&disabled, "spin_key_value.get"));
+        assert!(is_disabled_span(&disabled, "spin_sqlite.execute"));
+        assert!(!is_disabled_span(&disabled, "spin_sqlite.open"));
+        assert!(!is_disabled_span(&disabled, "spin_key_value_extra.get"));
+    }
 }
@@ -15,3 +15,56 @@ impl StringConstraints {
         Ok(text)
     }
 }
+
+#[derive(Clone, Debug)]
+pub(crate) struct IntegerConstraints {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+
+impl IntegerConstraints {
+    pub fn validate(&self, text: String) -> anyhow::Result<String> {
+        let value: i64 = text
+            .trim()
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Input '{}' is not an integer", text))?;
+        if let Some(min) = self.min {
+            if value < min {
+                anyhow::bail!("Input {} is less than the minimum of {}", value, min);
+            }
+        }
+        if let Some(max) = self.max {
+            if value > max {
+                anyhow::bail!("Input {} is greater than the maximum of {}", value, max);
+            }
+        }
+        Ok(value.to_string())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ChoiceConstraints {
+    pub choices: Vec<String>,
+}
+
+impl ChoiceConstraints {
+    pub fn validate(&self, text: String) -> anyhow::Result<String> {
+        if self.choices.contains(&text) {
+            Ok(text)
+        } else {
+            anyhow::bail!(
+                "Input '{}' is not one of the allowed values: {}",
+                text,
+                self.choices.join(", ")
+            );
+        }
+    }
+}
+
+pub(crate) fn validate_bool(text: String) -> anyhow::Result<String> {
+    match text.trim().to_lowercase().as_str() {
+        "true" | "yes" | "y" => Ok("true".to_owned()),
+        "false" | "no" | "n" => Ok("false".to_owned()),
+        _ => anyhow::bail!("Input '{}' is not a boolean (expected true or false)", text),
+    }
+}
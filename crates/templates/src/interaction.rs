@@ -8,7 +8,7 @@ use crate::{
 
 use anyhow::anyhow;
 // use console::style;
-use dialoguer::{Confirm, Input};
+use dialoguer::{Confirm, Input, Select};
 
 pub(crate) trait InteractionStrategy {
     fn allow_generate_into(&self, target_dir: &Path) -> Cancellable<(), anyhow::Error>;
@@ -20,6 +20,12 @@ pub(crate) trait InteractionStrategy {
         for parameter in run.template.parameters(&run.options.variant) {
             match self.populate_parameter(run, parameter) {
                 Cancellable::Ok(value) => {
+                    // Normalise the value, e.g. so that a boolean provided as `yes`
+                    // is rendered as `true`.
+                    let value = match parameter.validate_value(value) {
+                        Ok(value) => value,
+                        Err(e) => return Cancellable::Err(e),
+                    };
                     values.insert(parameter.id().to_owned(), value);
                 }
                 Cancellable::Cancelled => return Cancellable::Cancelled,
@@ -33,6 +39,7 @@ pub(crate) trait InteractionStrategy {
         run: &Run,
         parameter: &TemplateParameter,
     ) -> Cancellable<String, anyhow::Error>;
+    fn allow_run_command(&self, command: &str, dir: &Path) -> anyhow::Result<bool>;
 }
 
 pub(crate) struct Interactive;
@@ -71,6 +78,15 @@ impl InteractionStrategy for Interactive {
             },
         }
     }
+
+    fn allow_run_command(&self, command: &str, dir: &Path) -> anyhow::Result<bool> {
+        let prompt = format!(
+            "The template wants to run `{}` in {}. Run it?",
+            command,
+            dir.display()
+        );
+        Ok(crate::interaction::confirm(&prompt)?)
+    }
 }
 
 impl InteractionStrategy for Silent {
@@ -99,6 +115,17 @@ impl InteractionStrategy for Silent {
             },
         }
     }
+
+    fn allow_run_command(&self, command: &str, dir: &Path) -> anyhow::Result<bool> {
+        // Commands are only run with the user's consent, which can't be given
+        // when running silently unless it was given up front.
+        terminal::warn!(
+            "Skipped running `{}` in {}. Run it manually to finish setting up, or pass `--allow-run-commands` to run it.",
+            command,
+            dir.display()
+        );
+        Ok(false)
+    }
 }
 
 pub(crate) fn confirm(text: &str) -> dialoguer::Result<bool> {
@@ -111,7 +138,13 @@ pub(crate) fn prompt_parameter(parameter: &TemplateParameter) -> Option<String>
 
     loop {
         let input = match parameter.data_type() {
-            TemplateParameterDataType::String(_) => ask_free_text(prompt, default_value),
+            TemplateParameterDataType::String(_) | TemplateParameterDataType::Integer(_) => {
+                ask_free_text(prompt, default_value)
+            }
+            TemplateParameterDataType::Bool => ask_yes_no(prompt, default_value),
+            TemplateParameterDataType::Choice(constraints) => {
+                ask_choice(prompt, &constraints.choices, default_value)
+            }
         };
 
         match input {
@@ -138,6 +171,32 @@ fn ask_free_text(prompt: &str, default_value: &Option<String>) -> anyhow::Result
     Ok(result)
 }
 
+fn ask_yes_no(prompt: &str, default_value: &Option<String>) -> anyhow::Result<String> {
+    let mut confirm = Confirm::new().with_prompt(prompt);
+    if let Some(s) = default_value {
+        confirm = confirm.default(s == "true");
+    }
+    let result = confirm.interact()?;
+    Ok(result.to_string())
+}
+
+fn ask_choice(
+    prompt: &str,
+    choices: &[String],
+    default_value: &Option<String>,
+) -> anyhow::Result<String> {
+    let default_index = default_value
+        .as_ref()
+        .and_then(|d| choices.iter().position(|c| c == d))
+        .unwrap_or_default();
+    let index = Select::new()
+        .with_prompt(prompt)
+        .items(choices)
+        .default(default_index)
+        .interact()?;
+    Ok(choices[index].clone())
+}
+
 fn is_directory_empty(path: &Path) -> bool {
     if !path.exists() {
         return true;
@@ -17,6 +17,7 @@ mod run;
 mod source;
 mod store;
 mod template;
+mod testing;
 mod toml;
 mod writer;
 
@@ -24,6 +25,9 @@ pub use manager::*;
 pub use run::{Run, RunOptions};
 pub use source::TemplateSource;
 pub use template::{Template, TemplateVariantInfo};
+pub use testing::{
+    RenderedTest, SnapshotDifference, TemplateTestCase, TemplateTests, TEMPLATE_TESTS_FILE_NAME,
+};
 
 #[cfg(test)]
 mod test_built_ins;
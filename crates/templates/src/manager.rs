@@ -131,6 +131,9 @@ impl TemplateManager {
             .get_local()
             .await
             .context("Failed to get template source")?;
+        // Record what was actually downloaded
+        let recorded_source = source.recording(&local_source);
+        let source = recorded_source.as_ref().unwrap_or(source);
         let template_dirs = local_source
             .template_directories()
             .await
@@ -539,6 +542,8 @@ mod tests {
             accept_defaults: false,
             no_vcs: false,
             allow_overwrite: false,
+            skip_post_generate: false,
+            allow_run_commands: false,
         };
         rest(&mut options);
         options
@@ -1221,6 +1226,32 @@ mod tests {
         assert_contains(&err_str, "unknown filter 'lol_snort'");
     }
 
+    #[tokio::test]
+    async fn silent_run_skips_commands_unless_allowed() {
+        let manager = TempManager::new();
+        manager.install_test_data_templates().await;
+
+        let template = manager.get("run-command").unwrap().unwrap();
+
+        let dest_temp_dir = tempdir().unwrap();
+
+        let skipped_dir = dest_temp_dir.path().join("skipped");
+        let options = run_options("skipped", &skipped_dir, |opts| {
+            opts.accept_defaults = true;
+        });
+        template.run(options).silent().await.unwrap();
+        assert!(skipped_dir.join("spin.toml").exists());
+        assert!(!skipped_dir.join("ran.txt").exists());
+
+        let allowed_dir = dest_temp_dir.path().join("allowed");
+        let options = run_options("allowed", &allowed_dir, |opts| {
+            opts.accept_defaults = true;
+            opts.allow_run_commands = true;
+        });
+        template.run(options).silent().await.unwrap();
+        assert!(allowed_dir.join("ran.txt").exists());
+    }
+
     fn assert_contains(actual: &str, expected: &str) {
         assert!(
             actual.contains(expected),
@@ -53,6 +53,22 @@ pub(crate) struct RawConditional {
 )]
 pub(crate) enum RawCondition {
     ManifestEntryExists(String),
+    ParameterEquals { name: String, value: String },
+    TriggerTypeExists(String),
+    ComponentExists(String),
+    FileExists(String),
+    Not(Box<RawCondition>),
+}
+
+impl RawCondition {
+    /// Whether the condition can only be evaluated once parameter values are known.
+    pub fn depends_on_parameters(&self) -> bool {
+        match self {
+            Self::ParameterEquals { .. } => true,
+            Self::Not(inner) => inner.depends_on_parameters(),
+            _ => false,
+        }
+    }
 }
 
 impl TryFrom<toml::Value> for RawCondition {
@@ -65,15 +81,48 @@ impl TryFrom<toml::Value> for RawCondition {
         if table.keys().len() != 1 {
             anyhow::bail!("Invalid condition: should be a single-entry table");
         }
-        let Some(value) = table.get("manifest_entry_exists") else {
-            anyhow::bail!("Invalid condition: unknown condition type");
+        let (condition_type, value) = table.iter().next().unwrap();
+        let string_arg = || {
+            value.as_str().map(|s| s.to_owned()).ok_or_else(|| {
+                anyhow::anyhow!("Invalid condition: '{condition_type}' should be a string")
+            })
         };
-        let Some(path) = value.as_str() else {
-            anyhow::bail!(
-                "Invalid condition: 'manifest_entry_exists' should be a dotted-path string"
-            );
-        };
-        Ok(Self::ManifestEntryExists(path.to_owned()))
+        match condition_type.as_str() {
+            "manifest_entry_exists" => {
+                let Some(path) = value.as_str() else {
+                    anyhow::bail!(
+                        "Invalid condition: 'manifest_entry_exists' should be a dotted-path string"
+                    );
+                };
+                Ok(Self::ManifestEntryExists(path.to_owned()))
+            }
+            "parameter_equals" => {
+                let Some(args) = value.as_table() else {
+                    anyhow::bail!("Invalid condition: 'parameter_equals' should be a table with 'name' and 'value' entries");
+                };
+                let Some(name) = args.get("name").and_then(|n| n.as_str()) else {
+                    anyhow::bail!(
+                        "Invalid condition: 'parameter_equals' must have a string 'name' entry"
+                    );
+                };
+                // Parameter values are always strings, but allow `true` or `3` as well as `"true"` or `"3"`.
+                let value = match args.get("value") {
+                    Some(toml::Value::String(s)) => s.clone(),
+                    Some(toml::Value::Boolean(b)) => b.to_string(),
+                    Some(toml::Value::Integer(i)) => i.to_string(),
+                    _ => anyhow::bail!("Invalid condition: 'parameter_equals' must have a string, boolean or integer 'value' entry"),
+                };
+                Ok(Self::ParameterEquals {
+                    name: name.to_owned(),
+                    value,
+                })
+            }
+            "trigger_type_exists" => Ok(Self::TriggerTypeExists(string_arg()?)),
+            "component_exists" => Ok(Self::ComponentExists(string_arg()?)),
+            "file_exists" => Ok(Self::FileExists(string_arg()?)),
+            "not" => Ok(Self::Not(Box::new(Self::try_from(value.clone())?))),
+            _ => anyhow::bail!("Invalid condition: unknown condition type '{condition_type}'"),
+        }
     }
 }
 
@@ -83,15 +132,41 @@ pub(crate) struct RawParameter {
     #[serde(rename = "type")]
     pub data_type: String,
     pub prompt: String,
-    #[serde(rename = "default")]
+    #[serde(
+        rename = "default",
+        default,
+        deserialize_with = "deserialize_default_value"
+    )]
     pub default_value: Option<String>,
     pub pattern: Option<String>,
+    pub choices: Option<Vec<String>>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+
+// Defaults for boolean and integer parameters may be written as TOML booleans
+// and integers, but are handled as strings like all other parameter values.
+fn deserialize_default_value<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<toml::Value>::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(toml::Value::String(s)) => Ok(Some(s)),
+        Some(toml::Value::Boolean(b)) => Ok(Some(b.to_string())),
+        Some(toml::Value::Integer(i)) => Ok(Some(i.to_string())),
+        Some(_) => Err(serde::de::Error::custom(
+            "default must be a string, boolean or integer",
+        )),
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "snake_case", tag = "action")]
 pub(crate) enum RawExtraOutput {
     CreateDir(RawCreateDir),
+    RunCommand(RawRunCommand),
 }
 
 #[derive(Debug, Deserialize)]
@@ -101,6 +176,14 @@ pub(crate) struct RawCreateDir {
     pub at: Option<CreateLocation>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub(crate) struct RawRunCommand {
+    pub command: String,
+    pub path: Option<String>,
+    pub at: Option<CreateLocation>,
+}
+
 #[derive(Debug, Deserialize, Clone, Copy, Default)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub(crate) enum CreateLocation {
@@ -116,8 +199,22 @@ pub(crate) fn parse_manifest_toml(text: impl AsRef<str>) -> anyhow::Result<RawTe
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case", untagged)]
 pub(crate) enum RawInstalledFrom {
-    Git { git: String },
-    File { dir: String },
+    Git {
+        git: String,
+    },
+    File {
+        dir: String,
+    },
+    Tarball {
+        tarball: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        digest: Option<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        pinned: bool,
+    },
+    Oci {
+        oci: String,
+    },
 }
 
 pub(crate) fn parse_installed_from(text: impl AsRef<str>) -> Option<RawInstalledFrom> {
@@ -8,7 +8,7 @@ use crate::writer::{TemplateOutput, TemplateOutputs};
 // it needs to render.
 pub(crate) struct TemplateRenderer {
     pub render_operations: Vec<RenderOperation>,
-    pub parameter_values: HashMap<String, String>,
+    pub parameter_values: HashMap<String, liquid_core::Value>,
 }
 
 pub(crate) enum TemplateContent {
@@ -21,6 +21,11 @@ pub(crate) enum RenderOperation {
     MergeToml(PathBuf, MergeTarget, TemplateContent), // file to merge into, table to merge into, content to merge
     WriteFile(PathBuf, TemplateContent),
     CreateDirectory(PathBuf, std::sync::Arc<liquid::Template>),
+    RunCommand(
+        PathBuf,
+        std::sync::Arc<liquid::Template>,
+        std::sync::Arc<liquid::Template>,
+    ), // base directory, directory within base to run in, command to run
 }
 
 pub(crate) enum MergeTarget {
@@ -48,10 +53,7 @@ impl TemplateRenderer {
         let mut object = liquid::Object::new();
 
         for (k, v) in &self.parameter_values {
-            object.insert(
-                k.to_owned().into(),
-                liquid_core::Value::Scalar(v.to_owned().into()),
-            );
+            object.insert(k.to_owned().into(), v.to_owned());
         }
 
         object
@@ -81,6 +83,12 @@ impl RenderOperation {
                 let path = path.join(rendered); // TODO: should we validate that `rendered` was relative?`
                 Ok(TemplateOutput::CreateDirectory(path))
             }
+            Self::RunCommand(path, path_template, command_template) => {
+                let rendered_path = path_template.render(globals)?;
+                let rendered_command = command_template.render(globals)?;
+                let path = path.join(rendered_path);
+                Ok(TemplateOutput::RunCommand(path, rendered_command))
+            }
         }
     }
 }
@@ -17,6 +17,7 @@ use crate::{
 use crate::{
     renderer::{RenderOperation, TemplateContent, TemplateRenderer},
     template::Template,
+    writer::TemplateOutputs,
 };
 
 /// Executes a template to the point where it is ready to generate
@@ -43,6 +44,11 @@ pub struct RunOptions {
     /// Skip the overwrite prompt if the output directory already contains files
     /// (or, if silent, allow overwrite instead of erroring).
     pub allow_overwrite: bool,
+    /// If true, do not run the template's post-generation commands.
+    pub skip_post_generate: bool,
+    /// If true, run the template's post-generation commands without asking
+    /// (or, if silent, run them instead of skipping them).
+    pub allow_run_commands: bool,
 }
 
 impl Run {
@@ -66,17 +72,35 @@ impl Run {
     }
 
     async fn run(&self, interaction: impl InteractionStrategy) -> anyhow::Result<()> {
-        self.build_renderer(interaction)
+        self.build_renderer(&interaction)
             .await
             .and_then(|t| t.render())
+            .and_then(|o| self.confirm_commands(o, &interaction))
             .and_then_async(|o| async move { o.write().await })
             .await
             .err()
     }
 
+    fn confirm_commands(
+        &self,
+        mut outputs: TemplateOutputs,
+        interaction: &impl InteractionStrategy,
+    ) -> anyhow::Result<TemplateOutputs> {
+        outputs.retain_commands(|dir, command| {
+            if self.options.skip_post_generate {
+                return Ok(false);
+            }
+            if self.options.allow_run_commands {
+                return Ok(true);
+            }
+            interaction.allow_run_command(command, dir)
+        })?;
+        Ok(outputs)
+    }
+
     async fn build_renderer(
         &self,
-        interaction: impl InteractionStrategy,
+        interaction: &impl InteractionStrategy,
     ) -> Cancellable<TemplateRenderer, anyhow::Error> {
         self.build_renderer_raw(interaction).await.into()
     }
@@ -88,7 +112,7 @@ impl Run {
     // a better way but I don't see one yet...
     async fn build_renderer_raw(
         &self,
-        interaction: impl InteractionStrategy,
+        interaction: &impl InteractionStrategy,
     ) -> anyhow::Result<Option<TemplateRenderer>> {
         self.validate_version()?;
         self.validate_trigger()?;
@@ -106,19 +130,27 @@ impl Run {
 
         self.validate_provided_values()?;
 
+        // Parameter values are needed to resolve which files and snippets the
+        // template variant includes.
+        let parameter_values = match interaction.populate_parameters(self) {
+            Cancellable::Ok(parameter_values) => parameter_values,
+            Cancellable::Cancelled => return Ok(None),
+            Cancellable::Err(e) => return Err(e),
+        };
+
         let files = match self.template.content_dir() {
             None => vec![],
             Some(path) => {
                 let from = path
                     .absolutize()
                     .context("Failed to get absolute path of template directory")?;
-                self.included_files(&from, &to)?
+                self.included_files(&from, &to, &parameter_values)?
             }
         };
 
         let snippets = self
             .template
-            .snippets(&self.options.variant)
+            .snippets(&self.options.variant, &parameter_values)
             .iter()
             .map(|(id, path)| self.snippet_operation(id, path))
             .collect::<anyhow::Result<Vec<_>>>()?;
@@ -132,26 +164,32 @@ impl Run {
 
         let render_operations = files.into_iter().chain(snippets).chain(extras).collect();
 
-        match interaction.populate_parameters(self) {
-            Cancellable::Ok(parameter_values) => {
-                let values = self
-                    .special_values()
-                    .await
-                    .into_iter()
-                    .chain(parameter_values)
-                    .collect();
-                let prepared_template = TemplateRenderer {
-                    render_operations,
-                    parameter_values: values,
-                };
-                Ok(Some(prepared_template))
-            }
-            Cancellable::Cancelled => Ok(None),
-            Cancellable::Err(e) => Err(e),
-        }
+        let special_values = self
+            .special_values()
+            .await
+            .into_iter()
+            .map(|(k, v)| (k, liquid_core::Value::scalar(v)));
+        let parameter_values = parameter_values.into_iter().map(|(k, v)| {
+            let value = match self.template.parameter(&k) {
+                Some(parameter) => parameter.liquid_value(v),
+                None => liquid_core::Value::scalar(v),
+            };
+            (k, value)
+        });
+        let values = special_values.chain(parameter_values).collect();
+        let prepared_template = TemplateRenderer {
+            render_operations,
+            parameter_values: values,
+        };
+        Ok(Some(prepared_template))
     }
 
-    fn included_files(&self, from: &Path, to: &Path) -> anyhow::Result<Vec<RenderOperation>> {
+    fn included_files(
+        &self,
+        from: &Path,
+        to: &Path,
+        parameter_values: &HashMap<String, String>,
+    ) -> anyhow::Result<Vec<RenderOperation>> {
         let gitignore = ".gitignore";
         let mut all_content_files = Self::list_content_files(from)?;
         // If user asked for no_vcs
@@ -161,9 +199,12 @@ impl Run {
                 Some(file_name) => file_name.to_os_string() != gitignore,
             });
         }
-        let included_files =
-            self.template
-                .included_files(from, all_content_files, &self.options.variant);
+        let included_files = self.template.included_files(
+            from,
+            all_content_files,
+            &self.options.variant,
+            parameter_values,
+        );
         let template_contents = self.read_all(included_files)?;
         let outputs = Self::to_output_paths(from, to, template_contents);
         let file_ops = outputs
@@ -317,23 +358,33 @@ impl Run {
 
     fn extra_operation(&self, extra: &ExtraOutputAction) -> anyhow::Result<RenderOperation> {
         match extra {
-            ExtraOutputAction::CreateDirectory(_, template, at) => {
-                let component_path = self.options.output_path.clone();
-                let base_path = match at {
-                    crate::reader::CreateLocation::Component => component_path,
-                    crate::reader::CreateLocation::Manifest => match &self.options.variant {
-                        TemplateVariantInfo::NewApplication => component_path,
-                        TemplateVariantInfo::AddComponent { manifest_path } => manifest_path
-                            .parent()
-                            .map(|p| p.to_owned())
-                            .unwrap_or(component_path),
-                    },
-                };
-                Ok(RenderOperation::CreateDirectory(
-                    base_path,
-                    template.clone(),
-                ))
-            }
+            ExtraOutputAction::CreateDirectory(_, template, at) => Ok(
+                RenderOperation::CreateDirectory(self.extra_output_base_path(at), template.clone()),
+            ),
+            ExtraOutputAction::RunCommand {
+                command_template,
+                path_template,
+                at,
+                ..
+            } => Ok(RenderOperation::RunCommand(
+                self.extra_output_base_path(at),
+                path_template.clone(),
+                command_template.clone(),
+            )),
+        }
+    }
+
+    fn extra_output_base_path(&self, at: &crate::reader::CreateLocation) -> PathBuf {
+        let component_path = self.options.output_path.clone();
+        match at {
+            crate::reader::CreateLocation::Component => component_path,
+            crate::reader::CreateLocation::Manifest => match &self.options.variant {
+                TemplateVariantInfo::NewApplication => component_path,
+                TemplateVariantInfo::AddComponent { manifest_path } => manifest_path
+                    .parent()
+                    .map(|p| p.to_owned())
+                    .unwrap_or(component_path),
+            },
         }
     }
 
@@ -25,6 +25,18 @@ pub enum TemplateSource {
     /// Templates much be in a `/templates` directory under the specified
     /// root.
     File(PathBuf),
+    /// Install from a gzipped tarball at the specified URL. If a digest is
+    /// pinned, the downloaded tarball must match it.
+    ///
+    /// Templates must be in a `/templates` directory under the root of the
+    /// tarball, or under its single top-level directory.
+    Tarball(TarballTemplateSource),
+    /// Install from an artifact in an OCI registry, using the credentials
+    /// saved by `spin registry login` if present.
+    ///
+    /// The artifact must have a single layer containing a gzipped tarball
+    /// laid out as for the `Tarball` source.
+    Oci(String),
 }
 
 /// Settings for installing templates from a Git repository.
@@ -39,6 +51,19 @@ pub struct GitTemplateSource {
     spin_version: String,
 }
 
+/// Settings for installing templates from a tarball.
+#[derive(Debug)]
+pub struct TarballTemplateSource {
+    /// The URL of the tarball from which to install templates.
+    url: Url,
+    /// The SHA-256 digest of the tarball, if known.
+    digest: Option<String>,
+    /// Whether the digest was specified by the user, in which case the
+    /// downloaded tarball must match it. Otherwise, the digest only records
+    /// what was downloaded.
+    pinned: bool,
+}
+
 impl TemplateSource {
     /// Creates a `TemplateSource` referring to the specified Git repository
     /// and branch.
@@ -57,6 +82,27 @@ impl TemplateSource {
         }))
     }
 
+    /// Creates a `TemplateSource` referring to the specified tarball URL. If
+    /// a digest is specified (as hex, optionally prefixed with `sha256:`),
+    /// installation fails unless the tarball matches it.
+    pub fn try_from_tarball(
+        tarball_url: impl AsRef<str>,
+        digest: &Option<String>,
+    ) -> anyhow::Result<Self> {
+        let url_str = tarball_url.as_ref();
+        let url =
+            Url::parse(url_str).with_context(|| format!("Failed to parse {} as URL", url_str))?;
+        let digest = digest
+            .as_ref()
+            .map(|d| d.strip_prefix("sha256:").unwrap_or(d).to_ascii_lowercase());
+        let pinned = digest.is_some();
+        Ok(Self::Tarball(TarballTemplateSource {
+            url,
+            digest,
+            pinned,
+        }))
+    }
+
     pub(crate) fn to_install_record(&self) -> Option<crate::reader::RawInstalledFrom> {
         match self {
             Self::Git(g) => Some(crate::reader::RawInstalledFrom::Git {
@@ -72,6 +118,29 @@ impl TemplateSource {
                     None
                 }
             }
+            Self::Tarball(t) => Some(crate::reader::RawInstalledFrom::Tarball {
+                tarball: t.url.to_string(),
+                digest: t.digest.clone(),
+                pinned: t.pinned,
+            }),
+            Self::Oci(reference) => Some(crate::reader::RawInstalledFrom::Oci {
+                oci: reference.clone(),
+            }),
+        }
+    }
+
+    /// For a tarball source, returns the same source with the digest of the
+    /// tarball that was actually downloaded, so that the install record
+    /// captures it even if no digest was specified. The digest remains pinned
+    /// only if the user specified it. For other sources, returns None.
+    pub(crate) fn recording(&self, local: &LocalTemplateSource) -> Option<Self> {
+        match (self, &local.digest) {
+            (Self::Tarball(t), Some(digest)) => Some(Self::Tarball(TarballTemplateSource {
+                url: t.url.clone(),
+                digest: Some(digest.clone()),
+                pinned: t.pinned,
+            })),
+            _ => None,
         }
     }
 
@@ -88,6 +157,8 @@ impl TemplateSource {
 
 pub(crate) struct LocalTemplateSource {
     root: PathBuf,
+    /// The SHA-256 digest of the downloaded tarball, for tarball sources.
+    digest: Option<String>,
     _temp_dir: Option<TempDir>,
 }
 
@@ -96,6 +167,8 @@ impl TemplateSource {
         match self {
             Self::Git(git_source) => clone_local(git_source).await,
             Self::File(path) => check_local(path).await,
+            Self::Tarball(tarball_source) => download_tarball(tarball_source).await,
+            Self::Oci(reference) => pull_oci(reference).await,
         }
     }
 
@@ -103,6 +176,8 @@ impl TemplateSource {
         match self {
             Self::Git { .. } => true,
             Self::File(_) => false,
+            Self::Tarball(_) => true,
+            Self::Oci(_) => true,
         }
     }
 }
@@ -152,6 +227,7 @@ async fn clone_local(git_source: &GitTemplateSource) -> anyhow::Result<LocalTemp
     match clone_result {
         Ok(_) => Ok(LocalTemplateSource {
             root: path,
+            digest: None,
             _temp_dir: Some(temp_dir),
         }),
         Err(e) => Err(anyhow!("Error cloning Git repo {}: {}", url_str, e)),
@@ -181,10 +257,88 @@ fn version_preferred_tag(text: &str) -> String {
     format!("{}{}", TEMPLATE_VERSION_TAG_PREFIX, mm_version)
 }
 
+async fn download_tarball(
+    tarball_source: &TarballTemplateSource,
+) -> anyhow::Result<LocalTemplateSource> {
+    let url_str = tarball_source.url.as_str();
+
+    let response = reqwest::get(tarball_source.url.clone())
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Error downloading tarball {}", url_str))?;
+    let bytes = response
+        .bytes()
+        .await
+        .with_context(|| format!("Error downloading tarball {}", url_str))?;
+
+    let actual = spin_common::sha256::hex_digest_from_bytes(&bytes);
+    if let (true, Some(expected)) = (tarball_source.pinned, &tarball_source.digest) {
+        if &actual != expected {
+            return Err(anyhow!(
+                "Tarball {} has digest sha256:{} but expected sha256:{}. If the tarball has been legitimately republished, reinstall it with `spin templates install --tar {} --digest {} --upgrade`",
+                url_str,
+                actual,
+                expected,
+                url_str,
+                actual,
+            ));
+        }
+    }
+
+    let temp_dir = tempdir()?;
+    let path = temp_dir.path().to_owned();
+    let decoder = flate2::read::GzDecoder::new(bytes.as_ref());
+    tar::Archive::new(decoder)
+        .unpack(&path)
+        .with_context(|| format!("Error unpacking tarball {}", url_str))?;
+
+    Ok(LocalTemplateSource {
+        root: archive_root(path),
+        digest: Some(actual),
+        _temp_dir: Some(temp_dir),
+    })
+}
+
+async fn pull_oci(reference: &str) -> anyhow::Result<LocalTemplateSource> {
+    let temp_dir = tempdir()?;
+    let path = temp_dir.path().to_owned();
+
+    let mut client = spin_oci::Client::new(false, None)
+        .await
+        .context("Error creating registry client")?;
+    client
+        .pull_archive(
+            reference,
+            spin_oci::client::SPIN_TEMPLATES_MEDIA_TYPE,
+            &path,
+        )
+        .await
+        .with_context(|| format!("Error pulling templates from {}", reference))?;
+
+    Ok(LocalTemplateSource {
+        root: archive_root(path),
+        digest: None,
+        _temp_dir: Some(temp_dir),
+    })
+}
+
+// Archives are often built with all content under a single top-level directory
+// (e.g. `my-templates-1.0/templates/...`). If so, treat that as the root.
+fn archive_root(unpacked: PathBuf) -> PathBuf {
+    if unpacked.join(TEMPLATE_SOURCE_DIR).exists() {
+        return unpacked;
+    }
+    match subdirectories(&unpacked).as_deref() {
+        Ok([single]) if single.join(TEMPLATE_SOURCE_DIR).exists() => single.clone(),
+        _ => unpacked,
+    }
+}
+
 async fn check_local(path: &Path) -> anyhow::Result<LocalTemplateSource> {
     if path.exists() {
         Ok(LocalTemplateSource {
             root: path.to_owned(),
+            digest: None,
             _temp_dir: None,
         })
     } else {
@@ -223,4 +377,75 @@ mod test {
         assert_eq!("spin/templates/v1.2.3.4", version_preferred_tag("1.2.3.4"));
         assert_eq!("spin/templates/vgarbage", version_preferred_tag("garbage"));
     }
+
+    #[test]
+    fn tarball_digest_is_normalised() {
+        let source = TemplateSource::try_from_tarball(
+            "https://example.com/t.tar.gz",
+            &Some("sha256:ABC123".to_owned()),
+        )
+        .unwrap();
+        let TemplateSource::Tarball(tarball_source) = source else {
+            panic!("expected tarball source");
+        };
+        assert_eq!(Some("abc123".to_owned()), tarball_source.digest);
+    }
+
+    fn downloaded(digest: &str) -> LocalTemplateSource {
+        LocalTemplateSource {
+            root: PathBuf::from("/unused"),
+            digest: Some(digest.to_owned()),
+            _temp_dir: None,
+        }
+    }
+
+    #[test]
+    fn tarball_install_record_records_downloaded_digest() {
+        let source =
+            TemplateSource::try_from_tarball("https://example.com/t.tar.gz", &None).unwrap();
+        let recorded = source
+            .recording(&downloaded("abc123"))
+            .expect("tarball digest should be recorded");
+        let Some(crate::reader::RawInstalledFrom::Tarball {
+            tarball,
+            digest,
+            pinned,
+        }) = recorded.to_install_record()
+        else {
+            panic!("expected tarball install record");
+        };
+        assert_eq!("https://example.com/t.tar.gz", tarball);
+        assert_eq!(Some("abc123".to_owned()), digest);
+        assert!(!pinned);
+    }
+
+    #[test]
+    fn tarball_install_record_keeps_explicit_digest_pinned() {
+        let source = TemplateSource::try_from_tarball(
+            "https://example.com/t.tar.gz",
+            &Some("abc123".to_owned()),
+        )
+        .unwrap();
+        let recorded = source
+            .recording(&downloaded("abc123"))
+            .expect("tarball digest should be recorded");
+        let Some(crate::reader::RawInstalledFrom::Tarball { pinned, .. }) =
+            recorded.to_install_record()
+        else {
+            panic!("expected tarball install record");
+        };
+        assert!(pinned);
+    }
+
+    #[test]
+    fn archive_root_descends_into_single_top_level_directory() {
+        let temp_dir = tempdir().unwrap();
+        let unpacked = temp_dir.path().to_owned();
+        let nested = unpacked.join("my-templates-1.0");
+        std::fs::create_dir_all(nested.join(TEMPLATE_SOURCE_DIR)).unwrap();
+        assert_eq!(nested, archive_root(unpacked.clone()));
+
+        std::fs::create_dir_all(unpacked.join(TEMPLATE_SOURCE_DIR)).unwrap();
+        assert_eq!(unpacked, archive_root(unpacked.clone()));
+    }
 }
@@ -9,12 +9,13 @@ use itertools::Itertools;
 use regex::Regex;
 
 use crate::{
-    constraints::StringConstraints,
+    constraints::{validate_bool, ChoiceConstraints, IntegerConstraints, StringConstraints},
     reader::{
         RawCondition, RawConditional, RawExtraOutput, RawParameter, RawTemplateManifest,
         RawTemplateManifestV1, RawTemplateVariant,
     },
     run::{Run, RunOptions},
+    source::TemplateSource,
     store::TemplateLayout,
 };
 
@@ -37,6 +38,12 @@ pub struct Template {
 enum InstalledFrom {
     Git(String),
     Directory(String),
+    Tarball {
+        url: String,
+        digest: Option<String>,
+        pinned: bool,
+    },
+    Oci(String),
     Unknown,
 }
 
@@ -111,6 +118,11 @@ pub(crate) struct Conditional {
 #[derive(Clone, Debug)]
 pub(crate) enum Condition {
     ManifestEntryExists(Vec<String>),
+    ParameterEquals(String, String),
+    TriggerTypeExists(String),
+    ComponentExists(String),
+    FileExists(String),
+    Not(Box<Condition>),
     #[cfg(test)]
     Always(bool),
 }
@@ -124,6 +136,9 @@ pub(crate) enum TemplateTriggerCompatibility {
 #[derive(Clone, Debug)]
 pub(crate) enum TemplateParameterDataType {
     String(StringConstraints),
+    Bool,
+    Choice(ChoiceConstraints),
+    Integer(IntegerConstraints),
 }
 
 #[derive(Debug)]
@@ -140,6 +155,12 @@ pub(crate) enum ExtraOutputAction {
         std::sync::Arc<liquid::Template>,
         crate::reader::CreateLocation,
     ),
+    RunCommand {
+        command: String,
+        command_template: std::sync::Arc<liquid::Template>,
+        path_template: std::sync::Arc<liquid::Template>,
+        at: crate::reader::CreateLocation,
+    },
 }
 
 impl std::fmt::Debug for ExtraOutputAction {
@@ -148,6 +169,7 @@ impl std::fmt::Debug for ExtraOutputAction {
             Self::CreateDirectory(orig, ..) => {
                 f.debug_tuple("CreateDirectory").field(orig).finish()
             }
+            Self::RunCommand { command, .. } => f.debug_tuple("RunCommand").field(command).finish(),
         }
     }
 }
@@ -237,13 +259,37 @@ impl Template {
         }
     }
 
-    /// The Git repository from which the template was installed, if
-    /// it was installed from Git; otherwise None.
+    /// The Git repository, tarball URL or OCI reference from which the
+    /// template was installed, if it was installed from a source that can
+    /// be upgraded; otherwise None.
     pub fn source_repo(&self) -> Option<&str> {
         // TODO: this is kind of specialised - should we do the discarding of
-        // non-Git sources at the application layer?
+        // non-upgradeable sources at the application layer?
         match &self.installed_from {
             InstalledFrom::Git(url) => Some(url),
+            InstalledFrom::Tarball { url, .. } => Some(url),
+            InstalledFrom::Oci(reference) => Some(reference),
+            _ => None,
+        }
+    }
+
+    /// The source from which to upgrade the template, if it was installed
+    /// from a source that can be upgraded; otherwise None.
+    pub fn upgrade_source(&self, spin_version: &str) -> Option<TemplateSource> {
+        match &self.installed_from {
+            InstalledFrom::Git(url) => TemplateSource::try_from_git(url, &None, spin_version).ok(),
+            // A digest the user pinned at install time must still match, so
+            // taking a changed tarball requires reinstalling it. Otherwise the
+            // tarball is fetched again and its new digest recorded.
+            InstalledFrom::Tarball {
+                url,
+                digest,
+                pinned,
+            } => {
+                let expected_digest = if *pinned { digest.clone() } else { None };
+                TemplateSource::try_from_tarball(url, &expected_digest).ok()
+            }
+            InstalledFrom::Oci(reference) => Some(TemplateSource::Oci(reference.clone())),
             _ => None,
         }
     }
@@ -254,23 +300,31 @@ impl Template {
         match &self.installed_from {
             InstalledFrom::Git(repo) => repo,
             InstalledFrom::Directory(path) => path,
+            InstalledFrom::Tarball { url, .. } => url,
+            InstalledFrom::Oci(reference) => reference,
             InstalledFrom::Unknown => "",
         }
     }
 
     // TODO: we should resolve this once at the start of Run and then use that forever
-    fn variant(&self, variant_info: &TemplateVariantInfo) -> Option<TemplateVariant> {
+    fn variant(
+        &self,
+        variant_info: &TemplateVariantInfo,
+        parameter_values: &HashMap<String, String>,
+    ) -> Option<TemplateVariant> {
         let kind = variant_info.kind();
         self.variants
             .get(&kind)
-            .map(|vt| vt.resolve_conditions(variant_info))
+            .map(|vt| vt.resolve_conditions(variant_info, parameter_values))
     }
 
     pub(crate) fn parameters(
         &self,
         variant_kind: &TemplateVariantInfo,
     ) -> impl Iterator<Item = &TemplateParameter> {
-        let variant = self.variant(variant_kind).unwrap(); // TODO: for now
+        // Conditions on parameter values may not skip parameters, so we can resolve
+        // the variant before the values are known.
+        let variant = self.variant(variant_kind, &HashMap::new()).unwrap(); // TODO: for now
         self.parameters
             .iter()
             .filter(move |p| !variant.skip_parameter(p))
@@ -297,8 +351,12 @@ impl Template {
         self.variants.contains_key(&variant.kind())
     }
 
-    pub(crate) fn snippets(&self, variant_kind: &TemplateVariantInfo) -> HashMap<String, String> {
-        let variant = self.variant(variant_kind).unwrap(); // TODO: for now
+    pub(crate) fn snippets(
+        &self,
+        variant_kind: &TemplateVariantInfo,
+        parameter_values: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let variant = self.variant(variant_kind, parameter_values).unwrap(); // TODO: for now
         variant.snippets
     }
 
@@ -398,6 +456,15 @@ impl Template {
             RawCondition::ManifestEntryExists(path) => {
                 Condition::ManifestEntryExists(path.split('.').map(|s| s.to_string()).collect_vec())
             }
+            RawCondition::ParameterEquals { name, value } => {
+                Condition::ParameterEquals(name, value)
+            }
+            RawCondition::TriggerTypeExists(trigger_type) => {
+                Condition::TriggerTypeExists(trigger_type)
+            }
+            RawCondition::ComponentExists(name) => Condition::ComponentExists(name),
+            RawCondition::FileExists(path) => Condition::FileExists(path),
+            RawCondition::Not(inner) => Condition::Not(Box::new(Self::parse_condition(*inner))),
         }
     }
 
@@ -430,8 +497,9 @@ impl Template {
         base: &std::path::Path,
         all_files: Vec<PathBuf>,
         variant_kind: &TemplateVariantInfo,
+        parameter_values: &HashMap<String, String>,
     ) -> Vec<PathBuf> {
-        let variant = self.variant(variant_kind).unwrap(); // TODO: for now
+        let variant = self.variant(variant_kind, parameter_values).unwrap(); // TODO: for now
         all_files
             .into_iter()
             .filter(|path| !variant.skip_file(base, path))
@@ -496,13 +564,20 @@ impl Template {
 
 impl TemplateParameter {
     fn from_raw(id: &str, raw: &RawParameter) -> anyhow::Result<Self> {
-        let data_type = TemplateParameterDataType::parse(raw)?;
+        let data_type = TemplateParameterDataType::parse(raw)
+            .with_context(|| format!("Invalid parameter '{id}'"))?;
+        let default_value = raw
+            .default_value
+            .clone()
+            .map(|v| data_type.validate_value(v))
+            .transpose()
+            .with_context(|| format!("Invalid default for parameter '{id}'"))?;
 
         Ok(Self {
             id: id.to_owned(),
             data_type,
             prompt: raw.prompt.clone(),
-            default_value: raw.default_value.clone(),
+            default_value,
         })
     }
 
@@ -522,22 +597,77 @@ impl TemplateParameter {
         &self.default_value
     }
 
+    /// Validates a value for the parameter, returning it in normalised form
+    /// (e.g. `yes` becomes `true` for a boolean parameter).
     pub fn validate_value(&self, value: impl AsRef<str>) -> anyhow::Result<String> {
         self.data_type.validate_value(value.as_ref().to_owned())
     }
+
+    /// Converts a (validated) value to the type used when rendering templates,
+    /// so that booleans and integers behave as expected in Liquid conditions.
+    pub fn liquid_value(&self, value: String) -> liquid_core::Value {
+        match self.data_type {
+            TemplateParameterDataType::Bool => match value.parse::<bool>() {
+                Ok(b) => liquid_core::Value::scalar(b),
+                Err(_) => liquid_core::Value::scalar(value),
+            },
+            TemplateParameterDataType::Integer(_) => match value.parse::<i64>() {
+                Ok(i) => liquid_core::Value::scalar(i),
+                Err(_) => liquid_core::Value::scalar(value),
+            },
+            TemplateParameterDataType::String(_) | TemplateParameterDataType::Choice(_) => {
+                liquid_core::Value::scalar(value)
+            }
+        }
+    }
 }
 
 impl TemplateParameterDataType {
     fn parse(raw: &RawParameter) -> anyhow::Result<Self> {
-        match &raw.data_type[..] {
-            "string" => Ok(Self::String(parse_string_constraints(raw)?)),
-            _ => Err(anyhow!("Unrecognised data type '{}'", raw.data_type)),
+        let data_type = match &raw.data_type[..] {
+            "string" => Self::String(parse_string_constraints(raw)?),
+            "bool" => Self::Bool,
+            "choice" => Self::Choice(parse_choice_constraints(raw)?),
+            "integer" => Self::Integer(IntegerConstraints {
+                min: raw.min,
+                max: raw.max,
+            }),
+            _ => return Err(anyhow!("Unrecognised data type '{}'", raw.data_type)),
+        };
+        data_type.check_constraint_fields(raw)?;
+        Ok(data_type)
+    }
+
+    // Rejects constraints that don't apply to the data type, as they indicate
+    // a mistake in the template.
+    fn check_constraint_fields(&self, raw: &RawParameter) -> anyhow::Result<()> {
+        let unexpected = |field: &str| {
+            Err(anyhow!(
+                "'{field}' is not valid for parameters of type '{}'",
+                raw.data_type
+            ))
+        };
+        if raw.pattern.is_some() && !matches!(self, Self::String(_)) {
+            return unexpected("pattern");
+        }
+        if raw.choices.is_some() && !matches!(self, Self::Choice(_)) {
+            return unexpected("choices");
+        }
+        if raw.min.is_some() && !matches!(self, Self::Integer(_)) {
+            return unexpected("min");
+        }
+        if raw.max.is_some() && !matches!(self, Self::Integer(_)) {
+            return unexpected("max");
         }
+        Ok(())
     }
 
     fn validate_value(&self, value: String) -> anyhow::Result<String> {
         match self {
             TemplateParameterDataType::String(constraints) => constraints.validate(value),
+            TemplateParameterDataType::Bool => validate_bool(value),
+            TemplateParameterDataType::Choice(constraints) => constraints.validate(value),
+            TemplateParameterDataType::Integer(constraints) => constraints.validate(value),
         }
     }
 }
@@ -556,6 +686,23 @@ impl ExtraOutputAction {
                     create.at.unwrap_or_default(),
                 )
             }
+            RawExtraOutput::RunCommand(run) => {
+                let command_template =
+                    liquid::Parser::new().parse(&run.command).with_context(|| {
+                        format!("Template error: output {id} command is not a valid template")
+                    })?;
+                let path_template = liquid::Parser::new()
+                    .parse(run.path.as_deref().unwrap_or_default())
+                    .with_context(|| {
+                        format!("Template error: output {id} path is not a valid template")
+                    })?;
+                Self::RunCommand {
+                    command: run.command.clone(),
+                    command_template: std::sync::Arc::new(command_template),
+                    path_template: std::sync::Arc::new(path_template),
+                    at: run.at.unwrap_or_default(),
+                }
+            }
         })
     }
 }
@@ -572,10 +719,14 @@ impl TemplateVariant {
         self.skip_parameters.iter().any(|p| &parameter.id == p)
     }
 
-    fn resolve_conditions(&self, variant_info: &TemplateVariantInfo) -> Self {
+    fn resolve_conditions(
+        &self,
+        variant_info: &TemplateVariantInfo,
+        parameter_values: &HashMap<String, String>,
+    ) -> Self {
         let mut resolved = self.clone();
         for condition in &self.conditions {
-            if condition.condition.is_true(variant_info) {
+            if condition.condition.is_true(variant_info, parameter_values) {
                 resolved
                     .skip_files
                     .append(&mut condition.skip_files.clone());
@@ -592,32 +743,83 @@ impl TemplateVariant {
 }
 
 impl Condition {
-    fn is_true(&self, variant_info: &TemplateVariantInfo) -> bool {
+    fn is_true(
+        &self,
+        variant_info: &TemplateVariantInfo,
+        parameter_values: &HashMap<String, String>,
+    ) -> bool {
         match self {
-            Self::ManifestEntryExists(path) => match variant_info {
-                TemplateVariantInfo::NewApplication => false,
-                TemplateVariantInfo::AddComponent { manifest_path } => {
-                    let Ok(toml_text) = std::fs::read_to_string(manifest_path) else {
-                        return false;
-                    };
-                    let Ok(table) = toml::from_str::<toml::Value>(&toml_text) else {
-                        return false;
-                    };
-                    crate::toml::get_at(table, path).is_some()
+            Self::ManifestEntryExists(path) => {
+                let Some(table) = existing_manifest(variant_info) else {
+                    return false;
+                };
+                crate::toml::get_at(table, path).is_some()
+            }
+            Self::ParameterEquals(name, value) => parameter_values.get(name) == Some(value),
+            Self::TriggerTypeExists(trigger_type) => {
+                let Some(table) = existing_manifest(variant_info) else {
+                    return false;
+                };
+                // Version 2 manifests have `[[trigger.<type>]]` tables; version 1
+                // manifests have a single `trigger = { type = "<type>" }`.
+                let v2_trigger =
+                    crate::toml::get_at(table.clone(), &["trigger", trigger_type.as_str()]);
+                let v1_trigger = crate::toml::get_at(table, &["trigger", "type"]);
+                v2_trigger.is_some()
+                    || v1_trigger.as_ref().and_then(|t| t.as_str()) == Some(trigger_type)
+            }
+            Self::ComponentExists(name) => {
+                let Some(table) = existing_manifest(variant_info) else {
+                    return false;
+                };
+                // Version 2 manifests have `[component.<name>]` tables; version 1
+                // manifests have `[[component]]` tables with an `id`.
+                match crate::toml::get_at(table, &["component"]) {
+                    Some(toml::Value::Table(components)) => components.contains_key(name),
+                    Some(toml::Value::Array(components)) => components
+                        .iter()
+                        .any(|c| c.get("id").and_then(|id| id.as_str()) == Some(name)),
+                    _ => false,
                 }
+            }
+            Self::FileExists(path) => match variant_info {
+                TemplateVariantInfo::NewApplication => false,
+                TemplateVariantInfo::AddComponent { manifest_path } => manifest_path
+                    .parent()
+                    .is_some_and(|app_dir| app_dir.join(path).exists()),
             },
+            Self::Not(inner) => !inner.is_true(variant_info, parameter_values),
             #[cfg(test)]
             Self::Always(b) => *b,
         }
     }
 }
 
+/// The manifest of the application to which a component is being added, if any.
+fn existing_manifest(variant_info: &TemplateVariantInfo) -> Option<toml::Value> {
+    match variant_info {
+        TemplateVariantInfo::NewApplication => None,
+        TemplateVariantInfo::AddComponent { manifest_path } => {
+            let toml_text = std::fs::read_to_string(manifest_path).ok()?;
+            toml::from_str::<toml::Value>(&toml_text).ok()
+        }
+    }
+}
+
 fn parse_string_constraints(raw: &RawParameter) -> anyhow::Result<StringConstraints> {
     let regex = raw.pattern.as_ref().map(|re| Regex::new(re)).transpose()?;
 
     Ok(StringConstraints { regex })
 }
 
+fn parse_choice_constraints(raw: &RawParameter) -> anyhow::Result<ChoiceConstraints> {
+    let choices = raw.choices.clone().unwrap_or_default();
+    if choices.is_empty() {
+        anyhow::bail!("Parameters of type 'choice' must specify at least one choice");
+    }
+    Ok(ChoiceConstraints { choices })
+}
+
 fn read_install_record(layout: &TemplateLayout) -> InstalledFrom {
     use crate::reader::{parse_installed_from, RawInstalledFrom};
 
@@ -625,6 +827,16 @@ fn read_install_record(layout: &TemplateLayout) -> InstalledFrom {
     match installed_from_text.and_then(parse_installed_from) {
         Some(RawInstalledFrom::Git { git }) => InstalledFrom::Git(git),
         Some(RawInstalledFrom::File { dir }) => InstalledFrom::Directory(dir),
+        Some(RawInstalledFrom::Tarball {
+            tarball,
+            digest,
+            pinned,
+        }) => InstalledFrom::Tarball {
+            url: tarball,
+            digest,
+            pinned,
+        },
+        Some(RawInstalledFrom::Oci { oci }) => InstalledFrom::Oci(oci),
         None => InstalledFrom::Unknown,
     }
 }
@@ -639,6 +851,23 @@ fn validate_v1_manifest(raw: &RawTemplateManifestV1) -> anyhow::Result<()> {
     if raw.custom_filters.is_some() {
         anyhow::bail!("Custom filters are not supported in this version of Spin. Please update your template.");
     }
+    let variants = [&raw.new_application, &raw.add_component];
+    let conditionals = variants
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.conditions.as_ref())
+        .flatten();
+    for (id, conditional) in conditionals {
+        // Parameters are prompted for before their values are known, so a
+        // condition on parameter values can't be used to skip parameters.
+        let skips_parameters = conditional
+            .skip_parameters
+            .as_ref()
+            .is_some_and(|p| !p.is_empty());
+        if skips_parameters && conditional.condition.depends_on_parameters() {
+            anyhow::bail!("Condition '{id}' depends on parameter values, so cannot skip parameters. Use skip_files or skip_snippets instead.");
+        }
+    }
     Ok(())
 }
 
@@ -672,7 +901,7 @@ mod test {
         let condition = Template::parse_condition(RawCondition::ManifestEntryExists(
             "application.trigger.redis".to_owned(),
         ));
-        assert!(!condition.is_true(&TemplateVariantInfo::NewApplication));
+        assert!(!condition.is_true(&TemplateVariantInfo::NewApplication, &HashMap::new()));
     }
 
     #[test]
@@ -682,9 +911,12 @@ mod test {
         let condition = Template::parse_condition(RawCondition::ManifestEntryExists(
             "application.trigger.redis".to_owned(),
         ));
-        assert!(!condition.is_true(&TemplateVariantInfo::AddComponent {
-            manifest_path: temp_file.path()
-        }));
+        assert!(!condition.is_true(
+            &TemplateVariantInfo::AddComponent {
+                manifest_path: temp_file.path()
+            },
+            &HashMap::new()
+        ));
     }
 
     #[test]
@@ -695,9 +927,12 @@ mod test {
         let condition = Template::parse_condition(RawCondition::ManifestEntryExists(
             "application.trigger.redis".to_owned(),
         ));
-        assert!(condition.is_true(&TemplateVariantInfo::AddComponent {
-            manifest_path: temp_file.path()
-        }));
+        assert!(condition.is_true(
+            &TemplateVariantInfo::AddComponent {
+                manifest_path: temp_file.path()
+            },
+            &HashMap::new()
+        ));
     }
 
     #[test]
@@ -705,9 +940,12 @@ mod test {
         let condition = Template::parse_condition(RawCondition::ManifestEntryExists(
             "application.trigger.redis".to_owned(),
         ));
-        assert!(!condition.is_true(&TemplateVariantInfo::AddComponent {
-            manifest_path: PathBuf::from("this/file/does/not.exist")
-        }));
+        assert!(!condition.is_true(
+            &TemplateVariantInfo::AddComponent {
+                manifest_path: PathBuf::from("this/file/does/not.exist")
+            },
+            &HashMap::new()
+        ));
     }
 
     #[test]
@@ -751,7 +989,7 @@ mod test {
         };
 
         let variant_info = TemplateVariantInfo::NewApplication;
-        let variant = template.variant(&variant_info).unwrap();
+        let variant = template.variant(&variant_info, &HashMap::new()).unwrap();
         assert!(variant.skip_files.is_empty());
         assert!(variant.skip_parameters.is_empty());
         assert!(variant.snippets.is_empty());
@@ -759,7 +997,9 @@ mod test {
         let add_variant_info = TemplateVariantInfo::AddComponent {
             manifest_path: PathBuf::from("dummy"),
         };
-        let add_variant = template.variant(&add_variant_info).unwrap();
+        let add_variant = template
+            .variant(&add_variant_info, &HashMap::new())
+            .unwrap();
         // the conditional skip_files and skip_parameters are added to the variant's skip lists
         assert_eq!(2, add_variant.skip_files.len());
         assert!(add_variant.skip_files.contains(&"test1".to_owned()));
@@ -771,4 +1011,124 @@ mod test {
         assert!(!add_variant.snippets.contains_key("s1"));
         assert!(add_variant.snippets.contains_key("s2"));
     }
+
+    fn parse_parameter(toml_text: &str) -> anyhow::Result<TemplateParameter> {
+        let raw: RawParameter = toml::from_str(toml_text)?;
+        TemplateParameter::from_raw("p", &raw)
+    }
+
+    #[test]
+    fn bool_parameters_accept_yes_no_and_render_as_bools() {
+        let parameter =
+            parse_parameter("type = \"bool\"\nprompt = \"Enable?\"\ndefault = true").unwrap();
+        assert_eq!(&Some("true".to_owned()), parameter.default_value());
+        assert_eq!("false", parameter.validate_value("No").unwrap());
+        assert_eq!("true", parameter.validate_value("y").unwrap());
+        parameter.validate_value("maybe").unwrap_err();
+        assert_eq!(
+            liquid_core::Value::scalar(false),
+            parameter.liquid_value("false".to_owned())
+        );
+    }
+
+    #[test]
+    fn choice_parameters_accept_only_choices() {
+        let parameter = parse_parameter(
+            "type = \"choice\"\nprompt = \"Framework\"\nchoices = [\"axum\", \"hyper\"]",
+        )
+        .unwrap();
+        assert_eq!("hyper", parameter.validate_value("hyper").unwrap());
+        parameter.validate_value("rocket").unwrap_err();
+
+        parse_parameter("type = \"choice\"\nprompt = \"Framework\"\nchoices = []").unwrap_err();
+        parse_parameter(
+            "type = \"choice\"\nprompt = \"Framework\"\nchoices = [\"axum\"]\ndefault = \"rocket\"",
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn integer_parameters_respect_bounds() {
+        let parameter = parse_parameter(
+            "type = \"integer\"\nprompt = \"Port\"\nmin = 1\nmax = 65535\ndefault = 3000",
+        )
+        .unwrap();
+        assert_eq!(&Some("3000".to_owned()), parameter.default_value());
+        assert_eq!("8080", parameter.validate_value(" 8080").unwrap());
+        parameter.validate_value("0").unwrap_err();
+        parameter.validate_value("eighty").unwrap_err();
+        assert_eq!(
+            liquid_core::Value::scalar(8080i64),
+            parameter.liquid_value("8080".to_owned())
+        );
+    }
+
+    #[test]
+    fn constraints_must_match_parameter_type() {
+        parse_parameter("type = \"bool\"\nprompt = \"Enable?\"\npattern = \"y\"").unwrap_err();
+        parse_parameter("type = \"string\"\nprompt = \"Name\"\nmin = 1").unwrap_err();
+    }
+
+    fn parse_raw_condition(toml_text: &str) -> anyhow::Result<Condition> {
+        let value: toml::Value = toml::from_str(toml_text)?;
+        let raw = RawCondition::try_from(value)?;
+        Ok(Template::parse_condition(raw))
+    }
+
+    #[test]
+    fn parameter_equals_condition_checks_value() {
+        let condition =
+            parse_raw_condition("parameter_equals = { name = \"sqlite\", value = true }").unwrap();
+        let values = [("sqlite".to_owned(), "true".to_owned())]
+            .into_iter()
+            .collect();
+        assert!(condition.is_true(&TemplateVariantInfo::NewApplication, &values));
+        assert!(!condition.is_true(&TemplateVariantInfo::NewApplication, &HashMap::new()));
+
+        let negated =
+            parse_raw_condition("not = { parameter_equals = { name = \"sqlite\", value = true } }")
+                .unwrap();
+        assert!(!negated.is_true(&TemplateVariantInfo::NewApplication, &values));
+    }
+
+    #[test]
+    fn existing_app_conditions_inspect_manifest() {
+        let temp_file = make_temp_manifest(
+            "spin_manifest_version = 2\n[[trigger.redis]]\ncomponent = \"hello\"\n[component.hello]\nsource = \"hello.wasm\"",
+        );
+        let variant_info = TemplateVariantInfo::AddComponent {
+            manifest_path: temp_file.path(),
+        };
+        let is_true = |toml_text: &str| {
+            parse_raw_condition(toml_text)
+                .unwrap()
+                .is_true(&variant_info, &HashMap::new())
+        };
+
+        assert!(is_true("trigger_type_exists = \"redis\""));
+        assert!(!is_true("trigger_type_exists = \"http\""));
+        assert!(is_true("component_exists = \"hello\""));
+        assert!(!is_true("component_exists = \"goodbye\""));
+        assert!(is_true("file_exists = \"spin.toml\""));
+        assert!(!is_true("file_exists = \"Cargo.toml\""));
+    }
+
+    #[test]
+    fn unknown_conditions_are_rejected() {
+        parse_raw_condition("parameter_is_nice = \"p1\"").unwrap_err();
+        parse_raw_condition("parameter_equals = \"p1\"").unwrap_err();
+    }
+
+    #[test]
+    fn parameter_conditions_cannot_skip_parameters() {
+        let manifest = r#"
+            manifest_version = "1"
+            id = "test"
+            [add_component.conditions.sqlite]
+            condition = { parameter_equals = { name = "sqlite", value = false } }
+            skip_parameters = ["db-name"]
+        "#;
+        let RawTemplateManifest::V1(raw) = crate::reader::parse_manifest_toml(manifest).unwrap();
+        validate_v1_manifest(&raw).unwrap_err();
+    }
 }
@@ -40,6 +40,8 @@ async fn new_fileserver_creates_assets_dir() -> anyhow::Result<()> {
         accept_defaults: true,
         no_vcs: false,
         allow_overwrite: false,
+        skip_post_generate: false,
+        allow_run_commands: false,
     };
     manager
         .get("static-fileserver")?
@@ -87,6 +89,8 @@ async fn add_fileserver_creates_assets_dir_next_to_manifest() -> anyhow::Result<
         accept_defaults: true,
         no_vcs: false,
         allow_overwrite: false,
+        skip_post_generate: false,
+        allow_run_commands: false,
     };
     manager
         .get("http-empty")?
@@ -107,6 +111,8 @@ async fn add_fileserver_creates_assets_dir_next_to_manifest() -> anyhow::Result<
         accept_defaults: true,
         no_vcs: false,
         allow_overwrite: false,
+        skip_post_generate: false,
+        allow_run_commands: false,
     };
     manager
         .get("static-fileserver")?
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use tempfile::{tempdir, TempDir};
+use walkdir::WalkDir;
+
+use crate::{
+    source::TemplateSource, store::TemplateLayout, template::Template, RunOptions,
+    TemplateVariantInfo,
+};
+
+/// Name of the file, in the root of a template source, that declares the
+/// template tests.
+pub const TEMPLATE_TESTS_FILE_NAME: &str = "spin-template-tests.toml";
+
+/// Name of the directory, in the root of a template source, that contains
+/// the expected output of each test.
+const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+
+/// Snapshot tests for the templates in a template source directory. Each test
+/// runs a template with a set of parameter values, and compares the generated
+/// files with the snapshot in `snapshots/<test name>`.
+pub struct TemplateTests {
+    root: PathBuf,
+    cases: Vec<TemplateTestCase>,
+}
+
+/// A single snapshot test.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct TemplateTestCase {
+    /// The name of the test. This is also the name of the snapshot directory.
+    pub name: String,
+    /// The ID of the template to run.
+    pub template: String,
+    /// The name of the generated application. Defaults to the test name.
+    pub app_name: Option<String>,
+    /// Values for the template parameters. Parameters that are not given
+    /// take their default values.
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+    /// Whether to build the generated application as part of the test.
+    #[serde(default)]
+    pub build: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTemplateTests {
+    #[serde(default, rename = "test")]
+    tests: Vec<TemplateTestCase>,
+}
+
+/// The output of running a template for a test.
+pub struct RenderedTest {
+    app_dir: PathBuf,
+    snapshot_dir: PathBuf,
+    _temp_dir: TempDir,
+}
+
+/// A difference between the generated files and the snapshot.
+#[derive(Debug)]
+pub enum SnapshotDifference {
+    /// The snapshot contains a file that was not generated.
+    Missing(PathBuf),
+    /// A file was generated that is not in the snapshot.
+    Unexpected(PathBuf),
+    /// The generated file differs from the snapshot. For text files, this
+    /// includes a unified diff from the snapshot to the generated file.
+    Changed(PathBuf, Option<String>),
+}
+
+impl TemplateTests {
+    /// Loads the tests for the template source in `dir`.
+    pub fn load(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = dir.as_ref().to_owned();
+        let tests_file = root.join(TEMPLATE_TESTS_FILE_NAME);
+        let text = std::fs::read_to_string(&tests_file)
+            .with_context(|| format!("Failed to read {}", tests_file.display()))?;
+        let raw: RawTemplateTests = toml::from_str(&text)
+            .with_context(|| format!("Invalid template tests file {}", tests_file.display()))?;
+
+        let mut names = BTreeSet::new();
+        for case in &raw.tests {
+            if !names.insert(&case.name) {
+                anyhow::bail!(
+                    "Template tests file {} contains more than one test named '{}'",
+                    tests_file.display(),
+                    case.name
+                );
+            }
+        }
+
+        Ok(Self {
+            root,
+            cases: raw.tests,
+        })
+    }
+
+    /// The tests in the order they are declared.
+    pub fn cases(&self) -> &[TemplateTestCase] {
+        &self.cases
+    }
+
+    /// Runs the template for a test, generating files into a temporary
+    /// directory.
+    pub async fn render(&self, case: &TemplateTestCase) -> anyhow::Result<RenderedTest> {
+        let template = self.find_template(&case.template).await?;
+
+        let temp_dir = tempdir()?;
+        let app_name = case.app_name.clone().unwrap_or_else(|| case.name.clone());
+        let app_dir = temp_dir.path().join(&app_name);
+
+        let options = RunOptions {
+            variant: TemplateVariantInfo::NewApplication,
+            name: app_name,
+            output_path: app_dir.clone(),
+            values: case.values.clone(),
+            accept_defaults: true,
+            no_vcs: false,
+            allow_overwrite: false,
+            skip_post_generate: true,
+            allow_run_commands: false,
+        };
+        template
+            .run(options)
+            .silent()
+            .await
+            .with_context(|| format!("Failed to run template {}", case.template))?;
+
+        Ok(RenderedTest {
+            app_dir,
+            snapshot_dir: self.root.join(SNAPSHOTS_DIR_NAME).join(&case.name),
+            _temp_dir: temp_dir,
+        })
+    }
+
+    async fn find_template(&self, id: &str) -> anyhow::Result<Template> {
+        let local_source = TemplateSource::File(self.root.clone()).get_local().await?;
+        for template_dir in local_source.template_directories().await? {
+            let template = Template::load_from(&TemplateLayout::new(&template_dir))
+                .with_context(|| format!("Invalid template in {}", template_dir.display()))?;
+            if template.id() == id {
+                return Ok(template);
+            }
+        }
+        Err(anyhow!(
+            "Template source {} does not contain a template '{}'",
+            self.root.display(),
+            id
+        ))
+    }
+}
+
+impl RenderedTest {
+    /// The directory containing the generated application.
+    pub fn app_dir(&self) -> &Path {
+        &self.app_dir
+    }
+
+    /// Whether the test has a snapshot to compare against.
+    pub fn has_snapshot(&self) -> bool {
+        self.snapshot_dir.is_dir()
+    }
+
+    /// Compares the generated files with the snapshot.
+    pub fn compare(&self) -> anyhow::Result<Vec<SnapshotDifference>> {
+        compare_trees(&self.snapshot_dir, &self.app_dir)
+    }
+
+    /// Replaces the snapshot with the generated files.
+    pub fn update_snapshot(&self) -> anyhow::Result<()> {
+        if self.snapshot_dir.exists() {
+            std::fs::remove_dir_all(&self.snapshot_dir).with_context(|| {
+                format!(
+                    "Failed to remove old snapshot {}",
+                    self.snapshot_dir.display()
+                )
+            })?;
+        }
+        for relative_path in relative_files(&self.app_dir)? {
+            let dest = self.snapshot_dir.join(&relative_path);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(self.app_dir.join(&relative_path), &dest)
+                .with_context(|| format!("Failed to write snapshot file {}", dest.display()))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SnapshotDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "{} was not generated", path.display()),
+            Self::Unexpected(path) => write!(f, "{} is not in the snapshot", path.display()),
+            Self::Changed(path, None) => write!(f, "{} differs from the snapshot", path.display()),
+            Self::Changed(path, Some(diff)) => {
+                write!(f, "{} differs from the snapshot:\n{}", path.display(), diff)
+            }
+        }
+    }
+}
+
+fn compare_trees(expected: &Path, actual: &Path) -> anyhow::Result<Vec<SnapshotDifference>> {
+    let expected_files = if expected.exists() {
+        relative_files(expected)?
+    } else {
+        BTreeSet::new()
+    };
+    let actual_files = relative_files(actual)?;
+
+    let mut differences = vec![];
+    for path in expected_files.union(&actual_files) {
+        match (expected_files.contains(path), actual_files.contains(path)) {
+            (true, false) => differences.push(SnapshotDifference::Missing(path.clone())),
+            (false, true) => differences.push(SnapshotDifference::Unexpected(path.clone())),
+            _ => {
+                let expected_bytes = std::fs::read(expected.join(path))?;
+                let actual_bytes = std::fs::read(actual.join(path))?;
+                if expected_bytes != actual_bytes {
+                    let diff = text_diff(&expected_bytes, &actual_bytes);
+                    differences.push(SnapshotDifference::Changed(path.clone(), diff));
+                }
+            }
+        }
+    }
+    Ok(differences)
+}
+
+fn text_diff(expected: &[u8], actual: &[u8]) -> Option<String> {
+    let expected = std::str::from_utf8(expected).ok()?;
+    let actual = std::str::from_utf8(actual).ok()?;
+    let diff = similar::TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .header("snapshot", "generated")
+        .to_string();
+    Some(diff)
+}
+
+fn relative_files(dir: &Path) -> anyhow::Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let relative_path = entry.path().strip_prefix(dir)?.to_owned();
+            files.insert(relative_path);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compare_reports_missing_unexpected_and_changed_files() {
+        let expected = tempdir().unwrap();
+        let actual = tempdir().unwrap();
+        std::fs::write(expected.path().join("same.txt"), "same\n").unwrap();
+        std::fs::write(actual.path().join("same.txt"), "same\n").unwrap();
+        std::fs::write(expected.path().join("missing.txt"), "gone\n").unwrap();
+        std::fs::write(actual.path().join("unexpected.txt"), "new\n").unwrap();
+        std::fs::write(expected.path().join("changed.txt"), "before\n").unwrap();
+        std::fs::write(actual.path().join("changed.txt"), "after\n").unwrap();
+
+        let differences = compare_trees(expected.path(), actual.path()).unwrap();
+        assert_eq!(3, differences.len());
+        assert!(
+            matches!(&differences[0], SnapshotDifference::Changed(p, Some(diff)) if p == Path::new("changed.txt") && diff.contains("+after"))
+        );
+        assert!(
+            matches!(&differences[1], SnapshotDifference::Missing(p) if p == Path::new("missing.txt"))
+        );
+        assert!(
+            matches!(&differences[2], SnapshotDifference::Unexpected(p) if p == Path::new("unexpected.txt"))
+        );
+    }
+
+    #[tokio::test]
+    async fn can_render_and_snapshot_built_in_template() {
+        let built_ins_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let tests = TemplateTests {
+            root: built_ins_dir,
+            cases: vec![],
+        };
+        let case = TemplateTestCase {
+            name: "empty".to_owned(),
+            template: "http-empty".to_owned(),
+            app_name: None,
+            values: HashMap::new(),
+            build: false,
+        };
+
+        let rendered = tests.render(&case).await.unwrap();
+        assert!(rendered.app_dir().join("spin.toml").exists());
+
+        // Snapshotting into a scratch directory must make the comparison pass.
+        let snapshot_root = tempdir().unwrap();
+        let rendered = RenderedTest {
+            snapshot_dir: snapshot_root.path().join("empty"),
+            ..rendered
+        };
+        assert!(!rendered.has_snapshot());
+        rendered.update_snapshot().unwrap();
+        assert!(rendered.compare().unwrap().is_empty());
+    }
+}
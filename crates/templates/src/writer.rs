@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 
@@ -11,6 +11,7 @@ pub(crate) enum TemplateOutput {
     AppendToml(PathBuf, String),
     MergeToml(PathBuf, &'static str, String), // only have to worry about merging into root table for now
     CreateDirectory(PathBuf),
+    RunCommand(PathBuf, String), // directory to run in, command to run
 }
 
 impl TemplateOutputs {
@@ -18,8 +19,32 @@ impl TemplateOutputs {
         Self { outputs }
     }
 
+    /// Removes any post-generation commands for which `allow` returns false.
+    pub fn retain_commands(
+        &mut self,
+        mut allow: impl FnMut(&Path, &str) -> anyhow::Result<bool>,
+    ) -> anyhow::Result<()> {
+        let mut retained = Vec::with_capacity(self.outputs.len());
+        for output in std::mem::take(&mut self.outputs) {
+            if let TemplateOutput::RunCommand(dir, command) = &output {
+                if !allow(dir, command)? {
+                    continue;
+                }
+            }
+            retained.push(output);
+        }
+        self.outputs = retained;
+        Ok(())
+    }
+
     pub async fn write(&self) -> anyhow::Result<()> {
-        for output in &self.outputs {
+        // Post-generation commands run once all files are in place, in the
+        // order the template declares them.
+        let (commands, files): (Vec<_>, Vec<_>) = self
+            .outputs
+            .iter()
+            .partition(|o| matches!(o, TemplateOutput::RunCommand(..)));
+        for output in files.into_iter().chain(commands) {
             output.write().await?;
         }
         Ok(())
@@ -63,11 +88,34 @@ impl TemplateOutput {
                     .await
                     .with_context(|| format!("Failed to create directory {}", dir.display()))?;
             }
+            TemplateOutput::RunCommand(dir, command) => {
+                terminal::step!("Running", "`{}` in {}", command, dir.display());
+                let status = shell_command(command)
+                    .current_dir(dir)
+                    .status()
+                    .await
+                    .with_context(|| format!("Failed to run `{command}`"))?;
+                if !status.success() {
+                    anyhow::bail!("Post-generation command `{command}` failed: {status}");
+                }
+            }
         }
         Ok(())
     }
 }
 
+fn shell_command(command: &str) -> tokio::process::Command {
+    if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
 fn merge_toml(existing: &str, target: &str, text: &str) -> anyhow::Result<String> {
     use toml_edit::{DocumentMut, Entry, Item};
 
@@ -160,4 +208,27 @@ id = "dummy"
             new
         );
     }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn runs_allowed_commands_after_writing_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().to_owned();
+
+        let mut outputs = TemplateOutputs::new(vec![
+            TemplateOutput::RunCommand(dir.clone(), "cp source.txt copied.txt".to_owned()),
+            TemplateOutput::RunCommand(dir.clone(), "touch disallowed.txt".to_owned()),
+            TemplateOutput::WriteFile(dir.join("source.txt"), b"hello".to_vec()),
+        ]);
+        outputs
+            .retain_commands(|_, command| Ok(!command.contains("disallowed")))
+            .unwrap();
+        outputs.write().await.unwrap();
+
+        assert_eq!(
+            "hello",
+            std::fs::read_to_string(dir.join("copied.txt")).unwrap()
+        );
+        assert!(!dir.join("disallowed.txt").exists());
+    }
 }
@@ -3,8 +3,11 @@
 mod headers;
 mod instrument;
 mod outbound_http;
+mod pool;
 mod server;
 mod spin;
+#[cfg(test)]
+mod testing;
 mod tls;
 mod wagi;
 mod wasi;
@@ -21,7 +24,7 @@ use clap::Args;
 use serde::Deserialize;
 use spin_app::App;
 use spin_factors::RuntimeFactors;
-use spin_trigger::Trigger;
+use spin_trigger::{ShutdownSignal, Trigger};
 use wasmtime_wasi_http::bindings::http::types::ErrorCode;
 
 pub use server::HttpServer;
@@ -37,6 +40,12 @@ pub(crate) type TriggerApp<F> = spin_trigger::TriggerApp<HttpTrigger, F>;
 pub(crate) type TriggerInstanceBuilder<'a, F> =
     spin_trigger::TriggerInstanceBuilder<'a, HttpTrigger, F>;
 
+/// A [`spin_trigger::TriggerInstanceHooks`] for the HTTP trigger.
+pub(crate) type TriggerInstanceHooks<F> = spin_trigger::TriggerInstanceHooks<HttpTrigger, F>;
+
+/// A [`spin_trigger::Store`] for the HTTP trigger.
+pub(crate) type Store<F> = spin_trigger::Store<HttpTrigger, F>;
+
 #[derive(Args)]
 pub struct CliArgs {
     /// IP address and port to listen on
@@ -93,6 +102,41 @@ impl<F: RuntimeFactors> Trigger<F> for HttpTrigger {
         Ok(())
     }
 
+    fn supports_reload() -> bool {
+        true
+    }
+
+    async fn run_with_reloads(
+        self,
+        trigger_app: TriggerApp<F>,
+        reloads: tokio::sync::mpsc::Receiver<TriggerApp<F>>,
+    ) -> anyhow::Result<()> {
+        self.run_until_shutdown(trigger_app, reloads, ShutdownSignal::never())
+            .await
+    }
+
+    async fn run_until_shutdown(
+        self,
+        trigger_app: TriggerApp<F>,
+        mut reloads: tokio::sync::mpsc::Receiver<TriggerApp<F>>,
+        shutdown: ShutdownSignal,
+    ) -> anyhow::Result<()> {
+        let server = self.into_server(trigger_app)?;
+
+        let reload_server = server.clone();
+        tokio::spawn(async move {
+            while let Some(trigger_app) = reloads.recv().await {
+                if let Err(err) = reload_server.reload(trigger_app) {
+                    tracing::error!("Failed to reload application: {err:?}");
+                }
+            }
+        });
+
+        server.serve_until_shutdown(shutdown).await?;
+
+        Ok(())
+    }
+
     fn supported_host_requirements() -> Vec<&'static str> {
         vec![spin_app::locked::SERVICE_CHAINING_KEY]
     }
@@ -0,0 +1,277 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use spin_core::Instance;
+use spin_factors::RuntimeFactors;
+use spin_http::config::InstancePoolConfig;
+
+use crate::{Store, TriggerInstanceBuilder, TriggerInstanceHooks};
+
+/// An instance of a component, ready to handle a request.
+pub(crate) struct ReadyInstance<F: RuntimeFactors> {
+    pub instance: Instance,
+    pub store: Store<F>,
+    hooks: TriggerInstanceHooks<F>,
+    component_id: String,
+    /// The pool to return the instance to once it has handled a request, if
+    /// the instance is reusable.
+    pool: Weak<InstancePool<F>>,
+    /// The number of requests the instance has handled.
+    uses: usize,
+}
+
+impl<F: RuntimeFactors> ReadyInstance<F> {
+    /// Instantiate the component being built by the given builder.
+    pub async fn instantiate(
+        instance_builder: TriggerInstanceBuilder<'_, F>,
+    ) -> anyhow::Result<Self> {
+        let hooks = instance_builder.instance_hooks();
+        let component_id = instance_builder.app_component().id().to_owned();
+        let (instance, store) = instance_builder.instantiate(()).await?;
+        Ok(Self {
+            instance,
+            store,
+            hooks,
+            component_id,
+            pool: Weak::new(),
+            uses: 0,
+        })
+    }
+
+    /// Finish handling a request with the given outcome.
+    ///
+    /// This runs the after-execute hooks, then returns the instance to its
+    /// pool if it is reusable.
+    pub fn finish(mut self, outcome: Result<(), &anyhow::Error>) {
+        self.hooks.after_execute(&mut self.store, outcome);
+        spin_telemetry::metrics::histogram!(
+            spin.instance_memory_bytes = self.store.data().core_state().memory_consumed() as f64,
+            component_id = self.component_id.as_str()
+        );
+        // An instance whose request failed may have been left in a bad state
+        if outcome.is_ok() {
+            if let Some(pool) = self.pool.upgrade() {
+                // A discarded instance must be dropped after `put_back`
+                // releases the pool's lock
+                let discarded = pool.put_back(self);
+                drop(discarded);
+            }
+        }
+    }
+}
+
+impl<F: RuntimeFactors> Drop for ReadyInstance<F> {
+    fn drop(&mut self) {
+        // A reusable instance which isn't returned to its pool frees up room
+        // for a new instance
+        if let Some(pool) = self.pool.upgrade() {
+            pool.state.lock().unwrap().in_use -= 1;
+        }
+    }
+}
+
+/// A pool of instances of a single component, instantiated ahead of requests.
+pub(crate) struct InstancePool<F: RuntimeFactors> {
+    config: InstancePoolConfig,
+    state: Mutex<PoolState<F>>,
+}
+
+struct PoolState<F: RuntimeFactors> {
+    /// Instances ready to handle a request.
+    ready: Vec<ReadyInstance<F>>,
+    /// The number of reusable instances handling a request, which may be
+    /// returned to the pool.
+    in_use: usize,
+    /// Whether instances are being added to the pool.
+    filling: bool,
+}
+
+impl<F: RuntimeFactors> InstancePool<F> {
+    pub fn new(config: InstancePoolConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(PoolState {
+                ready: Vec::new(),
+                in_use: 0,
+                filling: false,
+            }),
+        }
+    }
+
+    /// Take a ready instance from the pool, if there is one.
+    pub fn take(&self) -> Option<ReadyInstance<F>> {
+        let mut state = self.state.lock().unwrap();
+        let instance = state.ready.pop()?;
+        if self.config.reusable {
+            state.in_use += 1;
+        }
+        Some(instance)
+    }
+
+    /// Attach an instance instantiated outside the pool to it, so that it is
+    /// returned to the pool if it is reusable.
+    pub fn adopt(self: &Arc<Self>, mut instance: ReadyInstance<F>) -> ReadyInstance<F> {
+        if self.config.reusable {
+            self.state.lock().unwrap().in_use += 1;
+            instance.pool = Arc::downgrade(self);
+        }
+        instance
+    }
+
+    /// Add a newly instantiated instance to the pool.
+    pub fn add(self: &Arc<Self>, mut instance: ReadyInstance<F>) {
+        if self.config.reusable {
+            instance.pool = Arc::downgrade(self);
+        }
+        self.state.lock().unwrap().ready.push(instance);
+    }
+
+    /// Returns true, and marks the pool as filling, if the pool needs more
+    /// instances and isn't already being filled. Once this returns true, the
+    /// caller should [`add`](Self::add) instances while
+    /// [`needs_instance`](Self::needs_instance) is true, then call
+    /// [`done_filling`](Self::done_filling).
+    pub fn start_filling(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.filling || !self.needs_instance_locked(&state) {
+            return false;
+        }
+        state.filling = true;
+        true
+    }
+
+    /// Whether the pool has fewer instances ready, or in use and possibly
+    /// coming back, than its configured size.
+    pub fn needs_instance(&self) -> bool {
+        self.needs_instance_locked(&self.state.lock().unwrap())
+    }
+
+    pub fn done_filling(&self) {
+        self.state.lock().unwrap().filling = false;
+    }
+
+    fn needs_instance_locked(&self, state: &PoolState<F>) -> bool {
+        state.ready.len() + state.in_use < self.config.size
+    }
+
+    /// Return a reusable instance which has successfully handled a request to
+    /// the pool. If the pool has no room for it, or it has been used the
+    /// maximum number of times, it is returned to be dropped.
+    fn put_back(&self, mut instance: ReadyInstance<F>) -> Option<ReadyInstance<F>> {
+        let mut state = self.state.lock().unwrap();
+        instance.uses += 1;
+        if instance.uses >= self.config.max_uses || state.ready.len() >= self.config.size {
+            return Some(instance);
+        }
+        state.in_use -= 1;
+        state.ready.push(instance);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{trigger_app_with_component, TestFactors},
+        TriggerApp,
+    };
+
+    /// A component exporting a function whose calls must be completed by
+    /// `post_return` before it can be called again.
+    const COMPONENT: &str = r#"
+        (component
+            (core module $m (func (export "run") (result i32) i32.const 42))
+            (core instance $i (instantiate $m))
+            (func (export "run") (result u32) (canon lift (core func $i "run")))
+        )
+    "#;
+
+    async fn pool_and_app(
+        size: usize,
+    ) -> anyhow::Result<(Arc<InstancePool<TestFactors>>, TriggerApp<TestFactors>)> {
+        let app = trigger_app_with_component(Default::default(), COMPONENT).await?;
+        let pool = Arc::new(InstancePool::new(InstancePoolConfig {
+            size,
+            reusable: true,
+            ..Default::default()
+        }));
+        Ok((pool, app))
+    }
+
+    async fn instantiate(
+        app: &TriggerApp<TestFactors>,
+    ) -> anyhow::Result<ReadyInstance<TestFactors>> {
+        ReadyInstance::instantiate(app.prepare("empty")?).await
+    }
+
+    async fn run(ready: &mut ReadyInstance<TestFactors>) -> anyhow::Result<u32> {
+        let func = ready
+            .instance
+            .get_typed_func::<(), (u32,)>(&mut ready.store, "run")?;
+        let (result,) = func.call_async(&mut ready.store, ()).await?;
+        func.post_return_async(&mut ready.store).await?;
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn instance_is_returned_after_post_return() -> anyhow::Result<()> {
+        let (pool, app) = pool_and_app(1).await?;
+        pool.add(instantiate(&app).await?);
+
+        let mut ready = pool.take().expect("pool should have an instance");
+        assert_eq!(run(&mut ready).await?, 42);
+        ready.finish(Ok(()));
+
+        // The returned instance can handle another request
+        let mut ready = pool.take().expect("instance should have been returned");
+        assert_eq!(ready.uses, 1);
+        assert_eq!(run(&mut ready).await?, 42);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn instance_is_dropped_on_error() -> anyhow::Result<()> {
+        let (pool, app) = pool_and_app(1).await?;
+        pool.add(instantiate(&app).await?);
+
+        let ready = pool.take().expect("pool should have an instance");
+        assert!(!pool.needs_instance());
+        ready.finish(Err(&anyhow::anyhow!("request failed")));
+
+        assert!(pool.take().is_none());
+        // The dropped instance frees up room for a new one
+        assert!(pool.needs_instance());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn size_is_honored_under_concurrent_take() -> anyhow::Result<()> {
+        let (pool, app) = pool_and_app(2).await?;
+        pool.add(instantiate(&app).await?);
+        pool.add(instantiate(&app).await?);
+
+        let taken = std::thread::scope(|scope| {
+            let takers = (0..8)
+                .map(|_| scope.spawn(|| pool.take()))
+                .collect::<Vec<_>>();
+            takers
+                .into_iter()
+                .filter_map(|taker| taker.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(taken.len(), 2);
+        // Instances in use count toward the pool's size
+        assert!(!pool.needs_instance());
+        assert!(!pool.start_filling());
+
+        // An instance adopted while the pool is full isn't kept once returned
+        let adopted = pool.adopt(instantiate(&app).await?);
+        for ready in taken.into_iter().chain([adopted]) {
+            ready.finish(Ok(()));
+        }
+        assert!(pool.take().is_some());
+        assert!(pool.take().is_some());
+        assert!(pool.take().is_none());
+        Ok(())
+    }
+}
@@ -1,4 +1,14 @@
-use std::{collections::HashMap, future::Future, io::IsTerminal, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::IsTerminal,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Instant,
+};
 
 use anyhow::{bail, Context};
 use http::{
@@ -22,9 +32,11 @@ use spin_http::{
     routes::{RouteMatch, Router},
     trigger::HandlerType,
 };
+use spin_trigger::ShutdownSignal;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     net::TcpListener,
+    sync::mpsc,
     task,
 };
 use tracing::Instrument;
@@ -34,6 +46,7 @@ use crate::{
     headers::strip_forbidden_headers,
     instrument::{finalize_http_span, http_span, instrument_error, MatchedRoute},
     outbound_http::OutboundHttpInterceptor,
+    pool::{InstancePool, ReadyInstance},
     spin::SpinHttpExecutor,
     wagi::WagiHttpExecutor,
     wasi::WasiHttpExecutor,
@@ -46,14 +59,28 @@ pub struct HttpServer<F: RuntimeFactors> {
     listen_addr: SocketAddr,
     /// The TLS configuration for the server.
     tls_config: Option<TlsConfig>,
+    /// The app being served. This is replaced when the app is reloaded;
+    /// requests already in flight finish on the app they started on.
+    served_app: RwLock<Arc<ServedApp<F>>>,
+    /// Whether to report the listen address and routes as JSON.
+    json_output: bool,
+}
+
+/// An app being served, with the routing information derived from it.
+struct ServedApp<F: RuntimeFactors> {
     /// Request router.
     router: Router,
     /// The app being triggered.
-    trigger_app: TriggerApp<F>,
+    trigger_app: Arc<TriggerApp<F>>,
     // Component ID -> component trigger config
     component_trigger_configs: HashMap<String, HttpTriggerConfig>,
     // Component ID -> handler type
     component_handler_types: HashMap<String, HandlerType>,
+    // Component ID -> pool of ready instances, for components configured with one
+    instance_pools: HashMap<String, Arc<InstancePool<F>>>,
+    /// Set once a reload replaces this app, so that the app is shut down when
+    /// the requests in flight on it have finished.
+    replaced: AtomicBool,
 }
 
 impl<F: RuntimeFactors> HttpServer<F> {
@@ -63,92 +90,88 @@ impl<F: RuntimeFactors> HttpServer<F> {
         tls_config: Option<TlsConfig>,
         trigger_app: TriggerApp<F>,
     ) -> anyhow::Result<Self> {
-        // This needs to be a vec before building the router to handle duplicate routes
-        let component_trigger_configs = Vec::from_iter(
-            trigger_app
-                .app()
-                .trigger_configs::<HttpTriggerConfig>("http")?
-                .into_iter()
-                .map(|(_, config)| (config.component.clone(), config)),
-        );
-
-        // Build router
-        let component_routes = component_trigger_configs
-            .iter()
-            .map(|(component_id, config)| (component_id.as_str(), &config.route));
-        let (router, duplicate_routes) = Router::build("/", component_routes)?;
-        if !duplicate_routes.is_empty() {
-            tracing::error!(
-                "The following component routes are duplicates and will never be used:"
-            );
-            for dup in &duplicate_routes {
-                tracing::error!(
-                    "  {}: {} (duplicate of {})",
-                    dup.replaced_id,
-                    dup.route(),
-                    dup.effective_id,
-                );
-            }
-        }
-        tracing::trace!(
-            "Constructed router: {:?}",
-            router.routes().collect::<Vec<_>>()
-        );
-
-        // Now that router is built we can merge duplicate routes by component
-        let component_trigger_configs = HashMap::from_iter(component_trigger_configs);
-
-        let component_handler_types = component_trigger_configs
-            .iter()
-            .map(|(component_id, trigger_config)| {
-                let handler_type = match &trigger_config.executor {
-                    None | Some(HttpExecutorType::Http) => {
-                        let component = trigger_app.get_component(component_id)?;
-                        HandlerType::from_component(trigger_app.engine().as_ref(), component)?
-                    }
-                    Some(HttpExecutorType::Wagi(wagi_config)) => {
-                        anyhow::ensure!(
-                            wagi_config.entrypoint == "_start",
-                            "Wagi component '{component_id}' cannot use deprecated 'entrypoint' field"
-                        );
-                        HandlerType::Wagi
-                    }
-                };
-                Ok((component_id.clone(), handler_type))
-            })
-            .collect::<anyhow::Result<_>>()?;
         Ok(Self {
             listen_addr,
             tls_config,
-            router,
-            trigger_app,
-            component_trigger_configs,
-            component_handler_types,
+            served_app: RwLock::new(Arc::new(ServedApp::new(trigger_app)?)),
+            json_output: spin_trigger::cli::json_output_requested(),
         })
     }
 
+    /// Replace the app being served. Requests already in flight finish on
+    /// the previous app.
+    pub fn reload(self: &Arc<Self>, trigger_app: TriggerApp<F>) -> anyhow::Result<()> {
+        let served_app = Arc::new(ServedApp::new(trigger_app)?);
+        self.fill_instance_pools(&served_app);
+        let previous = std::mem::replace(&mut *self.served_app.write().unwrap(), served_app);
+        previous.replaced.store(true, Ordering::Release);
+        terminal::step!("Reloaded", "application");
+        tracing::info!("Reloaded application");
+        Ok(())
+    }
+
+    /// The app currently being served.
+    fn served_app(&self) -> Arc<ServedApp<F>> {
+        self.served_app.read().unwrap().clone()
+    }
+
     /// Serve incoming requests over the provided [`TcpListener`].
     pub async fn serve(self: Arc<Self>) -> anyhow::Result<()> {
+        self.serve_until_shutdown(ShutdownSignal::never()).await
+    }
+
+    /// Serve incoming requests until `shutdown` is requested.
+    ///
+    /// On shutdown, the server stops accepting connections, lets requests
+    /// already in flight finish, and then shuts down the app being served.
+    pub async fn serve_until_shutdown(
+        self: Arc<Self>,
+        shutdown: ShutdownSignal,
+    ) -> anyhow::Result<()> {
         let listener = TcpListener::bind(self.listen_addr).await.with_context(|| {
             format!(
                 "Unable to listen on {listen_addr}",
                 listen_addr = self.listen_addr
             )
         })?;
+        self.fill_instance_pools(&self.served_app());
+        // Each connection holds a clone of `in_flight` until it closes, so
+        // `drained` yields `None` once they have all closed.
+        let (in_flight, mut drained) = mpsc::channel::<()>(1);
         if let Some(tls_config) = self.tls_config.clone() {
-            self.serve_https(listener, tls_config).await?;
+            self.clone()
+                .serve_https(listener, tls_config, &shutdown, in_flight)
+                .await?;
         } else {
-            self.serve_http(listener).await?;
+            self.clone()
+                .serve_http(listener, &shutdown, in_flight)
+                .await?;
         }
-        Ok(())
+
+        tracing::info!("Shutting down: waiting for in-flight requests to finish");
+        drained.recv().await;
+        self.served_app().trigger_app.shutdown().await
     }
 
-    async fn serve_http(self: Arc<Self>, listener: TcpListener) -> anyhow::Result<()> {
+    async fn serve_http(
+        self: Arc<Self>,
+        listener: TcpListener,
+        shutdown: &ShutdownSignal,
+        in_flight: mpsc::Sender<()>,
+    ) -> anyhow::Result<()> {
         self.print_startup_msgs("http", &listener)?;
         loop {
-            let (stream, client_addr) = listener.accept().await?;
-            self.clone()
-                .serve_connection(stream, Scheme::HTTP, client_addr);
+            let (stream, client_addr) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                _ = shutdown.requested() => return Ok(()),
+            };
+            self.clone().serve_connection(
+                stream,
+                Scheme::HTTP,
+                client_addr,
+                shutdown.clone(),
+                in_flight.clone(),
+            );
         }
     }
 
@@ -156,15 +179,24 @@ impl<F: RuntimeFactors> HttpServer<F> {
         self: Arc<Self>,
         listener: TcpListener,
         tls_config: TlsConfig,
+        shutdown: &ShutdownSignal,
+        in_flight: mpsc::Sender<()>,
     ) -> anyhow::Result<()> {
         self.print_startup_msgs("https", &listener)?;
         let acceptor = tls_config.server_config()?;
         loop {
-            let (stream, client_addr) = listener.accept().await?;
+            let (stream, client_addr) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                _ = shutdown.requested() => return Ok(()),
+            };
             match acceptor.accept(stream).await {
-                Ok(stream) => self
-                    .clone()
-                    .serve_connection(stream, Scheme::HTTPS, client_addr),
+                Ok(stream) => self.clone().serve_connection(
+                    stream,
+                    Scheme::HTTPS,
+                    client_addr,
+                    shutdown.clone(),
+                    in_flight.clone(),
+                ),
                 Err(err) => tracing::error!(?err, "Failed to start TLS session"),
             }
         }
@@ -200,9 +232,10 @@ impl<F: RuntimeFactors> HttpServer<F> {
             };
         }
 
-        match self.router.route(&path) {
+        let served_app = self.served_app();
+        match served_app.router.route(&path) {
             Ok(route_match) => {
-                self.handle_trigger_route(req, route_match, server_scheme, client_addr)
+                self.handle_served_route(served_app, req, route_match, server_scheme, client_addr)
                     .await
             }
             Err(_) => Self::not_found(NotFoundRouteKind::Normal(path.to_string())),
@@ -212,28 +245,25 @@ impl<F: RuntimeFactors> HttpServer<F> {
     /// Handles a successful route match.
     pub async fn handle_trigger_route(
         self: &Arc<Self>,
-        mut req: Request<Body>,
+        req: Request<Body>,
         route_match: RouteMatch,
         server_scheme: Scheme,
         client_addr: SocketAddr,
     ) -> anyhow::Result<Response<Body>> {
-        set_req_uri(&mut req, server_scheme.clone())?;
-        let app_id = self
-            .trigger_app
-            .app()
-            .get_metadata(APP_NAME_KEY)?
-            .unwrap_or_else(|| "<unnamed>".into());
-
-        let component_id = route_match.component_id();
-
-        spin_telemetry::metrics::monotonic_counter!(
-            spin.request_count = 1,
-            trigger_type = "http",
-            app_id = app_id,
-            component_id = component_id
-        );
+        let served_app = self.served_app();
+        self.handle_served_route(served_app, req, route_match, server_scheme, client_addr)
+            .await
+    }
 
-        let mut instance_builder = self.trigger_app.prepare(component_id)?;
+    /// Returns an instance builder for the given component of the given app,
+    /// with outbound HTTP set up for requests made by the instance.
+    fn prepare_instance<'a>(
+        self: &Arc<Self>,
+        served_app: &'a ServedApp<F>,
+        component_id: &str,
+        server_scheme: Scheme,
+    ) -> anyhow::Result<TriggerInstanceBuilder<'a, F>> {
+        let mut instance_builder = served_app.trigger_app.prepare(component_id)?;
 
         // Set up outbound HTTP request origin and service chaining
         // The outbound HTTP factor is required since both inbound and outbound wasi HTTP
@@ -248,33 +278,161 @@ impl<F: RuntimeFactors> HttpServer<F> {
         outbound_http.set_self_request_origin(origin);
         outbound_http.set_request_interceptor(OutboundHttpInterceptor::new(self.clone()))?;
 
+        Ok(instance_builder)
+    }
+
+    /// Returns an instance of the given component to handle a request, taking
+    /// it from the component's instance pool if it has one with an instance
+    /// ready.
+    async fn ready_instance(
+        self: &Arc<Self>,
+        served_app: &Arc<ServedApp<F>>,
+        component_id: &str,
+        server_scheme: Scheme,
+    ) -> anyhow::Result<ReadyInstance<F>> {
+        let Some(pool) = served_app.instance_pools.get(component_id) else {
+            let instance_builder =
+                self.prepare_instance(served_app, component_id, server_scheme)?;
+            return ReadyInstance::instantiate(instance_builder).await;
+        };
+        let instance = match pool.take() {
+            Some(instance) => instance,
+            None => {
+                tracing::debug!("Instance pool for component {component_id} is empty");
+                let instance_builder =
+                    self.prepare_instance(served_app, component_id, server_scheme)?;
+                pool.adopt(ReadyInstance::instantiate(instance_builder).await?)
+            }
+        };
+        self.fill_instance_pool(served_app, component_id);
+        Ok(instance)
+    }
+
+    /// Starts filling the instance pools of the given app's components.
+    fn fill_instance_pools(self: &Arc<Self>, served_app: &Arc<ServedApp<F>>) {
+        for component_id in served_app.instance_pools.keys() {
+            self.fill_instance_pool(served_app, component_id);
+        }
+    }
+
+    /// Starts instantiating the given component in the background, until its
+    /// instance pool is full.
+    fn fill_instance_pool(self: &Arc<Self>, served_app: &Arc<ServedApp<F>>, component_id: &str) {
+        let Some(pool) = served_app.instance_pools.get(component_id) else {
+            return;
+        };
+        if !pool.start_filling() {
+            return;
+        }
+        let server = self.clone();
+        let served_app = served_app.clone();
+        let pool = pool.clone();
+        let component_id = component_id.to_owned();
+        task::spawn(async move {
+            while pool.needs_instance() {
+                let instance = match server.prepare_instance(
+                    &served_app,
+                    &component_id,
+                    server.server_scheme(),
+                ) {
+                    Ok(instance_builder) => ReadyInstance::instantiate(instance_builder).await,
+                    Err(err) => Err(err),
+                };
+                match instance {
+                    Ok(instance) => pool.add(instance),
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to instantiate component {component_id} for its instance pool: {err:?}"
+                        );
+                        break;
+                    }
+                }
+            }
+            pool.done_filling();
+        });
+    }
+
+    /// The scheme of requests to the server.
+    fn server_scheme(&self) -> Scheme {
+        if self.tls_config.is_some() {
+            Scheme::HTTPS
+        } else {
+            Scheme::HTTP
+        }
+    }
+
+    /// Handles a successful route match on the given app, so that the
+    /// request is handled by the app it was routed on even if the app is
+    /// reloaded meanwhile.
+    async fn handle_served_route(
+        self: &Arc<Self>,
+        served_app: Arc<ServedApp<F>>,
+        mut req: Request<Body>,
+        route_match: RouteMatch,
+        server_scheme: Scheme,
+        client_addr: SocketAddr,
+    ) -> anyhow::Result<Response<Body>> {
+        let start = Instant::now();
+        set_req_uri(&mut req, server_scheme.clone())?;
+        let app_id = served_app
+            .trigger_app
+            .app()
+            .get_metadata(APP_NAME_KEY)?
+            .unwrap_or_else(|| "<unnamed>".into());
+
+        let component_id = route_match.component_id();
+
+        spin_telemetry::metrics::monotonic_counter!(
+            spin.request_count = 1,
+            trigger_type = "http",
+            app_id = app_id,
+            component_id = component_id,
+            route = route_match.raw_route()
+        );
+
         // Prepare HTTP executor
-        let trigger_config = self.component_trigger_configs.get(component_id).unwrap();
-        let handler_type = self.component_handler_types.get(component_id).unwrap();
+        let trigger_config = served_app
+            .component_trigger_configs
+            .get(component_id)
+            .unwrap();
+        let handler_type = served_app
+            .component_handler_types
+            .get(component_id)
+            .unwrap();
         let executor = trigger_config
             .executor
             .as_ref()
             .unwrap_or(&HttpExecutorType::Http);
 
         let res = match executor {
-            HttpExecutorType::Http => match handler_type {
-                HandlerType::Spin => {
-                    SpinHttpExecutor
-                        .execute(instance_builder, &route_match, req, client_addr)
-                        .await
-                }
-                HandlerType::Wasi0_2
-                | HandlerType::Wasi2023_11_10
-                | HandlerType::Wasi2023_10_18 => {
-                    WasiHttpExecutor {
-                        handler_type: *handler_type,
+            HttpExecutorType::Http => {
+                async {
+                    let instance = self
+                        .ready_instance(&served_app, component_id, server_scheme)
+                        .await?;
+                    match handler_type {
+                        HandlerType::Spin => {
+                            SpinHttpExecutor
+                                .execute(instance, &route_match, req, client_addr)
+                                .await
+                        }
+                        HandlerType::Wasi0_2
+                        | HandlerType::Wasi2023_11_10
+                        | HandlerType::Wasi2023_10_18 => {
+                            WasiHttpExecutor {
+                                handler_type: *handler_type,
+                            }
+                            .execute(instance, &route_match, req, client_addr)
+                            .await
+                        }
+                        HandlerType::Wagi => unreachable!(),
                     }
-                    .execute(instance_builder, &route_match, req, client_addr)
-                    .await
                 }
-                HandlerType::Wagi => unreachable!(),
-            },
+                .await
+            }
             HttpExecutorType::Wagi(wagi_config) => {
+                let instance_builder =
+                    self.prepare_instance(&served_app, component_id, server_scheme)?;
                 let executor = WagiHttpExecutor {
                     wagi_config: wagi_config.clone(),
                 };
@@ -283,6 +441,13 @@ impl<F: RuntimeFactors> HttpServer<F> {
                     .await
             }
         };
+        spin_telemetry::metrics::histogram!(
+            spin.request_duration = start.elapsed().as_secs_f64(),
+            trigger_type = "http",
+            app_id = app_id,
+            component_id = component_id,
+            route = route_match.raw_route()
+        );
         match res {
             Ok(res) => Ok(MatchedRoute::with_response_extension(
                 res,
@@ -298,7 +463,7 @@ impl<F: RuntimeFactors> HttpServer<F> {
 
     /// Returns spin status information.
     fn app_info(&self, route: String) -> anyhow::Result<Response<Body>> {
-        let info = AppInfo::new(self.trigger_app.app());
+        let info = AppInfo::new(self.served_app().trigger_app.app());
         let body = serde_json::to_vec_pretty(&info)?;
         Ok(MatchedRoute::with_response_extension(
             Response::builder()
@@ -347,24 +512,33 @@ impl<F: RuntimeFactors> HttpServer<F> {
         stream: S,
         server_scheme: Scheme,
         client_addr: SocketAddr,
+        shutdown: ShutdownSignal,
+        in_flight: mpsc::Sender<()>,
     ) {
         task::spawn(async move {
-            if let Err(err) = http1::Builder::new()
-                .keep_alive(true)
-                .serve_connection(
-                    TokioIo::new(stream),
-                    service_fn(move |request| {
-                        self.clone().instrumented_service_fn(
-                            server_scheme.clone(),
-                            client_addr,
-                            request,
-                        )
-                    }),
-                )
-                .await
-            {
+            let connection = http1::Builder::new().keep_alive(true).serve_connection(
+                TokioIo::new(stream),
+                service_fn(move |request| {
+                    self.clone().instrumented_service_fn(
+                        server_scheme.clone(),
+                        client_addr,
+                        request,
+                    )
+                }),
+            );
+            tokio::pin!(connection);
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = shutdown.requested() => {
+                    // Finish the request in progress, if any, then close
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(err) = result {
                 tracing::warn!("Error serving HTTP connection: {err:?}");
             }
+            drop(in_flight);
         });
     }
 
@@ -396,13 +570,17 @@ impl<F: RuntimeFactors> HttpServer<F> {
     fn print_startup_msgs(&self, scheme: &str, listener: &TcpListener) -> anyhow::Result<()> {
         let local_addr = listener.local_addr()?;
         let base_url = format!("{scheme}://{local_addr:?}");
+        if self.json_output {
+            return self.print_startup_json(&local_addr, &base_url);
+        }
         terminal::step!("\nServing", "{base_url}");
         tracing::info!("Serving {base_url}");
 
+        let served_app = self.served_app();
         println!("Available Routes:");
-        for (route, component_id) in self.router.routes() {
+        for (route, component_id) in served_app.router.routes() {
             println!("  {}: {}{}", component_id, base_url, route);
-            if let Some(component) = self.trigger_app.app().get_component(component_id) {
+            if let Some(component) = served_app.trigger_app.app().get_component(component_id) {
                 if let Some(description) = component.get_metadata(APP_DESCRIPTION_KEY)? {
                     println!("    {}", description);
                 }
@@ -410,6 +588,136 @@ impl<F: RuntimeFactors> HttpServer<F> {
         }
         Ok(())
     }
+
+    /// Reports the listen address and routes as a JSON event.
+    fn print_startup_json(&self, local_addr: &SocketAddr, base_url: &str) -> anyhow::Result<()> {
+        tracing::info!("Serving {base_url}");
+        let served_app = self.served_app();
+        let mut routes = vec![];
+        for (route, component_id) in served_app.router.routes() {
+            let description = match served_app.trigger_app.app().get_component(component_id) {
+                Some(component) => component.get_metadata(APP_DESCRIPTION_KEY)?,
+                None => None,
+            };
+            routes.push(serde_json::json!({
+                "component": component_id,
+                "route": route.to_string(),
+                "url": format!("{base_url}{route}"),
+                "description": description,
+            }));
+        }
+        let event = serde_json::json!({
+            "event": "serving",
+            "trigger": "http",
+            "address": local_addr.to_string(),
+            "base_url": base_url,
+            "routes": routes,
+        });
+        spin_trigger::cli::json_events::emit(&event)
+    }
+}
+
+impl<F: RuntimeFactors> ServedApp<F> {
+    fn new(trigger_app: TriggerApp<F>) -> anyhow::Result<Self> {
+        // This needs to be a vec before building the router to handle duplicate routes
+        let component_trigger_configs = Vec::from_iter(
+            trigger_app
+                .app()
+                .trigger_configs::<HttpTriggerConfig>("http")?
+                .into_iter()
+                .map(|(_, config)| (config.component.clone(), config)),
+        );
+
+        // Build router
+        let component_routes = component_trigger_configs
+            .iter()
+            .map(|(component_id, config)| (component_id.as_str(), &config.route));
+        let (router, duplicate_routes) = Router::build("/", component_routes)?;
+        if !duplicate_routes.is_empty() {
+            tracing::error!(
+                "The following component routes are duplicates and will never be used:"
+            );
+            for dup in &duplicate_routes {
+                tracing::error!(
+                    "  {}: {} (duplicate of {})",
+                    dup.replaced_id,
+                    dup.route(),
+                    dup.effective_id,
+                );
+            }
+        }
+        tracing::trace!(
+            "Constructed router: {:?}",
+            router.routes().collect::<Vec<_>>()
+        );
+
+        // Now that router is built we can merge duplicate routes by component
+        let component_trigger_configs = HashMap::from_iter(component_trigger_configs);
+
+        let component_handler_types = component_trigger_configs
+            .iter()
+            .map(|(component_id, trigger_config)| {
+                let handler_type = match &trigger_config.executor {
+                    None | Some(HttpExecutorType::Http) => {
+                        let component = trigger_app.get_component(component_id)?;
+                        HandlerType::from_component(trigger_app.engine().as_ref(), component)?
+                    }
+                    Some(HttpExecutorType::Wagi(wagi_config)) => {
+                        anyhow::ensure!(
+                            wagi_config.entrypoint == "_start",
+                            "Wagi component '{component_id}' cannot use deprecated 'entrypoint' field"
+                        );
+                        HandlerType::Wagi
+                    }
+                };
+                Ok((component_id.clone(), handler_type))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let instance_pools = component_trigger_configs
+            .iter()
+            .filter_map(|(component_id, trigger_config)| {
+                let pool_config = trigger_config.instance_pool.clone()?;
+                if matches!(trigger_config.executor, Some(HttpExecutorType::Wagi(_))) {
+                    return Some(Err(anyhow::anyhow!(
+                        "Wagi component '{component_id}' cannot use an instance pool"
+                    )));
+                }
+                Some(Ok((
+                    component_id.clone(),
+                    Arc::new(InstancePool::new(pool_config)),
+                )))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            router,
+            trigger_app: Arc::new(trigger_app),
+            component_trigger_configs,
+            component_handler_types,
+            instance_pools,
+            replaced: AtomicBool::new(false),
+        })
+    }
+}
+
+impl<F: RuntimeFactors> Drop for ServedApp<F> {
+    fn drop(&mut self) {
+        // The app being served when the server shuts down is shut down by
+        // the server itself
+        if !*self.replaced.get_mut() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let trigger_app = self.trigger_app.clone();
+        runtime.spawn(async move {
+            if let Err(err) = trigger_app.shutdown().await {
+                tracing::error!("Failed to shut down replaced application: {err:?}");
+            }
+        });
+    }
 }
 
 /// The incoming request's scheme and authority
@@ -454,7 +762,8 @@ fn set_req_uri(req: &mut Request<Body>, scheme: Scheme) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// An HTTP executor.
+/// An HTTP executor which needs to configure each instance before it is
+/// instantiated, so it can't be given an instance from an instance pool.
 pub(crate) trait HttpExecutor: Clone + Send + Sync + 'static {
     fn execute<F: RuntimeFactors>(
         &self,
@@ -464,3 +773,90 @@ pub(crate) trait HttpExecutor: Clone + Send + Sync + 'static {
         client_addr: SocketAddr,
     ) -> impl Future<Output = anyhow::Result<Response<Body>>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use spin_factors_test::toml;
+
+    use super::*;
+    use crate::testing::trigger_app;
+
+    #[tokio::test]
+    async fn reload_replaces_the_served_app() -> anyhow::Result<()> {
+        let first = trigger_app(toml! {
+            [[trigger.http]]
+            route = "/first"
+            component = "empty"
+            executor = { type = "wagi" }
+        })
+        .await?;
+        let server = Arc::new(HttpServer::new("127.0.0.1:0".parse()?, None, first)?);
+        let in_flight = server.served_app();
+
+        let second = trigger_app(toml! {
+            [[trigger.http]]
+            route = "/second"
+            component = "empty"
+            executor = { type = "wagi" }
+        })
+        .await?;
+        server.reload(second)?;
+
+        let served = server.served_app();
+        assert!(served.router.route("/second").is_ok());
+        assert!(served.router.route("/first").is_err());
+        assert!(!served.replaced.load(Ordering::Acquire));
+
+        // A request in flight during the reload finishes on the previous app,
+        // which is shut down once it is no longer in use
+        assert!(in_flight.router.route("/first").is_ok());
+        assert!(in_flight.replaced.load(Ordering::Acquire));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_in_flight_requests() -> anyhow::Result<()> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listen_addr = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        let app = trigger_app(toml! {
+            [[trigger.http]]
+            route = "/"
+            component = "empty"
+            executor = { type = "wagi" }
+        })
+        .await?;
+        let server = Arc::new(HttpServer::new(listen_addr, None, app)?);
+        let (request_shutdown, shutdown) = ShutdownSignal::new();
+        let mut serving = task::spawn(server.serve_until_shutdown(shutdown));
+
+        let mut stream = loop {
+            match tokio::net::TcpStream::connect(listen_addr).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        // Start a request, then ask the server to shut down before it is
+        // complete (giving the server time to accept the connection first)
+        stream
+            .write_all(b"GET /.well-known/spin/health HTTP/1.1\r\n")
+            .await?;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        request_shutdown.send(true)?;
+        let still_serving =
+            tokio::time::timeout(std::time::Duration::from_millis(200), &mut serving).await;
+        assert!(
+            still_serving.is_err(),
+            "server stopped with a request in flight"
+        );
+
+        // The request in flight is still served, after which the server stops
+        stream.write_all(b"Host: localhost\r\n\r\n").await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("OK"), "{response}");
+        tokio::time::timeout(std::time::Duration::from_secs(5), serving).await???;
+        Ok(())
+    }
+}
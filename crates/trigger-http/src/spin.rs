@@ -11,19 +11,19 @@ use tracing::{instrument, Level};
 
 use crate::{
     headers::{append_headers, prepare_request_headers},
-    server::HttpExecutor,
-    Body, TriggerInstanceBuilder,
+    pool::ReadyInstance,
+    Body,
 };
 
-/// An [`HttpExecutor`] that uses the `fermyon:spin/inbound-http` interface.
+/// An HTTP executor that uses the `fermyon:spin/inbound-http` interface.
 #[derive(Clone)]
 pub struct SpinHttpExecutor;
 
-impl HttpExecutor for SpinHttpExecutor {
+impl SpinHttpExecutor {
     #[instrument(name = "spin_trigger_http.execute_wasm", skip_all, err(level = Level::INFO), fields(otel.name = format!("execute_wasm_component {}", route_match.component_id())))]
-    async fn execute<F: RuntimeFactors>(
+    pub async fn execute<F: RuntimeFactors>(
         &self,
-        instance_builder: TriggerInstanceBuilder<'_, F>,
+        mut ready: ReadyInstance<F>,
         route_match: &RouteMatch,
         req: Request<Body>,
         client_addr: SocketAddr,
@@ -32,19 +32,19 @@ impl HttpExecutor for SpinHttpExecutor {
 
         tracing::trace!("Executing request using the Spin executor for component {component_id}");
 
-        let (instance, mut store) = instance_builder.instantiate(()).await?;
+        let (instance, store) = (ready.instance, &mut ready.store);
 
         let headers = prepare_request_headers(&req, route_match, client_addr)?;
         // Expects here are safe since we have already checked that this
         // instance exists
         let inbound_http = instance
-            .get_export(&mut store, None, "fermyon:spin/inbound-http")
+            .get_export(&mut *store, None, "fermyon:spin/inbound-http")
             .expect("no fermyon:spin/inbound-http found");
         let handle_request = instance
-            .get_export(&mut store, Some(&inbound_http), "handle-request")
+            .get_export(&mut *store, Some(&inbound_http), "handle-request")
             .expect("no handle-request found");
         let func = instance.get_typed_func::<(http_types::Request,), (http_types::Response,)>(
-            &mut store,
+            &mut *store,
             &handle_request,
         )?;
 
@@ -77,7 +77,14 @@ impl HttpExecutor for SpinHttpExecutor {
             body: Some(bytes),
         };
 
-        let (resp,) = func.call_async(&mut store, (req,)).await?;
+        let result = func.call_async(&mut *store, (req,)).await;
+        // Calls to the function must be completed by `post_return` before it can be called again
+        let result = match result {
+            Ok(resp) => func.post_return_async(&mut *store).await.map(|()| resp),
+            Err(err) => Err(err),
+        };
+        ready.finish(result.as_ref().map(|_| ()));
+        let (resp,) = result?;
 
         if resp.status < 100 || resp.status > 600 {
             tracing::error!("malformed HTTP status code");
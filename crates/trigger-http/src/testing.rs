@@ -0,0 +1,56 @@
+//! Helpers for testing the HTTP trigger against apps of minimal components.
+
+use std::sync::Arc;
+
+use spin_app::{App, AppComponent};
+use spin_core::{async_trait, Component};
+use spin_factor_wasi::{DummyFilesMounter, WasiFactor};
+use spin_factors::RuntimeFactors;
+use spin_factors_executor::{ComponentLoader, FactorsExecutor};
+use spin_factors_test::TestEnvironment;
+
+use crate::TriggerApp;
+
+#[derive(RuntimeFactors)]
+pub(crate) struct TestFactors {
+    wasi: WasiFactor,
+}
+
+/// Loads the test manifest, extended with `manifest`, as a [`TriggerApp`]. The
+/// manifest has a single component, `empty`.
+pub(crate) async fn trigger_app(manifest: toml::Table) -> anyhow::Result<TriggerApp<TestFactors>> {
+    trigger_app_with_component(manifest, "(component)").await
+}
+
+/// Like [`trigger_app`], but each component is loaded from the given WAT
+/// source rather than being empty.
+pub(crate) async fn trigger_app_with_component(
+    manifest: toml::Table,
+    wat: &'static str,
+) -> anyhow::Result<TriggerApp<TestFactors>> {
+    let factors = TestFactors {
+        wasi: WasiFactor::new(DummyFilesMounter),
+    };
+    let env = TestEnvironment::new(factors).extend_manifest(manifest);
+    let locked = env.build_locked_app().await?;
+    let app = App::new("test-app", locked);
+
+    let engine_builder = spin_core::Engine::builder(&Default::default())?;
+    let executor = Arc::new(FactorsExecutor::new(engine_builder, env.factors)?);
+    executor
+        .load_app(app, Default::default(), &WatComponentLoader(wat))
+        .await
+}
+
+struct WatComponentLoader(&'static str);
+
+#[async_trait]
+impl ComponentLoader for WatComponentLoader {
+    async fn load_component(
+        &self,
+        engine: &spin_core::wasmtime::Engine,
+        _component: &AppComponent,
+    ) -> anyhow::Result<Component> {
+        Component::new(engine, self.0)
+    }
+}
@@ -85,18 +85,29 @@ impl HttpExecutor for WagiHttpExecutor {
         wasi_builder.stdin_pipe(Cursor::new(body));
         wasi_builder.stdout(stdout.clone());
 
+        let instance_hooks = instance_builder.instance_hooks();
         let (instance, mut store) = instance_builder.instantiate(()).await?;
 
         let command = wasmtime_wasi::bindings::Command::new(&mut store, &instance)?;
 
         tracing::trace!("Calling Wasm entry point");
-        if let Err(()) = command
+        let result = command
             .wasi_cli_run()
             .call_run(&mut store)
             .await
-            .or_else(ignore_successful_proc_exit_trap)?
-        {
-            tracing::error!("Wagi main function returned unsuccessful result");
+            .or_else(ignore_successful_proc_exit_trap);
+        // A main function which returns an error has failed, although the
+        // response is still composed from whatever it wrote
+        let unsuccessful = matches!(result, Ok(Err(())))
+            .then(|| anyhow::anyhow!("Wagi main function returned unsuccessful result"));
+        let outcome = match (&result, &unsuccessful) {
+            (Err(err), _) | (_, Some(err)) => Err(err),
+            _ => Ok(()),
+        };
+        instance_hooks.after_execute(&mut store, outcome);
+        result?;
+        if let Some(err) = unsuccessful {
+            tracing::error!("{err}");
         }
         tracing::info!("Wagi execution complete");
 
@@ -14,19 +14,19 @@ use tracing::{instrument, Instrument, Level};
 use wasmtime_wasi_http::bindings::http::types::Scheme;
 use wasmtime_wasi_http::{bindings::Proxy, body::HyperIncomingBody as Body, WasiHttpView};
 
-use crate::{headers::prepare_request_headers, server::HttpExecutor, TriggerInstanceBuilder};
+use crate::{headers::prepare_request_headers, pool::ReadyInstance};
 
-/// An [`HttpExecutor`] that uses the `wasi:http/incoming-handler` interface.
+/// An HTTP executor that uses the `wasi:http/incoming-handler` interface.
 #[derive(Clone)]
 pub struct WasiHttpExecutor {
     pub handler_type: HandlerType,
 }
 
-impl HttpExecutor for WasiHttpExecutor {
+impl WasiHttpExecutor {
     #[instrument(name = "spin_trigger_http.execute_wasm", skip_all, err(level = Level::INFO), fields(otel.name = format!("execute_wasm_component {}", route_match.component_id())))]
-    async fn execute<F: RuntimeFactors>(
+    pub async fn execute<F: RuntimeFactors>(
         &self,
-        instance_builder: TriggerInstanceBuilder<'_, F>,
+        mut ready: ReadyInstance<F>,
         route_match: &RouteMatch,
         mut req: Request<Body>,
         client_addr: SocketAddr,
@@ -35,7 +35,7 @@ impl HttpExecutor for WasiHttpExecutor {
 
         tracing::trace!("Executing request using the Wasi executor for component {component_id}");
 
-        let (instance, mut store) = instance_builder.instantiate(()).await?;
+        let (instance, store) = (ready.instance, &mut ready.store);
 
         let headers = prepare_request_headers(&req, route_match, client_addr)?;
         req.headers_mut().clear();
@@ -82,17 +82,17 @@ impl HttpExecutor for WasiHttpExecutor {
         let handler = match self.handler_type {
             HandlerType::Wasi2023_10_18 => {
                 let indices =
-                    incoming_handler2023_10_18::GuestIndices::new_instance(&mut store, &instance)?;
-                let guest = indices.load(&mut store, &instance)?;
+                    incoming_handler2023_10_18::GuestIndices::new_instance(&mut *store, &instance)?;
+                let guest = indices.load(&mut *store, &instance)?;
                 Handler::Handler2023_10_18(guest)
             }
             HandlerType::Wasi2023_11_10 => {
                 let indices =
-                    incoming_handler2023_11_10::GuestIndices::new_instance(&mut store, &instance)?;
-                let guest = indices.load(&mut store, &instance)?;
+                    incoming_handler2023_11_10::GuestIndices::new_instance(&mut *store, &instance)?;
+                let guest = indices.load(&mut *store, &instance)?;
                 Handler::Handler2023_11_10(guest)
             }
-            HandlerType::Wasi0_2 => Handler::Latest(Proxy::new(&mut store, &instance)?),
+            HandlerType::Wasi0_2 => Handler::Latest(Proxy::new(&mut *store, &instance)?),
             HandlerType::Spin => unreachable!("should have used SpinHttpExecutor"),
             HandlerType::Wagi => unreachable!("should have used WagiExecutor instead"),
         };
@@ -100,23 +100,24 @@ impl HttpExecutor for WasiHttpExecutor {
         let span = tracing::debug_span!("execute_wasi");
         let handle = task::spawn(
             async move {
+                let store = &mut ready.store;
                 let result = match handler {
                     Handler::Latest(handler) => {
                         handler
                             .wasi_http_incoming_handler()
-                            .call_handle(&mut store, request, response)
+                            .call_handle(&mut *store, request, response)
                             .instrument(span)
                             .await
                     }
                     Handler::Handler2023_10_18(handler) => {
                         handler
-                            .call_handle(&mut store, request, response)
+                            .call_handle(&mut *store, request, response)
                             .instrument(span)
                             .await
                     }
                     Handler::Handler2023_11_10(handler) => {
                         handler
-                            .call_handle(&mut store, request, response)
+                            .call_handle(&mut *store, request, response)
                             .instrument(span)
                             .await
                     }
@@ -127,6 +128,8 @@ impl HttpExecutor for WasiHttpExecutor {
                     store.data().core_state().memory_consumed()
                 );
 
+                ready.finish(result.as_ref().map(|_| ()));
+
                 result
             }
             .in_current_span(),
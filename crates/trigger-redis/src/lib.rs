@@ -6,7 +6,10 @@ use redis::{Client, Msg};
 use serde::Deserialize;
 use spin_factor_variables::VariablesFactor;
 use spin_factors::RuntimeFactors;
-use spin_trigger::{cli::NoCliArgs, App, Trigger, TriggerApp};
+use spin_trigger::{
+    cli::{print_info, NoCliArgs},
+    App, ShutdownSignal, Trigger, TriggerApp,
+};
 use spin_world::exports::fermyon::spin::inbound_redis;
 use tracing::{instrument, Level};
 
@@ -43,6 +46,21 @@ impl<F: RuntimeFactors> Trigger<F> for RedisTrigger {
     }
 
     async fn run(self, trigger_app: spin_trigger::TriggerApp<Self, F>) -> anyhow::Result<()> {
+        let (_, reloads) = tokio::sync::mpsc::channel(1);
+        self.run_until_shutdown(trigger_app, reloads, ShutdownSignal::never())
+            .await
+    }
+
+    async fn run_until_shutdown(
+        self,
+        trigger_app: spin_trigger::TriggerApp<Self, F>,
+        reloads: tokio::sync::mpsc::Receiver<spin_trigger::TriggerApp<Self, F>>,
+        shutdown: ShutdownSignal,
+    ) -> anyhow::Result<()> {
+        // Subscriptions can't be switched to a reloaded app, so the trigger
+        // must be restarted instead
+        drop(reloads);
+
         let app_variables = trigger_app
             .configured_app()
             .app_state::<VariablesFactor>()
@@ -105,13 +123,24 @@ impl<F: RuntimeFactors> Trigger<F> for RedisTrigger {
         let mut subscriber_tasks = Vec::new();
         for (address, channel_components) in server_channel_components {
             let subscriber = Subscriber::new(address, trigger_app.clone(), channel_components)?;
-            let task = tokio::spawn(subscriber.run_listener());
+            let task = tokio::spawn(subscriber.run_listener(shutdown.clone()));
             subscriber_tasks.push(task);
         }
 
         // Wait for any task to complete
-        let (res, _, _) = futures::future::select_all(subscriber_tasks).await;
-        res?
+        let (res, _, other_tasks) = futures::future::select_all(subscriber_tasks).await;
+        res??;
+
+        // Subscribers only complete successfully once shutdown is requested;
+        // let the others finish handling the messages they have received
+        for res in futures::future::join_all(other_tasks).await {
+            match res {
+                Ok(Ok(())) => (),
+                Ok(Err(err)) => tracing::error!("Redis subscriber failed: {err:?}"),
+                Err(err) => tracing::error!("Redis subscriber task failed: {err:?}"),
+            }
+        }
+        trigger_app.shutdown().await
     }
 }
 
@@ -139,7 +168,9 @@ impl<F: RuntimeFactors> Subscriber<F> {
         })
     }
 
-    async fn run_listener(self) -> anyhow::Result<()> {
+    /// Handles messages until the connection is lost, or until `shutdown` is
+    /// requested and the message being handled, if any, is done.
+    async fn run_listener(self, shutdown: ShutdownSignal) -> anyhow::Result<()> {
         let server_addr = &self.client.get_connection_info().addr;
 
         tracing::info!("Connecting to Redis server at {server_addr}");
@@ -149,7 +180,7 @@ impl<F: RuntimeFactors> Subscriber<F> {
             .await
             .with_context(|| format!("Redis trigger failed to connect to {server_addr}"))?;
 
-        println!("Active Channels on {server_addr}:");
+        print_info(format_args!("Active Channels on {server_addr}:"));
 
         // Subscribe to channels
         for (channel, components) in &self.channel_components {
@@ -157,11 +188,21 @@ impl<F: RuntimeFactors> Subscriber<F> {
             pubsub.subscribe(channel).await.with_context(|| {
                 format!("Redis trigger failed to subscribe to channel {channel:?} on {server_addr}")
             })?;
-            println!("\t{server_addr}/{channel}: [{}]", components.join(","));
+            print_info(format_args!(
+                "\t{server_addr}/{channel}: [{}]",
+                components.join(",")
+            ));
         }
 
         let mut message_stream = pubsub.on_message();
-        while let Some(msg) = message_stream.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = message_stream.next() => msg,
+                _ = shutdown.requested() => return Ok(()),
+            };
+            let Some(msg) = msg else {
+                break;
+            };
             if let Err(err) = self.handle_message(msg).await {
                 tracing::error!("Error handling message from {server_addr}: {err}");
             }
@@ -180,13 +221,16 @@ impl<F: RuntimeFactors> Subscriber<F> {
         let channel = msg.get_channel_name();
         tracing::trace!(%server_addr, %channel, "Received message");
 
+        // Continue the publisher's trace, if it wrapped one around the payload
+        let payload = spin_telemetry::unwrap_payload_trace_context(msg.get_payload_bytes());
+
         let Some(component_ids) = self.channel_components.get(channel) else {
             anyhow::bail!("message from unexpected channel {channel:?}");
         };
 
         let dispatch_futures = component_ids.iter().map(|component_id| {
             tracing::trace!("Executing Redis component {component_id}");
-            self.dispatch_handler(&msg, component_id)
+            self.dispatch_handler(payload, component_id)
                 .inspect_err(move |err| {
                     tracing::info!("Component {component_id} handler failed: {err}");
                 })
@@ -196,7 +240,7 @@ impl<F: RuntimeFactors> Subscriber<F> {
         Ok(())
     }
 
-    async fn dispatch_handler(&self, msg: &Msg, component_id: &str) -> anyhow::Result<()> {
+    async fn dispatch_handler(&self, payload: &[u8], component_id: &str) -> anyhow::Result<()> {
         spin_telemetry::metrics::monotonic_counter!(
             spin.request_count = 1,
             trigger_type = "redis",
@@ -204,20 +248,133 @@ impl<F: RuntimeFactors> Subscriber<F> {
             component_id = component_id
         );
 
-        let (instance, mut store) = self
-            .trigger_app
-            .prepare(component_id)?
-            .instantiate(())
-            .await?;
+        let instance_builder = self.trigger_app.prepare(component_id)?;
+        let instance_hooks = instance_builder.instance_hooks();
+        let (instance, mut store) = instance_builder.instantiate(()).await?;
 
         let guest_indices = inbound_redis::GuestIndices::new_instance(&mut store, &instance)?;
         let guest = guest_indices.load(&mut store, &instance)?;
 
-        let payload = msg.get_payload_bytes().to_vec();
+        let payload = payload.to_vec();
 
-        guest
+        let result = guest
             .call_handle_message(&mut store, &payload)
-            .await?
-            .context("Redis handler returned an error")
+            .await
+            .and_then(|res| res.context("Redis handler returned an error"));
+        instance_hooks.after_execute(&mut store, result.as_ref().map(|_| ()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use opentelemetry::trace::{SpanId, TraceId, TraceResult, TracerProvider};
+    use opentelemetry_sdk::{
+        export::trace::SpanData,
+        trace::{Span, SpanProcessor},
+    };
+    use spin_core::{async_trait, Component};
+    use spin_factors::AppComponent;
+    use spin_factors_executor::{ComponentLoader, FactorsExecutor};
+    use spin_factors_test::TestEnvironment;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[derive(RuntimeFactors)]
+    struct TestFactors {
+        variables: VariablesFactor,
+    }
+
+    struct EmptyComponentLoader;
+
+    #[async_trait]
+    impl ComponentLoader for EmptyComponentLoader {
+        async fn load_component(
+            &self,
+            engine: &spin_core::wasmtime::Engine,
+            _component: &AppComponent,
+        ) -> anyhow::Result<Component> {
+            Component::new(engine, "(component)")
+        }
+    }
+
+    /// Collects ended spans.
+    #[derive(Clone, Debug, Default)]
+    struct CollectingSpanProcessor(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanProcessor for CollectingSpanProcessor {
+        fn on_start(&self, _span: &mut Span, _cx: &opentelemetry::Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            self.0.lock().unwrap().push(span);
+        }
+
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    async fn trigger_app() -> anyhow::Result<TriggerApp<RedisTrigger, TestFactors>> {
+        let env = TestEnvironment::new(TestFactors {
+            variables: VariablesFactor::default(),
+        });
+        let locked = env.build_locked_app().await?;
+        let app = App::new("test-app", locked);
+        let engine_builder = spin_core::Engine::builder(&Default::default())?;
+        let executor = Arc::new(FactorsExecutor::new(engine_builder, env.factors)?);
+        executor
+            .load_app(app, Default::default(), &EmptyComponentLoader)
+            .await
+    }
+
+    #[tokio::test]
+    async fn handler_span_continues_publisher_trace() -> anyhow::Result<()> {
+        opentelemetry::global::set_text_map_propagator(
+            opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+        );
+        let spans = CollectingSpanProcessor::default();
+        let tracer_provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_span_processor(spans.clone())
+            .build();
+        let collector = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer("test")));
+        let _collector = tracing::subscriber::set_default(collector);
+
+        let subscriber = Subscriber::new(
+            "redis://127.0.0.1:6379".into(),
+            Arc::new(trigger_app().await?),
+            ChannelComponents::new(),
+        )?;
+        let payload = b"\0spin-trace-context\n\
+            traceparent: 00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01\n\
+            \n\
+            hello";
+        let msg = Msg::from_owned_value(redis::Value::Array(vec![
+            redis::Value::BulkString(b"message".to_vec()),
+            redis::Value::BulkString(b"messages".to_vec()),
+            redis::Value::BulkString(payload.to_vec()),
+        ]))
+        .context("invalid message")?;
+        // No component subscribes to the channel, but the message is still received
+        assert!(subscriber.handle_message(msg).await.is_err());
+
+        let spans = spans.0.lock().unwrap();
+        let span = spans
+            .iter()
+            .find(|span| span.name == "messages receive")
+            .context("no handler span")?;
+        assert_eq!(
+            span.span_context.trace_id(),
+            TraceId::from_hex("0af7651916cd43dd8448eb211c80319c")?
+        );
+        assert_eq!(span.parent_span_id, SpanId::from_hex("b7ad6b7169203331")?);
+        Ok(())
     }
 }
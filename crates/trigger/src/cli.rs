@@ -1,10 +1,13 @@
 mod initial_kv_setter;
+pub mod json_events;
 mod launch_metadata;
+mod log_files;
 mod sqlite_statements;
 mod stdio;
 mod summary;
 
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{future::Future, sync::Arc};
 
 use anyhow::{Context, Result};
@@ -15,10 +18,14 @@ use spin_common::ui::quoted_path;
 use spin_common::url::parse_file_url;
 use spin_factors::RuntimeFactors;
 use spin_factors_executor::{ComponentLoader, FactorsExecutor};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 
-use crate::{loader::ComponentLoader as ComponentLoaderImpl, Trigger, TriggerApp};
+use crate::{loader::ComponentLoader as ComponentLoaderImpl, ShutdownSignal, Trigger, TriggerApp};
 pub use initial_kv_setter::InitialKvSetterHook;
 pub use launch_metadata::LaunchMetadata;
+pub use log_files::{
+    component_log_path, rotated_path, ComponentLogFormat, ComponentLogRecord, ComponentLogsConfig,
+};
 pub use sqlite_statements::SqlStatementExecutorHook;
 use stdio::FollowComponents;
 pub use stdio::StdioLoggingExecutorHooks;
@@ -34,6 +41,25 @@ pub const RUNTIME_CONFIG_FILE: &str = "RUNTIME_CONFIG_FILE";
 pub const SPIN_LOCKED_URL: &str = "SPIN_LOCKED_URL";
 pub const SPIN_LOCAL_APP_DIR: &str = "SPIN_LOCAL_APP_DIR";
 pub const SPIN_WORKING_DIR: &str = "SPIN_WORKING_DIR";
+pub const SPIN_JSON_OUTPUT: &str = "SPIN_JSON_OUTPUT";
+pub const SPIN_RELOAD_ON_STDIN: &str = "SPIN_RELOAD_ON_STDIN";
+
+/// Whether `spin up` asked for startup information to be reported as
+/// newline-delimited JSON on stdout. If so, events are written with
+/// [`json_events::emit`] and everything else is written to stderr.
+pub fn json_output_requested() -> bool {
+    std::env::var_os(SPIN_JSON_OUTPUT).is_some()
+}
+
+/// Prints an informational message to stdout, or to stderr if stdout is
+/// reserved for JSON output.
+pub fn print_info(message: impl std::fmt::Display) {
+    if json_output_requested() {
+        eprintln!("{message}");
+    } else {
+        println!("{message}");
+    }
+}
 
 /// A command that runs a TriggerExecutor.
 #[derive(Parser, Debug)]
@@ -109,6 +135,16 @@ pub struct FactorsTriggerCommand<T: Trigger<B::Factors>, B: RuntimeFactorsBuilde
     #[clap(long)]
     pub state_dir: Option<String>,
 
+    /// How long to wait, in seconds, after a request to shut down (e.g. Ctrl+C
+    /// or SIGTERM) for in-flight requests to finish and application state to
+    /// be flushed before exiting anyway.
+    #[clap(
+        long = "shutdown-timeout",
+        env = "SPIN_SHUTDOWN_TIMEOUT",
+        default_value = "10"
+    )]
+    pub shutdown_timeout: u64,
+
     #[clap(flatten)]
     pub trigger_args: T::CliArgs,
 
@@ -169,22 +205,14 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> FactorsTriggerCommand<T,
         let locked_url = std::env::var(SPIN_LOCKED_URL).context(SPIN_LOCKED_URL)?;
         let local_app_dir = std::env::var(SPIN_LOCAL_APP_DIR).ok();
 
+        if json_output_requested() {
+            json_events::reserve_stdout()?;
+        }
+
         let follow_components = self.follow_components();
 
         // Load App
-        let app = {
-            let path = parse_file_url(&locked_url)?;
-            let contents = std::fs::read(&path)
-                .with_context(|| format!("failed to read manifest at {}", quoted_path(&path)))?;
-            let locked =
-                serde_json::from_slice(&contents).context("failed to parse app lock file JSON")?;
-            App::new(locked_url, locked)
-        };
-
-        // Validate required host features
-        if let Err(unmet) = app.ensure_needs_only(&T::supported_host_requirements()) {
-            anyhow::bail!("This application requires the following features that are not available in this version of the '{}' trigger: {unmet}", T::TYPE);
-        }
+        let app = load_app::<T, B::Factors>(&locked_url)?;
 
         let trigger = T::new(self.trigger_args, &app)?;
         let mut builder: TriggerAppBuilder<T, B> = TriggerAppBuilder::new(trigger);
@@ -220,17 +248,56 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> FactorsTriggerCommand<T,
             log_dir,
         };
 
-        let run_fut = builder
-            .run(
-                app,
-                common_options,
-                self.builder_args,
-                &ComponentLoaderImpl::new(),
-            )
-            .await?;
+        if let Some(listen_addr) = spin_telemetry::prometheus::listen_addr()? {
+            tokio::spawn(async move {
+                if let Err(err) = spin_telemetry::prometheus::serve(listen_addr).await {
+                    tracing::error!("Prometheus metrics endpoint failed: {err:?}");
+                }
+            });
+        }
 
+        let (request_shutdown, shutdown) = ShutdownSignal::new();
+        let loader = ComponentLoaderImpl::new();
+        // `spin up` only asks triggers that support reloading to reload
+        let reload_on_stdin =
+            std::env::var_os(SPIN_RELOAD_ON_STDIN).is_some() && T::supports_reload();
+        let run_fut = if reload_on_stdin {
+            let reload_requests = reload_requests_from_stdin::<T, B::Factors>(locked_url);
+            let run_fut = builder
+                .run_with_reloads(
+                    app,
+                    common_options,
+                    self.builder_args,
+                    loader,
+                    reload_requests,
+                    shutdown,
+                )
+                .await?;
+            futures::future::Either::Left(run_fut)
+        } else {
+            let run_fut = builder
+                .run(app, common_options, self.builder_args, &loader, shutdown)
+                .await?;
+            futures::future::Either::Right(run_fut)
+        };
+
+        // The first request to shut down lets the trigger finish in-flight
+        // work, for up to the shutdown timeout. Further requests are ignored:
+        // under `spin up`, the trigger gets both the terminal's SIGINT and the
+        // SIGTERM that `spin up` forwards.
+        let shutdown_timeout = Duration::from_secs(self.shutdown_timeout);
         let (abortable, abort_handle) = futures::future::abortable(run_fut);
-        ctrlc::set_handler(move || abort_handle.abort())?;
+        ctrlc::set_handler(move || {
+            let already_requested = request_shutdown.send_replace(true);
+            if !already_requested {
+                tracing::info!("User requested shutdown: finishing in-flight work");
+                let abort_handle = abort_handle.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(shutdown_timeout);
+                    abort_handle.abort();
+                });
+            }
+        })?;
         match abortable.await {
             Ok(Ok(())) => {
                 tracing::info!("Trigger executor shut down: exiting");
@@ -241,7 +308,10 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> FactorsTriggerCommand<T,
                 Err(err)
             }
             Err(_aborted) => {
-                tracing::info!("User requested shutdown: exiting");
+                tracing::warn!(
+                    "In-flight work did not finish within the shutdown timeout of {}s: exiting",
+                    shutdown_timeout.as_secs()
+                );
                 Ok(())
             }
         }
@@ -259,6 +329,59 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> FactorsTriggerCommand<T,
     }
 }
 
+/// Loads the locked app at the given URL, and checks that the trigger supports
+/// the host features it requires.
+fn load_app<T: Trigger<F>, F: RuntimeFactors>(locked_url: &str) -> Result<App> {
+    let app = {
+        let path = parse_file_url(locked_url)?;
+        let contents = std::fs::read(&path)
+            .with_context(|| format!("failed to read manifest at {}", quoted_path(&path)))?;
+        let locked =
+            serde_json::from_slice(&contents).context("failed to parse app lock file JSON")?;
+        App::new(locked_url, locked)
+    };
+
+    // Validate required host features
+    if let Err(unmet) = app.ensure_needs_only(&T::supported_host_requirements()) {
+        anyhow::bail!("This application requires the following features that are not available in this version of the '{}' trigger: {unmet}", T::TYPE);
+    }
+
+    Ok(app)
+}
+
+/// Reloads the locked app each time a line is read from stdin. `spin up`
+/// rewrites the lock file before writing the line.
+fn reload_requests_from_stdin<T: Trigger<F>, F: RuntimeFactors>(
+    locked_url: String,
+) -> tokio::sync::mpsc::Receiver<App> {
+    reload_requests(BufReader::new(tokio::io::stdin()), move || {
+        load_app::<T, F>(&locked_url)
+    })
+}
+
+/// Calls `load` each time a line is read from `input`, and sends each app it
+/// loads. Stops at the end of `input`, or once the receiver is dropped.
+fn reload_requests<A: Send + 'static>(
+    input: impl AsyncBufRead + Unpin + Send + 'static,
+    mut load: impl FnMut() -> Result<A> + Send + 'static,
+) -> tokio::sync::mpsc::Receiver<A> {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        let mut lines = input.lines();
+        while let Ok(Some(_)) = lines.next_line().await {
+            match load() {
+                Ok(app) => {
+                    if tx.send(app).await.is_err() {
+                        break;
+                    }
+                }
+                Err(err) => tracing::error!("Failed to reload application: {err:?}"),
+            }
+        }
+    });
+    rx
+}
+
 const SLOTH_WARNING_DELAY_MILLIS: u64 = 1250;
 
 fn warn_if_wasm_build_slothful() -> sloth::SlothGuard {
@@ -283,6 +406,9 @@ fn help_heading<T: Trigger<F>, F: RuntimeFactors>() -> Option<&'static str> {
     }
 }
 
+/// A [`FactorsExecutor`] specialized to a [`Trigger`].
+type TriggerExecutor<T, F> = FactorsExecutor<F, <T as Trigger<F>>::InstanceState>;
+
 /// A builder for a [`TriggerApp`].
 pub struct TriggerAppBuilder<T, B> {
     engine_config: spin_core::Config,
@@ -311,6 +437,17 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> TriggerAppBuilder<T, B> {
         options: B::CliArgs,
         loader: &impl ComponentLoader,
     ) -> anyhow::Result<TriggerApp<T, B::Factors>> {
+        let (executor, runtime_config) = self.build_executor(&common_options, &options)?;
+        Self::load_into_executor(&executor, app, runtime_config, loader).await
+    }
+
+    /// Build the [`FactorsExecutor`] that apps are loaded into, and the
+    /// runtime config to load the first app with.
+    fn build_executor(
+        &mut self,
+        common_options: &FactorsConfig,
+        options: &B::CliArgs,
+    ) -> anyhow::Result<(Arc<TriggerExecutor<T, B::Factors>>, B::RuntimeConfig)> {
         let mut core_engine_builder = {
             self.trigger.update_core_config(&mut self.engine_config)?;
 
@@ -318,32 +455,95 @@ impl<T: Trigger<B::Factors>, B: RuntimeFactorsBuilder> TriggerAppBuilder<T, B> {
         };
         self.trigger.add_to_linker(core_engine_builder.linker())?;
 
-        let (factors, runtime_config) = B::build(&common_options, &options)?;
+        let (factors, runtime_config) = B::build(common_options, options)?;
 
         let mut executor = FactorsExecutor::new(core_engine_builder, factors)?;
-        B::configure_app(&mut executor, &runtime_config, &common_options, &options)?;
-        let executor = Arc::new(executor);
-
-        let configured_app = {
-            let _sloth_guard = warn_if_wasm_build_slothful();
-            executor
-                .load_app(app, runtime_config.into(), loader)
-                .await?
-        };
+        B::configure_app(&mut executor, &runtime_config, common_options, options)?;
+        Ok((Arc::new(executor), runtime_config))
+    }
 
-        Ok(configured_app)
+    async fn load_into_executor(
+        executor: &Arc<TriggerExecutor<T, B::Factors>>,
+        app: App,
+        runtime_config: B::RuntimeConfig,
+        loader: &impl ComponentLoader,
+    ) -> anyhow::Result<TriggerApp<T, B::Factors>> {
+        let _sloth_guard = warn_if_wasm_build_slothful();
+        executor
+            .clone()
+            .load_app(app, runtime_config.into(), loader)
+            .await
     }
 
-    /// Run the [`TriggerApp`] with the given [`App`] and options.
+    /// Run the [`TriggerApp`] with the given [`App`] and options until
+    /// `shutdown` is requested.
     pub async fn run(
         mut self,
         app: App,
         common_options: FactorsConfig,
         options: B::CliArgs,
         loader: &impl ComponentLoader,
+        shutdown: ShutdownSignal,
     ) -> anyhow::Result<impl Future<Output = anyhow::Result<()>>> {
         let configured_app = self.build(app, common_options, options, loader).await?;
-        Ok(self.trigger.run(configured_app))
+        // No reloads will be sent
+        let (_, reloads) = tokio::sync::mpsc::channel(1);
+        Ok(self
+            .trigger
+            .run_until_shutdown(configured_app, reloads, shutdown))
+    }
+
+    /// Run the [`TriggerApp`] with the given [`App`] and options until
+    /// `shutdown` is requested, loading each [`App`] received from
+    /// `reload_requests` into the same executor and passing it to the running
+    /// trigger.
+    ///
+    /// The returned future completes when the trigger does, or when the
+    /// trigger does not support reloading and a reload is requested.
+    pub async fn run_with_reloads(
+        mut self,
+        app: App,
+        common_options: FactorsConfig,
+        options: B::CliArgs,
+        loader: impl ComponentLoader,
+        mut reload_requests: tokio::sync::mpsc::Receiver<App>,
+        shutdown: ShutdownSignal,
+    ) -> anyhow::Result<impl Future<Output = anyhow::Result<()>>> {
+        let (executor, runtime_config) = self.build_executor(&common_options, &options)?;
+        let configured_app =
+            Self::load_into_executor(&executor, app, runtime_config.clone(), &loader).await?;
+
+        let (reloads_tx, reloads_rx) = tokio::sync::mpsc::channel(1);
+        let run = self
+            .trigger
+            .run_until_shutdown(configured_app, reloads_rx, shutdown);
+
+        let reload = async move {
+            while let Some(app) = reload_requests.recv().await {
+                // Reuse the factors and runtime config, so that the new app
+                // shares state such as key-value stores with the old one.
+                let reloaded =
+                    Self::load_into_executor(&executor, app, runtime_config.clone(), &loader).await;
+                match reloaded {
+                    Ok(configured_app) => {
+                        if reloads_tx.send(configured_app).await.is_err() {
+                            tracing::info!("Trigger does not support reloading: exiting");
+                            return Ok(());
+                        }
+                    }
+                    Err(err) => tracing::error!("Failed to reload application: {err:?}"),
+                }
+            }
+            // Without further reloads, the trigger runs until it finishes
+            futures::future::pending::<anyhow::Result<()>>().await
+        };
+
+        Ok(async move {
+            tokio::select! {
+                res = run => res,
+                res = reload => res,
+            }
+        })
     }
 }
 
@@ -354,7 +554,11 @@ pub trait RuntimeFactorsBuilder {
     /// CLI arguments not included in [`FactorsConfig`] needed  to build the [`RuntimeFactors`].
     type CliArgs: clap::Args;
     /// The wrapped runtime config type.
-    type RuntimeConfig: Into<<Self::Factors as RuntimeFactors>::RuntimeConfig>;
+    ///
+    /// When the application is reloaded, the new app is loaded with a clone
+    /// of the runtime config the first app was loaded with, so clones should
+    /// share state such as store connections.
+    type RuntimeConfig: Into<<Self::Factors as RuntimeFactors>::RuntimeConfig> + Clone;
 
     /// Build the factors and runtime config from the given options.
     fn build(
@@ -407,3 +611,28 @@ pub enum UserProvidedPath {
     /// Explicitly unset.
     Unset,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reload_is_requested_for_each_line_of_input() {
+        let input: &[u8] = b"reload\n\nreload\nreload";
+        let mut attempts = 0;
+        let mut reloads = reload_requests(input, move || {
+            attempts += 1;
+            if attempts == 2 {
+                anyhow::bail!("invalid manifest");
+            }
+            Ok(attempts)
+        });
+
+        let mut reloaded = vec![];
+        while let Some(app) = reloads.recv().await {
+            reloaded.push(app);
+        }
+        // A failed reload does not stop further reloads
+        assert_eq!(vec![1, 3, 4], reloaded);
+    }
+}
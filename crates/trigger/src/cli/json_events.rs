@@ -0,0 +1,53 @@
+use std::{
+    io::Write,
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::Context;
+
+/// The stream to which `spin up --output json` events are written.
+static EVENTS: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+
+/// Reserves stdout for JSON events.
+///
+/// On Unix, the original stdout becomes a dedicated event stream and the
+/// process's stdout is pointed at stderr, so that nothing else written to
+/// stdout - by the trigger, a dependency or a component - can be mixed into
+/// the JSON. On other platforms events share stdout with other output.
+pub(crate) fn reserve_stdout() -> anyhow::Result<()> {
+    let events = take_stdout().context("Failed to reserve stdout for JSON output")?;
+    // If already set, stdout has already been reserved
+    _ = EVENTS.set(Mutex::new(events));
+    Ok(())
+}
+
+/// Writes an event to the JSON event stream as a single line.
+pub fn emit(event: &serde_json::Value) -> anyhow::Result<()> {
+    let events = EVENTS.get_or_init(|| Mutex::new(Box::new(std::io::stdout())));
+    let mut line = serde_json::to_vec(event)?;
+    line.push(b'\n');
+    let mut events = events.lock().unwrap();
+    events.write_all(&line)?;
+    events.flush()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn take_stdout() -> std::io::Result<Box<dyn Write + Send>> {
+    use std::os::fd::AsFd;
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.flush()?;
+    let events = stdout.as_fd().try_clone_to_owned()?;
+    // SAFETY: dup2 only operates on file descriptors, both of which are open
+    // for the lifetime of the process.
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(Box::new(std::fs::File::from(events)))
+}
+
+#[cfg(not(unix))]
+fn take_stdout() -> std::io::Result<Box<dyn Write + Send>> {
+    Ok(Box::new(std::io::stdout()))
+}
@@ -12,6 +12,9 @@ use super::RuntimeFactorsBuilder;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LaunchMetadata {
     all_flags: Vec<LaunchFlag>,
+    // Triggers built before this was added can't reload
+    #[serde(default)]
+    supports_reload: bool,
 }
 
 // This assumes no triggers that want to participate in multi-trigger
@@ -35,7 +38,16 @@ impl LaunchMetadata {
             .map(LaunchFlag::infer)
             .collect();
 
-        LaunchMetadata { all_flags }
+        LaunchMetadata {
+            all_flags,
+            supports_reload: T::supports_reload(),
+        }
+    }
+
+    /// Whether the trigger can switch to a reloaded app while running. See
+    /// [`Trigger::supports_reload`].
+    pub fn supports_reload(&self) -> bool {
+        self.supports_reload
     }
 
     pub fn matches<'a>(&self, groups: &[Vec<&'a OsString>]) -> Vec<&'a OsString> {
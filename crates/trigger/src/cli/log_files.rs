@@ -0,0 +1,248 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration of component stdio log files, from the `[component_logs]`
+/// runtime config table.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentLogsConfig {
+    /// The format in which log lines are written.
+    #[serde(default)]
+    pub format: ComponentLogFormat,
+    /// Rotate a log file once writing to it would make it larger than this
+    /// many bytes.
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    /// Rotate a log file once it is this many seconds old.
+    #[serde(default)]
+    pub max_file_age_secs: Option<u64>,
+    /// How many rotated files to keep for each log file. Older files are
+    /// deleted.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for ComponentLogsConfig {
+    fn default() -> Self {
+        Self {
+            format: Default::default(),
+            max_file_size: None,
+            max_file_age_secs: None,
+            max_files: default_max_files(),
+        }
+    }
+}
+
+fn default_max_files() -> usize {
+    5
+}
+
+/// The format of component stdio log files.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentLogFormat {
+    /// Output is written as is.
+    #[default]
+    Text,
+    /// Each line of output is written as a JSON object with a timestamp, the
+    /// component ID, the stream and the current trace ID.
+    Json,
+}
+
+impl ComponentLogFormat {
+    /// The extension of log files in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "jsonl",
+        }
+    }
+}
+
+/// The path of the log file for a component's output stream (`stdout` or
+/// `stderr`).
+pub fn component_log_path(
+    log_dir: &Path,
+    component_id: &str,
+    stream: &str,
+    format: ComponentLogFormat,
+) -> PathBuf {
+    let sanitized_component_id = sanitize_filename::sanitize(component_id);
+    let extension = format.extension();
+    log_dir.join(format!("{sanitized_component_id}_{stream}.{extension}"))
+}
+
+/// A line of component output, as written to log files in the
+/// [`ComponentLogFormat::Json`] format.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ComponentLogRecord {
+    /// When the line was written, in RFC 3339 format.
+    pub timestamp: String,
+    pub component_id: String,
+    /// `stdout` or `stderr`.
+    pub stream: String,
+    /// The ID of the trace the line was written in, if any.
+    pub trace_id: Option<String>,
+    pub message: String,
+}
+
+/// A log file which is rotated according to a [`ComponentLogsConfig`].
+///
+/// Rotated files are renamed to `<path>.1`, `<path>.2`, ... with `<path>.1`
+/// being the most recent.
+pub(crate) struct RotatingLogFile {
+    path: PathBuf,
+    file: File,
+    /// The size of the current file.
+    size: u64,
+    /// When the current file was started.
+    started: SystemTime,
+    max_file_size: Option<u64>,
+    max_file_age: Option<Duration>,
+    max_files: usize,
+}
+
+impl RotatingLogFile {
+    pub fn open(path: &Path, config: &ComponentLogsConfig) -> std::io::Result<Self> {
+        let file = open_append(path)?;
+        let metadata = file.metadata()?;
+        Ok(Self {
+            path: path.to_owned(),
+            size: metadata.len(),
+            started: metadata.created().unwrap_or_else(|_| SystemTime::now()),
+            file,
+            max_file_size: config.max_file_size,
+            max_file_age: config.max_file_age_secs.map(Duration::from_secs),
+            max_files: config.max_files,
+        })
+    }
+
+    /// Write all of `buf` to the file, rotating it first if needed.
+    pub fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        if self.should_rotate(buf.len()) {
+            self.rotate()?;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+
+    fn should_rotate(&self, len: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let too_large = self
+            .max_file_size
+            .is_some_and(|max| self.size + len as u64 > max);
+        let too_old = self
+            .max_file_age
+            .is_some_and(|max| self.started.elapsed().is_ok_and(|elapsed| elapsed >= max));
+        too_large || too_old
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        // Shift <path>.N to <path>.N+1, dropping any beyond the retention limit
+        let oldest = rotated_path(&self.path, self.max_files.max(1));
+        if oldest.exists() {
+            std::fs::remove_file(&oldest)?;
+        }
+        for n in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        self.started = SystemTime::now();
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    File::options().create(true).append(true).open(path)
+}
+
+/// The path of the `n`th most recent rotated file of the log file at `path`.
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{n}"));
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_file_size: u64, max_files: usize) -> ComponentLogsConfig {
+        ComponentLogsConfig {
+            max_file_size: Some(max_file_size),
+            max_files,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rotates_when_size_exceeded() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("hello_stdout.txt");
+        let mut log = RotatingLogFile::open(&path, &config(10, 2))?;
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            log.write_all(line.as_bytes())?;
+        }
+        log.flush()?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "fourth\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 1))?, "third\n");
+        assert_eq!(std::fs::read_to_string(rotated_path(&path, 2))?, "second\n");
+        assert!(!rotated_path(&path, 3).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn does_not_rotate_a_single_large_write() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("hello_stdout.txt");
+        let mut log = RotatingLogFile::open(&path, &config(4, 2))?;
+
+        log.write_all(b"a long line\n")?;
+        log.flush()?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "a long line\n");
+        assert!(!rotated_path(&path, 1).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn parses_runtime_config() {
+        let config: ComponentLogsConfig = toml::from_str(
+            r#"
+            format = "json"
+            max_file_size = 1048576
+            max_files = 3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.format, ComponentLogFormat::Json);
+        assert_eq!(config.max_file_size, Some(1048576));
+        assert_eq!(config.max_file_age_secs, None);
+        assert_eq!(config.max_files, 3);
+    }
+}
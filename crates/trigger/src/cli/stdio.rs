@@ -1,6 +1,8 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    future::Future,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
     task::Poll,
 };
 
@@ -12,6 +14,11 @@ use spin_factors::RuntimeFactors;
 use spin_factors_executor::ExecutorHooks;
 use tokio::io::AsyncWrite;
 
+use super::log_files::{
+    component_log_path, ComponentLogFormat, ComponentLogRecord, ComponentLogsConfig,
+    RotatingLogFile,
+};
+
 /// Which components should have their logs followed on stdout/stderr.
 #[derive(Clone, Debug, Default)]
 pub enum FollowComponents {
@@ -39,35 +46,68 @@ impl FollowComponents {
 pub struct StdioLoggingExecutorHooks {
     follow_components: FollowComponents,
     log_dir: Option<PathBuf>,
+    logs_config: ComponentLogsConfig,
+    /// Open log files, shared by all instances of a component.
+    log_files: Mutex<HashMap<PathBuf, Arc<Mutex<RotatingLogFile>>>>,
 }
 
 impl StdioLoggingExecutorHooks {
-    pub fn new(follow_components: FollowComponents, log_dir: Option<PathBuf>) -> Self {
+    pub fn new(
+        follow_components: FollowComponents,
+        log_dir: Option<PathBuf>,
+        logs_config: ComponentLogsConfig,
+    ) -> Self {
         Self {
             follow_components,
             log_dir,
+            logs_config,
+            log_files: Default::default(),
         }
     }
 
     fn component_stdio_writer(
         &self,
         component_id: &str,
-        log_suffix: &str,
+        log_suffix: &'static str,
         log_dir: Option<&Path>,
     ) -> Result<ComponentStdioWriter> {
-        let sanitized_component_id = sanitize_filename::sanitize(component_id);
-        let log_path = log_dir
-            .map(|log_dir| log_dir.join(format!("{sanitized_component_id}_{log_suffix}.txt",)));
-        let log_path = log_path.as_deref();
+        let log_path = log_dir.map(|log_dir| {
+            component_log_path(log_dir, component_id, log_suffix, self.logs_config.format)
+        });
 
         let follow = self.follow_components.should_follow(component_id);
         match log_path {
-            Some(log_path) => ComponentStdioWriter::new_forward(log_path, follow)
-                .with_context(|| format!("Failed to open log file {}", quoted_path(log_path))),
+            Some(log_path) => {
+                let file = self.log_file(&log_path).with_context(|| {
+                    format!("Failed to open log file {}", quoted_path(&log_path))
+                })?;
+                let log = ComponentLog {
+                    file,
+                    format: self.logs_config.format,
+                    component_id: component_id.to_owned(),
+                    stream: log_suffix,
+                    partial_line: Vec::new(),
+                    pending: None,
+                };
+                ComponentStdioWriter::new_forward(log, follow)
+            }
             None => ComponentStdioWriter::new_inherit(),
         }
     }
 
+    fn log_file(&self, log_path: &Path) -> std::io::Result<Arc<Mutex<RotatingLogFile>>> {
+        let mut log_files = self.log_files.lock().unwrap();
+        if let Some(file) = log_files.get(log_path) {
+            return Ok(file.clone());
+        }
+        let file = Arc::new(Mutex::new(RotatingLogFile::open(
+            log_path,
+            &self.logs_config,
+        )?));
+        log_files.insert(log_path.to_owned(), file.clone());
+        Ok(file)
+    }
+
     fn validate_follows(&self, app: &spin_app::App) -> anyhow::Result<()> {
         match &self.follow_components {
             FollowComponents::Named(names) => {
@@ -100,7 +140,10 @@ impl<F: RuntimeFactors, U> ExecutorHooks<F, U> for StdioLoggingExecutorHooks {
             std::fs::create_dir_all(dir)
                 .with_context(|| format!("Failed to create log dir {}", quoted_path(dir)))?;
 
-            println!("Logging component stdio to {}", quoted_path(dir.join("")))
+            super::print_info(format_args!(
+                "Logging component stdio to {}",
+                quoted_path(dir.join(""))
+            ));
         }
         Ok(())
     }
@@ -138,8 +181,7 @@ enum ComponentStdioWriterInner {
     Inherit,
     /// Forward stdout/stderr to a file in addition to the inherited stdout/stderr.
     Forward {
-        sync_file: std::fs::File,
-        async_file: tokio::fs::File,
+        log: ComponentLog,
         state: ComponentStdioWriterState,
         follow: bool,
     },
@@ -148,25 +190,17 @@ enum ComponentStdioWriterInner {
 #[derive(Debug)]
 enum ComponentStdioWriterState {
     File,
+    /// Waiting for the given number of bytes of output to be written to the
+    /// log file.
+    Writing(usize),
     Follow(std::ops::Range<usize>),
 }
 
 impl ComponentStdioWriter {
-    fn new_forward(log_path: &Path, follow: bool) -> anyhow::Result<Self> {
-        let sync_file = std::fs::File::options()
-            .create(true)
-            .append(true)
-            .open(log_path)?;
-
-        let async_file = sync_file
-            .try_clone()
-            .context("could not get async file handle")?
-            .into();
-
+    fn new_forward(log: ComponentLog, follow: bool) -> anyhow::Result<Self> {
         Ok(Self {
             inner: ComponentStdioWriterInner::Forward {
-                sync_file,
-                async_file,
+                log,
                 state: ComponentStdioWriterState::File,
                 follow,
             },
@@ -200,23 +234,26 @@ impl AsyncWrite for ComponentStdioWriter {
                     };
                     return Poll::Ready(Ok(written));
                 }
-                ComponentStdioWriterInner::Forward {
-                    async_file,
-                    state,
-                    follow,
-                    ..
-                } => match &state {
+                ComponentStdioWriterInner::Forward { log, state, follow } => match &state {
                     ComponentStdioWriterState::File => {
-                        let written =
-                            futures::ready!(std::pin::Pin::new(async_file).poll_write(cx, buf));
-                        let written = match written {
-                            Ok(w) => w,
-                            Err(e) => return Poll::Ready(Err(e)),
-                        };
+                        // Wait for any flush that was started but not completed
+                        if let Err(e) = futures::ready!(log.poll_pending(cx)) {
+                            return Poll::Ready(Err(e));
+                        }
+                        log.start_write(buf);
+                        *state = ComponentStdioWriterState::Writing(buf.len());
+                    }
+                    ComponentStdioWriterState::Writing(len) => {
+                        let len = *len;
+                        if let Err(e) = futures::ready!(log.poll_pending(cx)) {
+                            *state = ComponentStdioWriterState::File;
+                            return Poll::Ready(Err(e));
+                        }
                         if *follow {
-                            *state = ComponentStdioWriterState::Follow(0..written);
+                            *state = ComponentStdioWriterState::Follow(0..len);
                         } else {
-                            return Poll::Ready(Ok(written));
+                            *state = ComponentStdioWriterState::File;
+                            return Poll::Ready(Ok(len));
                         }
                     }
                     ComponentStdioWriterState::Follow(range) => {
@@ -251,10 +288,15 @@ impl AsyncWrite for ComponentStdioWriter {
             ComponentStdioWriterInner::Inherit => {
                 std::pin::Pin::new(&mut tokio::io::stderr()).poll_flush(cx)
             }
-            ComponentStdioWriterInner::Forward {
-                async_file, state, ..
-            } => match state {
-                ComponentStdioWriterState::File => std::pin::Pin::new(async_file).poll_flush(cx),
+            ComponentStdioWriterInner::Forward { log, state, .. } => match state {
+                ComponentStdioWriterState::File => {
+                    // A pending operation here can only be an earlier call's flush
+                    if log.pending.is_none() {
+                        log.start_flush();
+                    }
+                    log.poll_pending(cx)
+                }
+                ComponentStdioWriterState::Writing(_) => log.poll_pending(cx),
                 ComponentStdioWriterState::Follow(_) => {
                     std::pin::Pin::new(&mut tokio::io::stderr()).poll_flush(cx)
                 }
@@ -266,21 +308,7 @@ impl AsyncWrite for ComponentStdioWriter {
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<std::result::Result<(), std::io::Error>> {
-        let this = self.get_mut();
-
-        match &mut this.inner {
-            ComponentStdioWriterInner::Inherit => {
-                std::pin::Pin::new(&mut tokio::io::stderr()).poll_flush(cx)
-            }
-            ComponentStdioWriterInner::Forward {
-                async_file, state, ..
-            } => match state {
-                ComponentStdioWriterState::File => std::pin::Pin::new(async_file).poll_shutdown(cx),
-                ComponentStdioWriterState::Follow(_) => {
-                    std::pin::Pin::new(&mut tokio::io::stderr()).poll_flush(cx)
-                }
-            },
-        }
+        self.poll_flush(cx)
     }
 }
 
@@ -293,14 +321,12 @@ impl std::io::Write for ComponentStdioWriter {
                 std::io::stderr().write_all(buf)?;
                 Ok(buf.len())
             }
-            ComponentStdioWriterInner::Forward {
-                sync_file, follow, ..
-            } => {
-                let written = sync_file.write(buf)?;
+            ComponentStdioWriterInner::Forward { log, follow, .. } => {
+                log.write(buf)?;
                 if *follow {
-                    std::io::stderr().write_all(&buf[..written])?;
+                    std::io::stderr().write_all(buf)?;
                 }
-                Ok(written)
+                Ok(buf.len())
             }
         }
     }
@@ -308,10 +334,8 @@ impl std::io::Write for ComponentStdioWriter {
     fn flush(&mut self) -> std::io::Result<()> {
         match &mut self.inner {
             ComponentStdioWriterInner::Inherit => std::io::stderr().flush(),
-            ComponentStdioWriterInner::Forward {
-                sync_file, follow, ..
-            } => {
-                sync_file.flush()?;
+            ComponentStdioWriterInner::Forward { log, follow, .. } => {
+                log.flush()?;
                 if *follow {
                     std::io::stderr().flush()?;
                 }
@@ -321,6 +345,127 @@ impl std::io::Write for ComponentStdioWriter {
     }
 }
 
+/// Writes one of a component instance's output streams to the component's
+/// log file, in the configured format.
+///
+/// The log file is shared by all instances of the component, so when writing
+/// asynchronously it is locked and written on the blocking thread pool rather
+/// than on the runtime's worker threads.
+struct ComponentLog {
+    file: Arc<Mutex<RotatingLogFile>>,
+    format: ComponentLogFormat,
+    component_id: String,
+    stream: &'static str,
+    /// Output after the last newline, not yet written as a JSON record.
+    partial_line: Vec<u8>,
+    /// An asynchronous write or flush that has been started but whose result
+    /// has not yet been reported.
+    pending: Option<tokio::task::JoinHandle<std::io::Result<()>>>,
+}
+
+impl ComponentLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        let bytes = self.format(buf);
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.file.lock().unwrap().write_all(&bytes)
+    }
+
+    /// Flushes the log file. In JSON format, output after the last newline is
+    /// kept back, so that records are only ever written for complete lines.
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.lock().unwrap().flush()
+    }
+
+    /// Starts writing to the log file on the blocking thread pool. The result
+    /// is reported by [`ComponentLog::poll_pending`].
+    fn start_write(&mut self, buf: &[u8]) {
+        let bytes = self.format(buf);
+        let file = self.file.clone();
+        self.pending = Some(tokio::task::spawn_blocking(move || {
+            if bytes.is_empty() {
+                return Ok(());
+            }
+            file.lock().unwrap().write_all(&bytes)
+        }));
+    }
+
+    /// Starts flushing the log file on the blocking thread pool. The result
+    /// is reported by [`ComponentLog::poll_pending`].
+    fn start_flush(&mut self) {
+        let file = self.file.clone();
+        self.pending = Some(tokio::task::spawn_blocking(move || {
+            file.lock().unwrap().flush()
+        }));
+    }
+
+    fn poll_pending(&mut self, cx: &mut std::task::Context<'_>) -> Poll<std::io::Result<()>> {
+        let Some(pending) = &mut self.pending else {
+            return Poll::Ready(Ok(()));
+        };
+        let result = futures::ready!(std::pin::Pin::new(pending).poll(cx));
+        self.pending = None;
+        Poll::Ready(result.unwrap_or_else(|e| Err(std::io::Error::other(e))))
+    }
+
+    /// The bytes to write to the log file for `buf`. In JSON format, this is a
+    /// record for each line completed by `buf`.
+    fn format(&mut self, buf: &[u8]) -> Vec<u8> {
+        match self.format {
+            ComponentLogFormat::Text => buf.to_vec(),
+            ComponentLogFormat::Json => {
+                self.partial_line.extend_from_slice(buf);
+                let Some(end) = self.partial_line.iter().rposition(|&b| b == b'\n') else {
+                    return Vec::new();
+                };
+                let lines: Vec<u8> = self.partial_line.drain(..=end).collect();
+                let records: String = lines[..end]
+                    .split(|&b| b == b'\n')
+                    .map(|line| self.json_record(line))
+                    .collect();
+                records.into_bytes()
+            }
+        }
+    }
+
+    fn json_record(&self, line: &[u8]) -> String {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let record = ComponentLogRecord {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            component_id: self.component_id.clone(),
+            stream: self.stream.to_owned(),
+            trace_id: spin_telemetry::current_trace_id(),
+            message: String::from_utf8_lossy(line).into_owned(),
+        };
+        // Serializing a struct of strings can't fail
+        let mut json = serde_json::to_string(&record).unwrap();
+        json.push('\n');
+        json
+    }
+}
+
+impl Drop for ComponentLog {
+    fn drop(&mut self) {
+        // Don't lose output without a trailing newline
+        if self.partial_line.is_empty() {
+            return;
+        }
+        let line = std::mem::take(&mut self.partial_line);
+        let record = self.json_record(&line);
+        let file = self.file.clone();
+        let write = move || {
+            let mut file = file.lock().unwrap();
+            _ = file.write_all(record.as_bytes());
+            _ = file.flush();
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(write)),
+            Err(_) => write(),
+        }
+    }
+}
+
 fn bullet_list<S: std::fmt::Display>(items: impl IntoIterator<Item = S>) -> String {
     items
         .into_iter()
@@ -328,3 +473,63 @@ fn bullet_list<S: std::fmt::Display>(items: impl IntoIterator<Item = S>) -> Stri
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_log(path: &Path) -> ComponentLog {
+        let config = ComponentLogsConfig {
+            format: ComponentLogFormat::Json,
+            ..Default::default()
+        };
+        ComponentLog {
+            file: Arc::new(Mutex::new(RotatingLogFile::open(path, &config).unwrap())),
+            format: ComponentLogFormat::Json,
+            component_id: "hello".to_owned(),
+            stream: "stdout",
+            partial_line: Vec::new(),
+            pending: None,
+        }
+    }
+
+    fn messages(path: &Path) -> Vec<String> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<ComponentLogRecord>(line)
+                    .unwrap()
+                    .message
+            })
+            .collect()
+    }
+
+    #[test]
+    fn json_partial_line_is_written_on_drop_not_flush() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("hello_stdout.jsonl");
+        let mut log = json_log(&path);
+
+        log.write(b"first\nsec").unwrap();
+        log.flush().unwrap();
+        assert_eq!(vec!["first"], messages(&path));
+
+        log.write(b"ond").unwrap();
+        drop(log);
+        assert_eq!(vec!["first", "second"], messages(&path));
+    }
+
+    #[tokio::test]
+    async fn async_writes_reach_log_file() {
+        use tokio::io::AsyncWriteExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("hello_stdout.jsonl");
+        let mut writer = ComponentStdioWriter::new_forward(json_log(&path), false).unwrap();
+
+        writer.write_all(b"one\ntwo\nthr").await.unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(vec!["one", "two"], messages(&path));
+    }
+}
@@ -6,7 +6,7 @@ use std::future::Future;
 use clap::Args;
 use spin_core::Linker;
 use spin_factors::RuntimeFactors;
-use spin_factors_executor::{FactorsExecutorApp, FactorsInstanceBuilder};
+use spin_factors_executor::{FactorsExecutorApp, FactorsInstanceBuilder, InstanceHooks};
 
 pub use spin_app::App;
 
@@ -17,6 +17,9 @@ pub type TriggerApp<T, F> = FactorsExecutorApp<F, <T as Trigger<F>>::InstanceSta
 pub type TriggerInstanceBuilder<'a, T, F> =
     FactorsInstanceBuilder<'a, F, <T as Trigger<F>>::InstanceState>;
 
+/// Type alias for a [`spin_factors_executor::InstanceHooks`] specialized to a [`Trigger`].
+pub type TriggerInstanceHooks<T, F> = InstanceHooks<F, <T as Trigger<F>>::InstanceState>;
+
 /// Type alias for a [`spin_core::Store`] specialized to a [`Trigger`].
 pub type Store<T, F> = spin_core::Store<TriggerInstanceState<T, F>>;
 
@@ -65,6 +68,58 @@ pub trait Trigger<F: RuntimeFactors>: Sized + Send {
         trigger_app: TriggerApp<Self, F>,
     ) -> impl Future<Output = anyhow::Result<()>> + Send;
 
+    /// Whether this trigger can switch to a reloaded app while running. A
+    /// trigger which returns true must override
+    /// [`Trigger::run_with_reloads`] or [`Trigger::run_until_shutdown`] to
+    /// switch to the apps it receives.
+    ///
+    /// This is reported in the trigger's [`LaunchMetadata`](cli::LaunchMetadata),
+    /// so that `spin up` restarts triggers that can't reload instead.
+    fn supports_reload() -> bool {
+        false
+    }
+
+    /// Run this trigger, switching to each [`TriggerApp`] received from
+    /// `reloads` as it arrives.
+    ///
+    /// The default implementation drops `reloads` and calls [`Trigger::run`].
+    /// This tells the sender that the trigger can't switch apps while running,
+    /// and must be restarted instead.
+    fn run_with_reloads(
+        self,
+        trigger_app: TriggerApp<Self, F>,
+        reloads: tokio::sync::mpsc::Receiver<TriggerApp<Self, F>>,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        drop(reloads);
+        self.run(trigger_app)
+    }
+
+    /// Run this trigger until `shutdown` is requested, switching to each
+    /// [`TriggerApp`] received from `reloads` as it arrives.
+    ///
+    /// Once shutdown is requested, the trigger should stop accepting new work,
+    /// wait for work already in progress to finish, and then call
+    /// [`FactorsExecutorApp::shutdown`] on its app so that state held by
+    /// factors is flushed.
+    ///
+    /// The default implementation runs [`Trigger::run_with_reloads`] until
+    /// shutdown is requested and then drops it, cancelling any work in
+    /// progress.
+    fn run_until_shutdown(
+        self,
+        trigger_app: TriggerApp<Self, F>,
+        reloads: tokio::sync::mpsc::Receiver<TriggerApp<Self, F>>,
+        shutdown: ShutdownSignal,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        let run = self.run_with_reloads(trigger_app, reloads);
+        async move {
+            tokio::select! {
+                res = run => res,
+                _ = shutdown.requested() => Ok(()),
+            }
+        }
+    }
+
     /// Returns a list of host requirements supported by this trigger specifically.
     ///
     /// See [`App::ensure_needs_only`].
@@ -72,3 +127,31 @@ pub trait Trigger<F: RuntimeFactors>: Sized + Send {
         Vec::new()
     }
 }
+
+/// Signals a running [`Trigger`] that it should shut down gracefully.
+#[derive(Clone)]
+pub struct ShutdownSignal(tokio::sync::watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    /// Returns a signal, and a sender which requests shutdown when `true` is
+    /// sent to it.
+    pub fn new() -> (tokio::sync::watch::Sender<bool>, Self) {
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        (tx, Self(rx))
+    }
+
+    /// Returns a signal which never requests shutdown.
+    pub fn never() -> Self {
+        Self::new().1
+    }
+
+    /// Completes when shutdown has been requested. If the sender is dropped
+    /// without requesting shutdown, this never completes.
+    pub async fn requested(&self) {
+        let mut rx = self.0.clone();
+        let sender_dropped = rx.wait_for(|requested| *requested).await.is_err();
+        if sender_dropped {
+            futures::future::pending::<()>().await;
+        }
+    }
+}
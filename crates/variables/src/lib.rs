@@ -10,6 +10,8 @@ pub use env::*;
 pub use statik::*;
 pub use vault::*;
 
+use std::sync::Arc;
+
 use serde::Deserialize;
 use spin_expressions::Provider;
 use spin_factors::{anyhow, runtime_config::toml::GetTomlValue};
@@ -19,7 +21,7 @@ use spin_factor_variables::runtime_config::RuntimeConfig;
 /// Resolves a runtime configuration for the variables factor from a TOML table.
 pub fn runtime_config_from_toml(table: &impl GetTomlValue) -> anyhow::Result<RuntimeConfig> {
     // Always include the environment variable provider.
-    let var_provider = vec![Box::<EnvVariablesProvider>::default() as _];
+    let var_provider = vec![Arc::<EnvVariablesProvider>::default() as _];
     let value = table
         .get("variables_provider")
         .or_else(|| table.get("config_provider"));
@@ -32,8 +34,8 @@ pub fn runtime_config_from_toml(table: &impl GetTomlValue) -> anyhow::Result<Run
     let provider_configs: Vec<VariableProviderConfiguration> = array.clone().try_into()?;
     let mut providers = provider_configs
         .into_iter()
-        .map(VariableProviderConfiguration::into_provider)
-        .collect::<anyhow::Result<Vec<_>>>()?;
+        .map(|config| config.into_provider().map(Arc::from))
+        .collect::<anyhow::Result<Vec<Arc<dyn Provider>>>>()?;
     providers.extend(var_provider);
     Ok(RuntimeConfig { providers })
 }
@@ -8,6 +8,8 @@ pub mod cloud;
 pub mod doctor;
 /// Commands for external subcommands (i.e. plugins)
 pub mod external;
+/// Command for showing component logs.
+pub mod logs;
 /// Command for creating a new application.
 pub mod new;
 /// Command for adding a plugin to Spin
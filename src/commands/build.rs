@@ -5,7 +5,7 @@ use clap::Parser;
 
 use crate::{
     directory_rels::notify_if_nondefault_rel,
-    opts::{APP_MANIFEST_FILE_OPT, BUILD_UP_OPT},
+    opts::{OutputFormat, APP_MANIFEST_FILE_OPT, BUILD_UP_OPT},
 };
 
 use super::up::UpCommand;
@@ -29,6 +29,27 @@ pub struct BuildCommand {
     #[clap(short = 'c', long, multiple = true)]
     pub component_id: Vec<String>,
 
+    /// Build all selected components, even if they are unchanged since their
+    /// last successful build.
+    #[clap(long = "force", takes_value = false)]
+    pub force: bool,
+
+    /// The maximum number of components to build at the same time. Components
+    /// are always built after any components whose output they depend on.
+    #[clap(short = 'j', long = "jobs", default_value = "1")]
+    pub jobs: usize,
+
+    /// The build profile to use. Components with this profile are built
+    /// with the commands and environment it specifies.
+    #[clap(long = "profile")]
+    pub profile: Option<String>,
+
+    /// The format in which to report build progress. With `json`, each build
+    /// step is reported as a line of JSON on stdout, and the output of the
+    /// build commands is written to stderr.
+    #[clap(value_enum, long = "output", default_value = "text")]
+    pub output: OutputFormat,
+
     /// Run the application after building.
     #[clap(name = BUILD_UP_OPT, short = 'u', long = "up")]
     pub up: bool,
@@ -43,7 +64,13 @@ impl BuildCommand {
             spin_common::paths::find_manifest_file_path(self.app_source.as_ref())?;
         notify_if_nondefault_rel(&manifest_file, distance);
 
-        spin_build::build(&manifest_file, &self.component_id).await?;
+        let options = spin_build::BuildOptions {
+            force: self.force,
+            jobs: self.jobs,
+            profile: self.profile.clone(),
+            json_output: self.output == OutputFormat::Json,
+        };
+        spin_build::build(&manifest_file, &self.component_id, &options).await?;
 
         if self.up {
             let mut cmd = UpCommand::parse_from(
@@ -54,6 +81,12 @@ impl BuildCommand {
                 .chain(self.up_args),
             );
             cmd.file_source = Some(manifest_file);
+            if cmd.profile.is_none() {
+                cmd.profile = self.profile;
+            }
+            if cmd.output == OutputFormat::Text {
+                cmd.output = self.output;
+            }
             cmd.run().await
         } else {
             Ok(())
@@ -5,7 +5,7 @@ use clap::Parser;
 use dialoguer::{console::Emoji, Confirm, Select};
 use spin_doctor::{Diagnosis, DryRunNotSupported, PatientDiagnosis};
 
-use crate::opts::APP_MANIFEST_FILE_OPT;
+use crate::opts::{OutputFormat, APP_MANIFEST_FILE_OPT};
 
 #[derive(Parser, Debug)]
 #[clap(about = "Detect and fix problems with Spin applications")]
@@ -20,6 +20,17 @@ pub struct DoctorCommand {
         alias = "file"
     )]
     pub app_source: Option<PathBuf>,
+
+    /// The runtime config file the application will be run with. If provided,
+    /// key-value stores and SQLite databases used by the application are
+    /// checked against it.
+    #[clap(long = "runtime-config-file")]
+    pub runtime_config_file: Option<PathBuf>,
+
+    /// The format in which to report problems. With `json`, each problem is
+    /// reported as a line of JSON on stdout, and no treatments are offered.
+    #[clap(value_enum, long = "output", default_value = "text")]
+    pub output: OutputFormat,
 }
 
 impl DoctorCommand {
@@ -33,6 +44,15 @@ impl DoctorCommand {
             );
         }
 
+        let mut checkup = spin_doctor::Checkup::new(&manifest_file)?;
+        if let Some(runtime_config_file) = &self.runtime_config_file {
+            checkup.set_runtime_config_file(runtime_config_file);
+        }
+
+        if self.output == OutputFormat::Json {
+            return report_json(checkup).await;
+        }
+
         println!("{icon}The Spin Doctor is in.", icon = Emoji("📟 ", ""));
         println!(
             "{icon}Checking {}...",
@@ -40,7 +60,6 @@ impl DoctorCommand {
             icon = Emoji("🩺 ", "")
         );
 
-        let mut checkup = spin_doctor::Checkup::new(manifest_file)?;
         let mut has_problems = false;
         while let Some(PatientDiagnosis { diagnosis, patient }) = checkup.next_diagnosis().await? {
             show_diagnosis(&*diagnosis);
@@ -89,6 +108,33 @@ impl DoctorCommand {
     }
 }
 
+/// Reports each diagnosis as a line of JSON, without offering treatment.
+async fn report_json(mut checkup: spin_doctor::Checkup) -> Result<()> {
+    let mut problems = 0;
+    while let Some(PatientDiagnosis { diagnosis, .. }) = checkup.next_diagnosis().await? {
+        problems += 1;
+        let severity = if diagnosis.is_critical() {
+            "critical"
+        } else {
+            "warning"
+        };
+        let event = serde_json::json!({
+            "event": "diagnosis",
+            "description": diagnosis.description(),
+            "severity": severity,
+            "treatment_available": diagnosis.treatment().is_some(),
+            "treatment": diagnosis.treatment().map(|t| t.summary()),
+        });
+        println!("{event}");
+    }
+    let event = serde_json::json!({
+        "event": "checkup_finished",
+        "problems": problems,
+    });
+    println!("{event}");
+    Ok(())
+}
+
 fn show_diagnosis(diagnosis: &dyn Diagnosis) {
     let icon = if diagnosis.is_critical() {
         Emoji("❗ ", "")
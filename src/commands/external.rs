@@ -4,7 +4,12 @@ use crate::opts::PLUGIN_OVERRIDE_COMPATIBILITY_CHECK_FLAG;
 use anyhow::{anyhow, Result};
 use spin_common::ui::quoted_path;
 use spin_plugins::{
-    badger::BadgerChecker, error::Error as PluginError, manifest::warn_unsupported_version,
+    badger::BadgerChecker,
+    error::Error as PluginError,
+    lockfile::{PinnedPlugin, PluginsLockfile, PLUGINS_LOCK_FILE_NAME},
+    lookup::PluginLookup,
+    manager::{ManifestLocation, PluginManager},
+    manifest::warn_unsupported_version,
     PluginStore,
 };
 use std::io::{stderr, IsTerminal};
@@ -57,14 +62,24 @@ pub async fn execute_external_subcommand(
     app: clap::App<'_>,
 ) -> anyhow::Result<()> {
     let (plugin_name, args, override_compatibility_check) = parse_subcommand(cmd)?;
-    let plugin_store = PluginStore::try_default()?;
-    let plugin_version = ensure_plugin_available(
-        &plugin_name,
-        &plugin_store,
-        app,
-        override_compatibility_check,
-    )
-    .await?;
+    let global_store = PluginStore::try_default()?;
+    let (plugin_store, plugin_version) = match project_pin(&plugin_name)? {
+        // A pinned version is deliberate, so there's no point badgering about upgrades.
+        Some(pin) => (
+            ensure_pinned_plugin_available(&pin, global_store).await?,
+            None,
+        ),
+        None => {
+            let plugin_version = ensure_plugin_available(
+                &plugin_name,
+                &global_store,
+                app,
+                override_compatibility_check,
+            )
+            .await?;
+            (global_store, plugin_version)
+        }
+    };
 
     let binary = plugin_store.installed_binary_path(&plugin_name);
     if !binary.exists() {
@@ -112,6 +127,56 @@ fn set_kill_on_ctrl_c(child: &tokio::process::Child) {
     }
 }
 
+/// Gets the version of the plugin pinned by the project in the current directory, if any.
+fn project_pin(plugin_name: &str) -> anyhow::Result<Option<PinnedPlugin>> {
+    let Some(lockfile_path) = PluginsLockfile::find(&env::current_dir()?) else {
+        return Ok(None);
+    };
+    let lockfile = PluginsLockfile::load(&lockfile_path)?;
+    let pin = lockfile.get(plugin_name).cloned();
+    if pin.is_some() {
+        tracing::info!(
+            "Using version of {plugin_name} pinned in {}",
+            quoted_path(&lockfile_path)
+        );
+    }
+    Ok(pin)
+}
+
+/// Returns the store containing the pinned version of the plugin, installing it
+/// alongside the global plugins if needed. If the globally installed version is
+/// the pinned version, that is used.
+async fn ensure_pinned_plugin_available(
+    pin: &PinnedPlugin,
+    global_store: PluginStore,
+) -> anyhow::Result<PluginStore> {
+    if let Ok(manifest) = global_store.read_plugin_manifest(&pin.name) {
+        if pin.verify(&manifest).is_ok() {
+            return Ok(global_store);
+        }
+    }
+
+    let pinned_store = global_store.pinned_store(&pin.name, &pin.version);
+    if let Ok(manifest) = pinned_store.read_plugin_manifest(&pin.name) {
+        pin.verify(&manifest)?;
+        return Ok(pinned_store);
+    }
+
+    eprintln!(
+        "Installing version {} of the `{}` plugin, as pinned in {PLUGINS_LOCK_FILE_NAME}.",
+        pin.version, pin.name
+    );
+    let manager = PluginManager::try_default()?;
+    let manifest_location =
+        ManifestLocation::PluginsRepository(PluginLookup::new(&pin.name, Some(pin.semver()?)));
+    let manifest = manager
+        .get_manifest(&manifest_location, true, SPIN_VERSION, &None)
+        .await?;
+    pin.verify(&manifest)?;
+    let package = spin_plugins::manager::get_package(&manifest)?;
+    manager.install_pinned(&manifest, package, &None).await
+}
+
 async fn ensure_plugin_available(
     plugin_name: &str,
     plugin_store: &PluginStore,
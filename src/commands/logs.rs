@@ -0,0 +1,532 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, FixedOffset, Utc};
+use clap::Parser;
+use regex::Regex;
+use spin_common::ui::quoted_path;
+use spin_runtime_config::TomlResolver;
+use spin_trigger::cli::{
+    component_log_path, rotated_path, ComponentLogFormat, ComponentLogRecord, ComponentLogsConfig,
+    UserProvidedPath,
+};
+
+use crate::opts::APP_MANIFEST_FILE_OPT;
+
+const STREAMS: [&str; 2] = ["stdout", "stderr"];
+
+/// How often to check log files for new output when following.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Show the output of a Spin application's components.
+#[derive(Parser, Debug)]
+#[clap(about = "Show the output of a Spin application's components")]
+pub struct LogsCommand {
+    /// The application whose logs to show. This may be a manifest (spin.toml)
+    /// file, or a directory containing a spin.toml file.
+    /// If omitted, it defaults to "spin.toml".
+    #[clap(name = APP_MANIFEST_FILE_OPT, long = "from", alias = "file")]
+    pub app_source: Option<PathBuf>,
+
+    /// Show output only from the given component(s).
+    #[clap(short = 'c', long = "component", multiple_occurrences = true)]
+    pub components: Vec<String>,
+
+    /// Show only output written within the given time, e.g. `30s`, `5m`,
+    /// `2h` or `1d`. This needs the `json` component log format.
+    #[clap(long = "since", parse(try_from_str = parse_since))]
+    pub since: Option<Duration>,
+
+    /// Show only lines matching the given regular expression.
+    #[clap(long = "grep")]
+    pub grep: Option<Regex>,
+
+    /// Keep showing output as it is written.
+    #[clap(short = 'f', long = "follow")]
+    pub follow: bool,
+
+    /// The log directory the application was run with, if not the default.
+    #[clap(short = 'L', long = "log-dir", env = "SPIN_LOG_DIR")]
+    pub log_dir: Option<PathBuf>,
+
+    /// The state directory the application was run with, if not the default.
+    #[clap(long = "state-dir")]
+    pub state_dir: Option<PathBuf>,
+
+    /// The runtime config file the application was run with, if any.
+    #[clap(long = "runtime-config-file", env = "RUNTIME_CONFIG_FILE")]
+    pub runtime_config_file: Option<PathBuf>,
+}
+
+impl LogsCommand {
+    pub async fn run(self) -> Result<()> {
+        let (log_dir, config) = self.resolve_log_dir()?;
+        if self.since.is_some() && config.format != ComponentLogFormat::Json {
+            bail!("--since needs timestamped logs: set `format = \"json\"` in the [component_logs] section of the runtime config file");
+        }
+
+        let mut reader = LogReader::new(log_dir, config.format, self.components.clone());
+        let since = self
+            .since
+            .and_then(|since| SystemTime::now().checked_sub(since))
+            .map(DateTime::<Utc>::from);
+        for line in reader.read_all()? {
+            let recent = match (since, line.timestamp) {
+                (Some(since), Some(timestamp)) => timestamp.with_timezone(&Utc) >= since,
+                _ => true,
+            };
+            if recent {
+                self.print(&line);
+            }
+        }
+
+        while self.follow {
+            tokio::time::sleep(FOLLOW_INTERVAL).await;
+            for line in reader.read_new()? {
+                self.print(&line);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves the log directory the same way `spin up` does.
+    fn resolve_log_dir(&self) -> Result<(PathBuf, ComponentLogsConfig)> {
+        let local_app_dir = match &self.app_source {
+            Some(app_source) => {
+                let (manifest_file, _) =
+                    spin_common::paths::find_manifest_file_path(Some(app_source))?;
+                manifest_file.parent().map(ToOwned::to_owned)
+            }
+            None => spin_common::paths::find_manifest_file_path(None::<&Path>)
+                .ok()
+                .and_then(|(manifest_file, _)| manifest_file.parent().map(ToOwned::to_owned)),
+        };
+
+        let toml = match &self.runtime_config_file {
+            Some(path) => {
+                let file = std::fs::read_to_string(path).with_context(|| {
+                    format!("Failed to read runtime config file {}", quoted_path(path))
+                })?;
+                toml::from_str(&file).with_context(|| {
+                    format!("Failed to parse runtime config file {}", quoted_path(path))
+                })?
+            }
+            None => toml::Table::new(),
+        };
+        let resolver = TomlResolver::new(
+            &toml,
+            local_app_dir,
+            user_provided_path(self.state_dir.as_deref()),
+            user_provided_path(self.log_dir.as_deref()),
+        );
+        let log_dir = resolver.log_dir()?.context(
+            "No log directory found: run this command from the application directory, or use --from or --log-dir",
+        )?;
+        if !log_dir.is_dir() && !self.follow {
+            bail!(
+                "No logs found in {}. Has the application been run?",
+                quoted_path(&log_dir)
+            );
+        }
+        Ok((log_dir, resolver.component_logs()?))
+    }
+
+    fn print(&self, line: &LogLine) {
+        if let Some(grep) = &self.grep {
+            if !grep.is_match(&line.message) {
+                return;
+            }
+        }
+        let LogLine {
+            component,
+            stream,
+            message,
+            ..
+        } = line;
+        match &line.timestamp {
+            Some(timestamp) => println!("{timestamp} [{component} {stream}] {message}"),
+            None => println!("[{component} {stream}] {message}"),
+        }
+    }
+}
+
+fn user_provided_path(path: Option<&Path>) -> UserProvidedPath {
+    match path {
+        Some(p) if p.as_os_str().is_empty() => UserProvidedPath::Unset,
+        Some(p) => UserProvidedPath::Provided(p.to_owned()),
+        None => UserProvidedPath::Default,
+    }
+}
+
+/// Parses durations like `30s`, `5m`, `2h` and `1d`.
+fn parse_since(s: &str) -> Result<Duration> {
+    let unit_start = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (count, unit) = s.split_at(unit_start);
+    let count: u64 = count
+        .parse()
+        .with_context(|| format!("Invalid duration '{s}': expected e.g. `30s`, `5m` or `2h`"))?;
+    let unit_secs = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => bail!("Invalid duration unit '{unit}': expected `s`, `m`, `h` or `d`"),
+    };
+    Ok(Duration::from_secs(count * unit_secs))
+}
+
+/// A line of component output.
+#[derive(Debug, PartialEq)]
+struct LogLine {
+    /// When the line was written, if the log format records it.
+    timestamp: Option<DateTime<FixedOffset>>,
+    component: String,
+    stream: String,
+    message: String,
+}
+
+/// Reads the component log files in a log directory.
+struct LogReader {
+    log_dir: PathBuf,
+    format: ComponentLogFormat,
+    /// The components to read logs for, or empty for all components.
+    components: Vec<String>,
+    files: Vec<LogFile>,
+}
+
+/// A component log file, and how much of it has been read.
+struct LogFile {
+    component: String,
+    stream: &'static str,
+    path: PathBuf,
+    /// The file being read. This follows the file by identity, rather than
+    /// by path, so that lines written to it before it was rotated are not
+    /// missed.
+    handle: Option<same_file::Handle>,
+    /// The offset after the last complete line which has been read.
+    offset: u64,
+}
+
+impl LogFile {
+    /// Reads the complete lines written since the last read. If the file has
+    /// been rotated, this finishes reading the rotated file, then reads the
+    /// new file at the log path.
+    fn read_new_lines(&mut self) -> Result<Vec<String>> {
+        let mut lines = vec![];
+        loop {
+            if self.handle.is_none() {
+                self.handle = open_log(&self.path)?;
+            }
+            let Some(handle) = self.handle.as_mut() else {
+                break;
+            };
+            if handle.as_file().metadata()?.len() < self.offset {
+                // The file has been truncated
+                self.offset = 0;
+            }
+            let (new_lines, offset) = read_lines(handle.as_file_mut(), &self.path, self.offset)?;
+            lines.extend(new_lines);
+            self.offset = offset;
+
+            match same_file::Handle::from_path(&self.path) {
+                Ok(current) if current != *handle => {
+                    // The file has been rotated
+                    self.handle = None;
+                    self.offset = 0;
+                }
+                _ => break,
+            }
+        }
+        Ok(lines)
+    }
+}
+
+impl LogReader {
+    fn new(log_dir: PathBuf, format: ComponentLogFormat, components: Vec<String>) -> Self {
+        Self {
+            log_dir,
+            format,
+            components,
+            files: vec![],
+        }
+    }
+
+    /// Reads all lines in the log files, including rotated files, ordered by
+    /// time where known.
+    fn read_all(&mut self) -> Result<Vec<LogLine>> {
+        self.find_new_files()?;
+        let mut lines = vec![];
+        for file in &mut self.files {
+            let rotated = (1..)
+                .map(|n| rotated_path(&file.path, n))
+                .take_while(|path| path.exists())
+                .collect::<Vec<_>>();
+            for path in rotated.iter().rev() {
+                let (rotated_lines, _) = read_lines_from(path, 0)?;
+                lines.extend(parse_lines(file, self.format, rotated_lines));
+            }
+            file.handle = None;
+            file.offset = 0;
+            let new_lines = file.read_new_lines()?;
+            lines.extend(parse_lines(file, self.format, new_lines));
+        }
+        sort_by_time(&mut lines);
+        Ok(lines)
+    }
+
+    /// Reads lines written since the last read, ordered by time where known.
+    fn read_new(&mut self) -> Result<Vec<LogLine>> {
+        self.find_new_files()?;
+        let mut lines = vec![];
+        for file in &mut self.files {
+            let new_lines = file.read_new_lines()?;
+            lines.extend(parse_lines(file, self.format, new_lines));
+        }
+        sort_by_time(&mut lines);
+        Ok(lines)
+    }
+
+    /// Adds any log files which have appeared in the log directory.
+    fn find_new_files(&mut self) -> Result<()> {
+        let entries = match std::fs::read_dir(&self.log_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!(
+                        "Failed to read log directory {}",
+                        quoted_path(&self.log_dir)
+                    )
+                })
+            }
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if self.files.iter().any(|file| file.path == path) {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            for stream in STREAMS {
+                let suffix = format!("_{stream}.{}", self.format.extension());
+                let Some(file_component) = file_name.strip_suffix(&suffix) else {
+                    continue;
+                };
+                let component = if self.components.is_empty() {
+                    Some(file_component.to_owned())
+                } else {
+                    self.components
+                        .iter()
+                        .find(|c| component_log_path(&self.log_dir, c, stream, self.format) == path)
+                        .cloned()
+                };
+                if let Some(component) = component {
+                    self.files.push(LogFile {
+                        component,
+                        stream,
+                        path: path.clone(),
+                        handle: None,
+                        offset: 0,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the complete lines in the file at `path` after `offset`, returning
+/// them and the offset after the last of them.
+fn read_lines_from(path: &Path, offset: u64) -> Result<(Vec<String>, u64)> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open log file {}", quoted_path(path)))?;
+    read_lines(&mut file, path, offset)
+}
+
+/// Reads the complete lines in `file`, which was opened from `path`, after
+/// `offset`, returning them and the offset after the last of them.
+fn read_lines(file: &mut std::fs::File, path: &Path, offset: u64) -> Result<(Vec<String>, u64)> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = vec![];
+    file.read_to_end(&mut buf)
+        .with_context(|| format!("Failed to read log file {}", quoted_path(path)))?;
+    let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+        return Ok((vec![], offset));
+    };
+    let lines = buf[..end]
+        .split(|&b| b == b'\n')
+        .map(|line| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect();
+    Ok((lines, offset + end as u64 + 1))
+}
+
+/// Opens the log file at `path` for reading, if it exists.
+fn open_log(path: &Path) -> Result<Option<same_file::Handle>> {
+    match std::fs::File::open(path) {
+        Ok(file) => Ok(Some(same_file::Handle::from_file(file)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to open log file {}", quoted_path(path))),
+    }
+}
+
+fn parse_lines(
+    file: &LogFile,
+    format: ComponentLogFormat,
+    lines: Vec<String>,
+) -> impl Iterator<Item = LogLine> + '_ {
+    lines.into_iter().map(move |line| {
+        // Lines which aren't records, e.g. from before the format was
+        // changed, are shown as they are
+        let record = match format {
+            ComponentLogFormat::Json => serde_json::from_str::<ComponentLogRecord>(&line).ok(),
+            ComponentLogFormat::Text => None,
+        };
+        match record {
+            Some(record) => LogLine {
+                timestamp: DateTime::parse_from_rfc3339(&record.timestamp).ok(),
+                component: file.component.clone(),
+                stream: record.stream,
+                message: record.message,
+            },
+            None => LogLine {
+                timestamp: None,
+                component: file.component.clone(),
+                stream: file.stream.to_owned(),
+                message: line,
+            },
+        }
+    })
+}
+
+/// Merges lines from different files by time. The sort is stable, so lines
+/// without a timestamp stay in the order they were read.
+fn sort_by_time(lines: &mut [LogLine]) {
+    if lines.iter().all(|line| line.timestamp.is_some()) {
+        lines.sort_by_key(|line| line.timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_since_durations() {
+        assert_eq!(parse_since("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_since("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_since("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_since("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_since("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_since("5 minutes").is_err());
+        assert!(parse_since("m").is_err());
+    }
+
+    fn record(timestamp: &str, component: &str, stream: &str, message: &str) -> String {
+        let record = ComponentLogRecord {
+            timestamp: timestamp.to_owned(),
+            component_id: component.to_owned(),
+            stream: stream.to_owned(),
+            trace_id: None,
+            message: message.to_owned(),
+        };
+        serde_json::to_string(&record).unwrap() + "\n"
+    }
+
+    #[test]
+    fn merges_json_logs_by_time() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let stdout = dir.path().join("hello_stdout.jsonl");
+        let stderr = dir.path().join("hello_stderr.jsonl");
+        std::fs::write(
+            rotated_path(&stdout, 1),
+            record("2024-01-01T00:00:00Z", "hello", "stdout", "first"),
+        )?;
+        std::fs::write(
+            &stdout,
+            record("2024-01-01T00:00:02Z", "hello", "stdout", "third"),
+        )?;
+        std::fs::write(
+            &stderr,
+            record("2024-01-01T00:00:01Z", "hello", "stderr", "second"),
+        )?;
+
+        let mut reader = LogReader::new(dir.path().into(), ComponentLogFormat::Json, vec![]);
+        let messages = reader
+            .read_all()?
+            .into_iter()
+            .map(|line| line.message)
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["first", "second", "third"]);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_new_complete_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let stdout = dir.path().join("hello_stdout.txt");
+        std::fs::write(&stdout, "old\n")?;
+
+        let mut reader = LogReader::new(
+            dir.path().into(),
+            ComponentLogFormat::Text,
+            vec!["hello".into()],
+        );
+        assert_eq!(reader.read_all()?.len(), 1);
+
+        std::fs::write(&stdout, "old\nnew\npartial")?;
+        let lines = reader.read_new()?;
+        assert_eq!(
+            lines,
+            [LogLine {
+                timestamp: None,
+                component: "hello".into(),
+                stream: "stdout".into(),
+                message: "new".into(),
+            }]
+        );
+
+        // Other components' logs are ignored
+        std::fs::write(dir.path().join("other_stdout.txt"), "other\n")?;
+        assert!(reader.read_new()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn follows_rotated_files() -> Result<()> {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir()?;
+        let stdout = dir.path().join("hello_stdout.txt");
+        std::fs::write(&stdout, "old\n")?;
+
+        let mut reader = LogReader::new(dir.path().into(), ComponentLogFormat::Text, vec![]);
+        assert_eq!(reader.read_all()?.len(), 1);
+
+        // Rotate to a new file which is already longer than the old one
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&stdout)?
+            .write_all(b"before rotation\n")?;
+        std::fs::rename(&stdout, rotated_path(&stdout, 1))?;
+        std::fs::write(&stdout, "after rotation, with a much longer line\n")?;
+
+        let messages = reader
+            .read_new()?
+            .into_iter()
+            .map(|line| line.message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            ["before rotation", "after rotation, with a much longer line"]
+        );
+        Ok(())
+    }
+}
@@ -76,6 +76,23 @@ pub struct TemplateNewCommandCore {
         takes_value = false
     )]
     pub allow_overwrite: bool,
+
+    /// Do not run any commands (such as installing dependencies) that the
+    /// template would run after generating files. Without this, Spin asks
+    /// before running each command.
+    #[clap(long = "skip-post-generate", takes_value = false)]
+    pub skip_post_generate: bool,
+
+    /// Run any commands that the template would run after generating files
+    /// without confirming. Without this, commands are skipped if Spin cannot
+    /// ask before running them, for example with `--accept-defaults` in a
+    /// script.
+    #[clap(
+        long = "allow-run-commands",
+        takes_value = false,
+        conflicts_with = "skip-post-generate"
+    )]
+    pub allow_run_commands: bool,
 }
 
 /// Scaffold a new application based on a template.
@@ -195,6 +212,8 @@ impl TemplateNewCommandCore {
             accept_defaults: self.accept_defaults,
             no_vcs: self.no_vcs,
             allow_overwrite: self.allow_overwrite,
+            skip_post_generate: self.skip_post_generate,
+            allow_run_commands: self.allow_run_commands,
         };
 
         let run = template.run(options);
@@ -6,9 +6,16 @@ use clap::{Parser, Subcommand};
 use semver::Version;
 use spin_plugins::{
     error::Error,
-    lookup::{fetch_plugins_repo, plugins_repo_url, PluginLookup},
+    lockfile::{PluginsLockfile, PLUGINS_LOCK_FILE_NAME},
+    lookup::PluginLookup,
     manager::{self, InstallAction, ManifestLocation, PluginManager},
     manifest::{PluginManifest, PluginPackage},
+    repos::{
+        validate_repository_name, PluginRepositories, PluginRepository, PluginRepositorySource,
+        DEFAULT_ADDITIONAL_REPOSITORY_PRIORITY,
+    },
+    signature::{SignatureVerification, TrustedPublishers},
+    PluginStore,
 };
 use std::path::{Path, PathBuf};
 use url::Url;
@@ -37,11 +44,32 @@ pub enum PluginCommands {
     /// Upgrade one or all plugins.
     Upgrade(Upgrade),
 
-    /// Fetch the latest Spin plugins from the spin-plugins repository.
+    /// Fetch the latest Spin plugins from the registered plugins repositories.
     Update,
 
     /// Print information about a plugin.
     Show(Show),
+
+    /// Check the signatures of installed plugins against trusted publishers.
+    Verify(Verify),
+
+    /// Manage the publishers trusted to sign plugin manifests.
+    #[clap(subcommand)]
+    Trust(TrustCommands),
+
+    /// Manage the repositories (indexes) in which plugins are looked up.
+    #[clap(subcommand)]
+    Repo(RepoCommands),
+
+    /// Pin the version of a plugin used by the current project.
+    ///
+    /// Pins are recorded in a spin-plugins.lock file. When a plugin is run
+    /// from within the project, the pinned version is used, and is installed
+    /// alongside any globally installed version if necessary.
+    Pin(Pin),
+
+    /// Remove a plugin version pin from the current project.
+    Unpin(Unpin),
 }
 
 impl PluginCommands {
@@ -54,6 +82,11 @@ impl PluginCommands {
             PluginCommands::Upgrade(cmd) => cmd.run().await,
             PluginCommands::Update => update().await,
             PluginCommands::Show(cmd) => cmd.run().await,
+            PluginCommands::Verify(cmd) => cmd.run().await,
+            PluginCommands::Trust(cmd) => cmd.run().await,
+            PluginCommands::Repo(cmd) => cmd.run().await,
+            PluginCommands::Pin(cmd) => cmd.run().await,
+            PluginCommands::Unpin(cmd) => cmd.run().await,
         }
     }
 }
@@ -293,7 +326,8 @@ impl Upgrade {
         // Getting only eligible plugins to upgrade
         for installed_plugin in installed_in_catalogue {
             let manager = PluginManager::try_default()?;
-            let manifest_location = ManifestLocation::PluginsRepository(PluginLookup::new(
+            let manifest_location = ManifestLocation::PluginsRepository(upgrade_lookup(
+                manager.store(),
                 &installed_plugin.name,
                 None,
             ));
@@ -353,7 +387,8 @@ impl Upgrade {
         // Upgrade plugins selected
         for (installed_plugin, manifest) in plugins_selected {
             let manager = PluginManager::try_default()?;
-            let manifest_location = ManifestLocation::PluginsRepository(PluginLookup::new(
+            let manifest_location = ManifestLocation::PluginsRepository(upgrade_lookup(
+                manager.store(),
                 &installed_plugin.name,
                 None,
             ));
@@ -385,7 +420,7 @@ impl Upgrade {
                 .ok_or_else(|| anyhow!("Cannot convert path {} stem to str", path.display()))?
                 .to_string();
             let manifest_location =
-                ManifestLocation::PluginsRepository(PluginLookup::new(&name, None));
+                ManifestLocation::PluginsRepository(upgrade_lookup(manager.store(), &name, None));
             let manifest = match manager
                 .get_manifest(
                     &manifest_location,
@@ -421,7 +456,8 @@ impl Upgrade {
         let manifest_location = match (self.local_manifest_src, self.remote_manifest_src) {
             (Some(path), None) => ManifestLocation::Local(path),
             (None, Some(url)) => ManifestLocation::Remote(url),
-            _ => ManifestLocation::PluginsRepository(PluginLookup::new(
+            _ => ManifestLocation::PluginsRepository(upgrade_lookup(
+                manager.store(),
                 self.name
                     .as_ref()
                     .context("plugin name is required for upgrades")?,
@@ -483,6 +519,316 @@ impl Show {
     }
 }
 
+/// Check the signatures of installed plugins.
+#[derive(Parser, Debug)]
+pub struct Verify {
+    /// Name of the Spin plugin to verify. If omitted, all installed plugins are verified.
+    pub name: Option<String>,
+}
+
+impl Verify {
+    pub async fn run(self) -> Result<()> {
+        let manager = PluginManager::try_default()?;
+        let manifests = match &self.name {
+            Some(name) => vec![manager.store().read_plugin_manifest(name)?],
+            None => manager.store().installed_manifests()?,
+        };
+
+        if manifests.is_empty() {
+            println!("No installed plugins to verify");
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        for manifest in manifests {
+            match manager.verify_signature(&manifest) {
+                Ok(SignatureVerification::Verified { publisher }) => println!(
+                    "{} {}: signed by trusted publisher '{publisher}'",
+                    manifest.name(),
+                    manifest.version()
+                ),
+                Ok(SignatureVerification::Unsigned) => {
+                    println!("{} {}: not signed", manifest.name(), manifest.version())
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!(
+                        "{} {}: verification failed: {e:#}",
+                        manifest.name(),
+                        manifest.version()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{failed} plugin(s) failed signature verification");
+        }
+        Ok(())
+    }
+}
+
+/// Manage the publishers trusted to sign plugin manifests.
+#[derive(Subcommand, Debug)]
+pub enum TrustCommands {
+    /// Trust a publisher's signing key.
+    Add(TrustAdd),
+
+    /// Stop trusting a publisher.
+    Remove(TrustRemove),
+
+    /// List trusted publishers.
+    List,
+}
+
+impl TrustCommands {
+    pub async fn run(self) -> Result<()> {
+        let manager = PluginManager::try_default()?;
+        let mut trusted = TrustedPublishers::load(manager.store())?;
+        match self {
+            Self::Add(cmd) => {
+                trusted.add(&cmd.publisher, &cmd.public_key)?;
+                trusted.save(manager.store())?;
+                println!("Publisher '{}' is now trusted", cmd.publisher);
+                println!("Plugin manifests must now be signed by a trusted publisher");
+            }
+            Self::Remove(cmd) => {
+                if trusted.remove(&cmd.publisher) {
+                    trusted.save(manager.store())?;
+                    println!("Publisher '{}' is no longer trusted", cmd.publisher);
+                } else {
+                    println!(
+                        "Publisher '{}' isn't trusted, so no changes were made",
+                        cmd.publisher
+                    );
+                }
+            }
+            Self::List => {
+                if trusted.publishers().is_empty() {
+                    println!("No trusted publishers");
+                }
+                for publisher in trusted.publishers() {
+                    println!("{} {}", publisher.name, publisher.public_key);
+                }
+                if trusted.signatures_required() {
+                    println!("Plugin manifests must be signed by a trusted publisher");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct TrustAdd {
+    /// Name of the publisher, as it appears in plugin manifest signatures.
+    pub publisher: String,
+
+    /// The publisher's base64-encoded Ed25519 public key.
+    pub public_key: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct TrustRemove {
+    /// Name of the publisher.
+    pub publisher: String,
+}
+
+/// Manage the repositories (indexes) in which plugins are looked up.
+#[derive(Subcommand, Debug)]
+pub enum RepoCommands {
+    /// Register a plugins repository.
+    Add(RepoAdd),
+
+    /// Unregister a plugins repository.
+    Remove(RepoRemove),
+
+    /// List plugins repositories in the order in which they are searched.
+    List,
+}
+
+impl RepoCommands {
+    pub async fn run(self) -> Result<()> {
+        let manager = PluginManager::try_default()?;
+        let plugins_dir = manager.store().get_plugins_directory();
+        let mut repositories = PluginRepositories::load(plugins_dir)?;
+        match self {
+            Self::Add(cmd) => {
+                let repository = PluginRepository {
+                    name: cmd.name.clone(),
+                    source: cmd.source()?,
+                    priority: cmd.priority,
+                };
+                repositories.add(repository.clone())?;
+                repository
+                    .fetch(plugins_dir, false)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to fetch plugins repository '{}'", cmd.name)
+                    })?;
+                repositories.save(plugins_dir)?;
+                println!("Plugins repository '{}' added", cmd.name);
+            }
+            Self::Remove(cmd) => {
+                let Some(repository) = repositories.get(&cmd.name) else {
+                    println!(
+                        "No plugins repository named '{}', so no changes were made",
+                        cmd.name
+                    );
+                    return Ok(());
+                };
+                repositories.remove(&cmd.name)?;
+                repositories.save(plugins_dir)?;
+                if let PluginRepositorySource::Git { .. } = &repository.source {
+                    // This is only our clone: it's fine if we can't clean it up.
+                    if let Ok(root) = repository.root(plugins_dir) {
+                        _ = std::fs::remove_dir_all(root);
+                    }
+                }
+                println!("Plugins repository '{}' removed", cmd.name);
+            }
+            Self::List => {
+                for repository in repositories.in_priority_order() {
+                    println!(
+                        "{} {} (priority {})",
+                        repository.name, repository.source, repository.priority
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct RepoAdd {
+    /// Name by which to refer to the repository.
+    #[clap(value_parser = parse_repository_name)]
+    pub name: String,
+
+    /// URL of a git repository, or path to a local directory, containing
+    /// plugin manifests in the same layout as the spin-plugins repository.
+    pub source: String,
+
+    /// Repositories with higher priority are searched first. The spin-plugins
+    /// repository has priority 0.
+    #[clap(long = "priority", default_value_t = DEFAULT_ADDITIONAL_REPOSITORY_PRIORITY, allow_hyphen_values = true)]
+    pub priority: i32,
+}
+
+impl RepoAdd {
+    fn source(&self) -> Result<PluginRepositorySource> {
+        let path = PathBuf::from(&self.source);
+        if path.is_dir() {
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("Invalid repository directory {}", path.display()))?;
+            return Ok(PluginRepositorySource::Local { path });
+        }
+        let url = Url::parse(&self.source).with_context(|| {
+            format!(
+                "'{}' is neither a directory nor a git repository URL",
+                self.source
+            )
+        })?;
+        Ok(PluginRepositorySource::Git { url })
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct RepoRemove {
+    /// Name of the repository.
+    #[clap(value_parser = parse_repository_name)]
+    pub name: String,
+}
+
+fn parse_repository_name(name: &str) -> Result<String, String> {
+    validate_repository_name(name)
+        .map(|()| name.to_owned())
+        .map_err(|e| e.to_string())
+}
+
+/// Pin the version of a plugin used by the current project.
+#[derive(Parser, Debug)]
+pub struct Pin {
+    /// Name of the Spin plugin.
+    pub name: String,
+
+    /// Version to pin. If omitted, the installed version is pinned, or the
+    /// latest compatible version if the plugin is not installed.
+    #[clap(long = "version", short = 'v')]
+    pub version: Option<Version>,
+}
+
+impl Pin {
+    pub async fn run(self) -> Result<()> {
+        let manager = PluginManager::try_default()?;
+        let installed = manager.store().read_plugin_manifest(&self.name).ok();
+        let manifest = match (self.version, installed) {
+            (None, Some(installed)) => installed,
+            (version, _) => {
+                let location =
+                    ManifestLocation::PluginsRepository(PluginLookup::new(&self.name, version));
+                manager
+                    .get_manifest(&location, false, SPIN_VERSION, &None)
+                    .await?
+            }
+        };
+
+        let lockfile_path = project_lockfile_path()?;
+        let mut lockfile = if lockfile_path.exists() {
+            PluginsLockfile::load(&lockfile_path)?
+        } else {
+            PluginsLockfile::default()
+        };
+        let pin = lockfile.pin(&manifest);
+        println!("Pinned plugin '{}' to version {}", pin.name, pin.version);
+        lockfile.save(&lockfile_path)?;
+        Ok(())
+    }
+}
+
+/// Remove a plugin version pin from the current project.
+#[derive(Parser, Debug)]
+pub struct Unpin {
+    /// Name of the Spin plugin.
+    pub name: String,
+}
+
+impl Unpin {
+    pub async fn run(self) -> Result<()> {
+        let lockfile_path = project_lockfile_path()?;
+        if !lockfile_path.exists() {
+            println!("No {PLUGINS_LOCK_FILE_NAME} found, so no changes were made");
+            return Ok(());
+        }
+        let mut lockfile = PluginsLockfile::load(&lockfile_path)?;
+        if lockfile.unpin(&self.name) {
+            lockfile.save(&lockfile_path)?;
+            println!("Plugin '{}' is no longer pinned", self.name);
+        } else {
+            println!(
+                "Plugin '{}' isn't pinned, so no changes were made",
+                self.name
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The lockfile for the project containing the current directory, or a new
+/// lockfile in the current directory if there isn't one.
+fn project_lockfile_path() -> Result<PathBuf> {
+    let cwd = std::env::current_dir()?;
+    Ok(PluginsLockfile::find(&cwd).unwrap_or_else(|| cwd.join(PLUGINS_LOCK_FILE_NAME)))
+}
+
+/// Looks up upgrades for an installed plugin in the repository it was
+/// installed from, or in all repositories if that is not known.
+fn upgrade_lookup(store: &PluginStore, name: &str, version: Option<Version>) -> PluginLookup {
+    PluginLookup::new(name, version).in_repository(store.installed_from_repository(name))
+}
+
 fn is_potential_upgrade(current: &PluginManifest, candidate: &PluginManifest) -> bool {
     match (current.try_version(), candidate.try_version()) {
         (Ok(cur_ver), Ok(cand_ver)) => cand_ver > cur_ver,
@@ -764,7 +1110,7 @@ fn merge_plugin_lists(a: Vec<PluginDescriptor>, b: Vec<PluginDescriptor>) -> Vec
     result
 }
 
-/// Updates the locally cached spin-plugins repository, fetching the latest plugins.
+/// Updates the locally cached plugins repositories, fetching the latest plugins.
 pub(crate) async fn update() -> Result<()> {
     update_silent().await?;
     println!("Plugin information updated successfully");
@@ -781,9 +1127,23 @@ pub(crate) async fn update_silent() -> Result<()> {
     }
 
     let plugins_dir = manager.store().get_plugins_directory();
-    let url = plugins_repo_url()?;
-    fetch_plugins_repo(&url, plugins_dir, true).await?;
-    Ok(())
+    let mut first_error = None;
+    for repository in PluginRepositories::load(plugins_dir)?.in_priority_order() {
+        if let Err(e) = repository.fetch(plugins_dir, true).await {
+            tracing::warn!(
+                "Failed to update plugins repository '{}': {e:#}",
+                repository.name
+            );
+            first_error.get_or_insert(e.context(format!(
+                "Failed to update plugins repository '{}'",
+                repository.name
+            )));
+        }
+    }
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
 }
 
 fn continue_to_install(
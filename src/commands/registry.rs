@@ -6,6 +6,9 @@ use spin_common::arg_parser::parse_kv;
 use spin_oci::{client::InferPredefinedAnnotations, Client};
 use std::{io::Read, path::PathBuf, time::Duration};
 
+/// The variant name given to the `--from` application when pushing variants.
+const DEFAULT_VARIANT_NAME: &str = "default";
+
 /// Commands for working with OCI registries to distribute applications.
 #[derive(Subcommand, Debug)]
 pub enum RegistryCommands {
@@ -13,6 +16,8 @@ pub enum RegistryCommands {
     Push(Push),
     /// Pull a Spin application from a registry.
     Pull(Pull),
+    /// Save a Spin application from a registry to a file in the OCI image layout format.
+    Save(Save),
     /// Log in to a registry.
     Login(Login),
 }
@@ -22,6 +27,7 @@ impl RegistryCommands {
         match self {
             RegistryCommands::Push(cmd) => cmd.run().await,
             RegistryCommands::Pull(cmd) => cmd.run().await,
+            RegistryCommands::Save(cmd) => cmd.run().await,
             RegistryCommands::Login(cmd) => cmd.run().await,
         }
     }
@@ -66,6 +72,15 @@ pub struct Push {
     /// Any existing value will be overwritten. Can be used multiple times.
     #[clap(long = "annotation", parse(try_from_str = parse_kv))]
     pub annotations: Vec<(String, String)>,
+
+    /// Push an additional variant of the application (in name=manifest format),
+    /// such as a build for a different target environment. If any variants are
+    /// given, the application is pushed as an image index grouping the variants,
+    /// and hosts pulling it use the first variant whose host requirements they
+    /// support. The application specified with `--from` is added as the last
+    /// variant, with the name "default". Can be used multiple times.
+    #[clap(long = "variant", parse(try_from_str = parse_kv))]
+    pub variants: Vec<(String, String)>,
 }
 
 impl Push {
@@ -74,8 +89,21 @@ impl Push {
             spin_common::paths::find_manifest_file_path(self.app_source.as_ref())?;
         notify_if_nondefault_rel(&app_file, distance);
 
+        let mut variants = Vec::with_capacity(self.variants.len());
+        for (name, path) in &self.variants {
+            let (variant_file, _) = spin_common::paths::find_manifest_file_path(Some(path))
+                .with_context(|| format!("cannot find manifest for variant {name:?}"))?;
+            variants.push((name.clone(), variant_file));
+        }
+        if !variants.is_empty() {
+            variants.push((DEFAULT_VARIANT_NAME.to_owned(), app_file.clone()));
+        }
+
         if self.build {
-            spin_build::build(&app_file, &[]).await?;
+            spin_build::build(&app_file, &[], &Default::default()).await?;
+            for (_, variant_file) in variants.iter().filter(|(_, f)| f != &app_file) {
+                spin_build::build(variant_file, &[], &Default::default()).await?;
+            }
         }
 
         let annotations = if self.annotations.is_empty() {
@@ -88,14 +116,25 @@ impl Push {
 
         let _spinner = create_dotted_spinner(2000, "Pushing app to the Registry".to_owned());
 
-        let digest = client
-            .push(
-                &app_file,
-                &self.reference,
-                annotations,
-                InferPredefinedAnnotations::All,
-            )
-            .await?;
+        let digest = if variants.is_empty() {
+            client
+                .push(
+                    &app_file,
+                    &self.reference,
+                    annotations,
+                    InferPredefinedAnnotations::All,
+                )
+                .await?
+        } else {
+            client
+                .push_variants(
+                    &variants,
+                    &self.reference,
+                    annotations,
+                    InferPredefinedAnnotations::All,
+                )
+                .await?
+        };
         match digest {
             Some(digest) => println!("Pushed with digest {digest}"),
             None => println!("Pushed; the registry did not return the digest"),
@@ -139,6 +178,48 @@ impl Pull {
     }
 }
 
+#[derive(Parser, Debug)]
+pub struct Save {
+    /// Ignore server certificate errors
+    #[clap(
+        name = INSECURE_OPT,
+        short = 'k',
+        long = "insecure",
+        takes_value = false,
+    )]
+    pub insecure: bool,
+
+    /// Reference in the registry of the published Spin application.
+    /// This is a string whose format is defined by the registry standard, and generally consists of <registry>/<username>/<application-name>:<version>. E.g. ghcr.io/ogghead/spin-test-app:0.1.0
+    #[clap()]
+    pub reference: String,
+
+    /// The file to save the application to. The application is saved as a tar
+    /// archive of an OCI image layout, which can be run with `spin up --from-oci-layout`.
+    #[clap(short = 'o', long = "output")]
+    pub output: PathBuf,
+
+    /// Cache directory for downloaded registry data.
+    #[clap(long)]
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Save {
+    /// Save a Spin application from an OCI registry to an OCI image layout
+    pub async fn run(self) -> Result<()> {
+        let mut client = spin_oci::Client::new(self.insecure, self.cache_dir.clone()).await?;
+
+        let _spinner = create_dotted_spinner(2000, "Saving app from the Registry".to_owned());
+
+        client.save(&self.reference, &self.output).await?;
+        println!(
+            "Successfully saved the app to {}",
+            spin_common::ui::quoted_path(&self.output)
+        );
+        Ok(())
+    }
+}
+
 #[derive(Parser, Debug)]
 pub struct Login {
     /// Username for the registry
@@ -8,13 +8,15 @@ use path_absolutize::Absolutize;
 use serde::Serialize;
 use spin_templates::{
     InstallOptions, InstallationResults, InstalledTemplateWarning, ListResults, ProgressReporter,
-    SkippedReason, Template, TemplateManager, TemplateSource,
+    SkippedReason, Template, TemplateManager, TemplateSource, TemplateTestCase, TemplateTests,
 };
 
 use crate::build_info::*;
 
 const INSTALL_FROM_DIR_OPT: &str = "FROM_DIR";
 const INSTALL_FROM_GIT_OPT: &str = "FROM_GIT";
+const INSTALL_FROM_TAR_OPT: &str = "FROM_TAR";
+const INSTALL_FROM_OCI_OPT: &str = "FROM_OCI";
 const UPGRADE_ONLY: &str = "GIT_URL";
 
 const DEFAULT_TEMPLATES_INSTALL_PROMPT: &str =
@@ -24,7 +26,8 @@ const DEFAULT_TEMPLATE_REPO: &str = "https://github.com/fermyon/spin";
 /// Commands for working with WebAssembly component templates.
 #[derive(Subcommand, Debug)]
 pub enum TemplateCommands {
-    /// Install templates from a Git repository or local directory.
+    /// Install templates from a Git repository, local directory, tarball or
+    /// OCI registry.
     ///
     /// The files of the templates are copied to the local template store: a
     /// directory in your data or home directory.
@@ -41,6 +44,12 @@ pub enum TemplateCommands {
 
     /// List the installed templates.
     List(List),
+
+    /// Test templates against snapshots of their expected output.
+    ///
+    /// Tests are declared in a spin-template-tests.toml file in the template
+    /// source directory, and snapshots are kept in its `snapshots` directory.
+    Test(Test),
 }
 
 impl TemplateCommands {
@@ -50,11 +59,13 @@ impl TemplateCommands {
             TemplateCommands::Upgrade(cmd) => cmd.run().await,
             TemplateCommands::Uninstall(cmd) => cmd.run().await,
             TemplateCommands::List(cmd) => cmd.run().await,
+            TemplateCommands::Test(cmd) => cmd.run().await,
         }
     }
 }
 
-/// Install templates from a Git repository or local directory.
+/// Install templates from a Git repository, local directory, tarball or
+/// OCI registry.
 #[derive(Parser, Debug)]
 pub struct Install {
     /// The URL of the templates git repository.
@@ -63,7 +74,7 @@ pub struct Install {
         name = INSTALL_FROM_GIT_OPT,
         long = "git",
         alias = "repo",
-        conflicts_with = INSTALL_FROM_DIR_OPT,
+        conflicts_with_all = &[INSTALL_FROM_DIR_OPT, INSTALL_FROM_TAR_OPT, INSTALL_FROM_OCI_OPT],
     )]
     pub git: Option<String>,
 
@@ -75,10 +86,36 @@ pub struct Install {
     #[clap(
         name = INSTALL_FROM_DIR_OPT,
         long = "dir",
-        conflicts_with = INSTALL_FROM_GIT_OPT,
+        conflicts_with_all = &[INSTALL_FROM_GIT_OPT, INSTALL_FROM_TAR_OPT, INSTALL_FROM_OCI_OPT],
     )]
     pub dir: Option<PathBuf>,
 
+    /// The URL of a gzipped tarball containing the template(s) to install.
+    /// The templates must be in a "templates" directory at the root of the
+    /// tarball or of its single top-level directory.
+    #[clap(
+        name = INSTALL_FROM_TAR_OPT,
+        long = "tar",
+        conflicts_with_all = &[INSTALL_FROM_GIT_OPT, INSTALL_FROM_DIR_OPT, INSTALL_FROM_OCI_OPT],
+    )]
+    pub tar: Option<String>,
+
+    /// The expected SHA-256 digest of the tarball. If present, installation
+    /// fails if the downloaded tarball does not match, and so does
+    /// `spin templates upgrade` if the tarball has since changed. Otherwise,
+    /// upgrading fetches the tarball again and records its new digest.
+    #[clap(long = "digest", requires = INSTALL_FROM_TAR_OPT)]
+    pub digest: Option<String>,
+
+    /// An OCI registry reference to an artifact containing the template(s)
+    /// to install. Uses the credentials from `spin registry login` if present.
+    #[clap(
+        name = INSTALL_FROM_OCI_OPT,
+        long = "oci",
+        conflicts_with_all = &[INSTALL_FROM_GIT_OPT, INSTALL_FROM_DIR_OPT, INSTALL_FROM_TAR_OPT],
+    )]
+    pub oci: Option<String>,
+
     /// If present, updates existing templates instead of skipping.
     #[clap(long = "upgrade", alias = "update")]
     pub update: bool,
@@ -119,16 +156,20 @@ impl Install {
     pub async fn run(self) -> Result<()> {
         let template_manager = TemplateManager::try_default()
             .context("Failed to construct template directory path")?;
-        let source = match (&self.git, &self.dir) {
-            (Some(git), None) => {
+        let source = match (&self.git, &self.dir, &self.tar, &self.oci) {
+            (Some(git), None, None, None) => {
                 let git_url = infer_github(git);
                 TemplateSource::try_from_git(git_url, &self.branch, SPIN_VERSION)?
             }
-            (None, Some(dir)) => {
+            (None, Some(dir), None, None) => {
                 let abs_dir = dir.absolutize().map(|d| d.to_path_buf());
                 TemplateSource::File(abs_dir.unwrap_or_else(|_| dir.clone()))
             }
-            _ => anyhow::bail!("Exactly one of `git` and `dir` sources must be specified"),
+            (None, None, Some(tar), None) => TemplateSource::try_from_tarball(tar, &self.digest)?,
+            (None, None, None, Some(oci)) => TemplateSource::Oci(oci.clone()),
+            _ => anyhow::bail!(
+                "Exactly one of `git`, `dir`, `tar` and `oci` sources must be specified"
+            ),
         };
 
         let reporter = ConsoleProgressReporter;
@@ -204,6 +245,9 @@ impl Upgrade {
                 git: self.git.clone(),
                 branch: self.branch.clone(),
                 dir: None,
+                tar: None,
+                digest: None,
+                oci: None,
                 update: true,
             };
 
@@ -247,24 +291,35 @@ impl Upgrade {
             .iter()
             .partition(|t| t.source_repo().is_some());
 
-        let mut repos = origin
-            .iter()
-            .filter_map(|t| t.source_repo())
-            .collect::<HashSet<_>>();
+        let mut repos = HashSet::new();
+        let mut sources = vec![];
+        for template in &origin {
+            let (Some(repo), Some(template_source)) = (
+                template.source_repo(),
+                template.upgrade_source(SPIN_VERSION),
+            ) else {
+                continue;
+            };
+            if repos.insert(repo) {
+                sources.push(RepoSelection::from_source(repo, template_source).await);
+            }
+        }
 
         // Try to detect two repos that are likely to have been installed before
         // we started recording upgrade info.
         let has_unorigined_default_templates = no_origin.iter().any(|t| t.id() == "http-rust");
         let has_unorigined_js_templates = no_origin.iter().any(|t| t.id() == "http-js");
+        let mut unorigined_repos = vec![];
         if has_unorigined_default_templates {
-            repos.insert("https://github.com/fermyon/spin");
+            unorigined_repos.push("https://github.com/fermyon/spin");
         }
         if has_unorigined_js_templates {
-            repos.insert("https://github.com/fermyon/spin-js-sdk");
+            unorigined_repos.push("https://github.com/fermyon/spin-js-sdk");
         }
-
-        let mut sources = vec![];
-        for repo in repos {
+        for repo in unorigined_repos {
+            if !repos.insert(repo) {
+                continue;
+            }
             if let Some(source) = RepoSelection::from_repo(repo).await {
                 sources.push(source);
             }
@@ -382,12 +437,16 @@ struct RepoSelection {
 impl RepoSelection {
     async fn from_repo(repo: &str) -> Option<Self> {
         let template_source = TemplateSource::try_from_git(repo, &None, SPIN_VERSION).ok()?;
+        Some(Self::from_source(repo, template_source).await)
+    }
+
+    async fn from_source(repo: &str, template_source: TemplateSource) -> Self {
         let resolved_tag = template_source.resolved_tag().await;
-        Some(Self {
+        Self {
             repo: repo.to_owned(),
             template_source,
             resolved_tag,
-        })
+        }
     }
 }
 
@@ -454,6 +513,100 @@ impl Uninstall {
     }
 }
 
+/// Test templates against snapshots of their expected output.
+#[derive(Parser, Debug)]
+pub struct Test {
+    /// The template source directory, containing a "templates" directory
+    /// and a spin-template-tests.toml file.
+    #[clap(default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Run only the named test. Can be specified more than once.
+    #[clap(long = "test", multiple_occurrences = true)]
+    pub tests: Vec<String>,
+
+    /// Replace the snapshots with the generated output instead of comparing.
+    #[clap(long = "update-snapshots", takes_value = false)]
+    pub update_snapshots: bool,
+
+    /// Build the generated application for every test, not only those that
+    /// request it.
+    #[clap(long = "build", takes_value = false)]
+    pub build: bool,
+}
+
+impl Test {
+    pub async fn run(self) -> Result<()> {
+        let tests = TemplateTests::load(&self.dir)?;
+        let cases = tests
+            .cases()
+            .iter()
+            .filter(|c| self.tests.is_empty() || self.tests.contains(&c.name))
+            .collect::<Vec<_>>();
+        if cases.is_empty() {
+            anyhow::bail!("No template tests to run");
+        }
+
+        let mut failed = vec![];
+        for case in cases {
+            terminal::step!("Testing", "{} (template {})", case.name, case.template);
+            if let Err(e) = self.run_case(&tests, case).await {
+                terminal::error!("{} failed: {e:#}", case.name);
+                failed.push(case.name.clone());
+            }
+        }
+
+        if failed.is_empty() {
+            terminal::step!("Finished", "all template tests passed");
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "{} template test(s) failed: {}",
+                failed.len(),
+                failed.join(", ")
+            )
+        }
+    }
+
+    async fn run_case(&self, tests: &TemplateTests, case: &TemplateTestCase) -> Result<()> {
+        let rendered = tests.render(case).await?;
+
+        if self.update_snapshots {
+            let verb = if rendered.has_snapshot() {
+                "Updated"
+            } else {
+                "Created"
+            };
+            rendered.update_snapshot()?;
+            println!("{verb} snapshot for {}", case.name);
+        } else if !rendered.has_snapshot() {
+            anyhow::bail!("no snapshot for {}; run with --update-snapshots", case.name);
+        } else {
+            let differences = rendered.compare()?;
+            if !differences.is_empty() {
+                for difference in &differences {
+                    println!("{difference}");
+                }
+                anyhow::bail!(
+                    "output differs from the snapshot in {} file(s)",
+                    differences.len()
+                );
+            }
+        }
+
+        if self.build || case.build {
+            let manifest_file = rendered
+                .app_dir()
+                .join(spin_common::paths::DEFAULT_MANIFEST_FILE);
+            spin_build::build(&manifest_file, &[], &Default::default())
+                .await
+                .context("generated application failed to build")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// List the installed templates.
 #[derive(Parser, Debug)]
 pub struct List {
@@ -620,6 +773,9 @@ async fn install_default_templates() -> anyhow::Result<()> {
         git: Some(DEFAULT_TEMPLATE_REPO.to_owned()),
         branch: None,
         dir: None,
+        tar: None,
+        digest: None,
+        oci: None,
         update: false,
     };
     install_cmd
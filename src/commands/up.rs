@@ -4,8 +4,13 @@ use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
     fmt::Debug,
+    net::SocketAddr,
     path::{Path, PathBuf},
     process::Stdio,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
@@ -16,8 +21,13 @@ use spin_common::ui::quoted_path;
 use spin_factor_outbound_networking::validate_service_chaining_for_components;
 use spin_loader::FilesMountStrategy;
 use spin_oci::OciLoader;
-use spin_trigger::cli::{LaunchMetadata, SPIN_LOCAL_APP_DIR, SPIN_LOCKED_URL, SPIN_WORKING_DIR};
+use spin_telemetry::prometheus::SPIN_PROMETHEUS_LISTEN_ADDR;
+use spin_trigger::cli::{
+    LaunchMetadata, SPIN_JSON_OUTPUT, SPIN_LOCAL_APP_DIR, SPIN_LOCKED_URL, SPIN_RELOAD_ON_STDIN,
+    SPIN_WORKING_DIR,
+};
 use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 
 use crate::{directory_rels::notify_if_nondefault_rel, opts::*};
 
@@ -79,6 +89,15 @@ pub struct UpCommand {
     )]
     pub registry_source: Option<String>,
 
+    /// The application to run, from an OCI image layout directory or tar file
+    /// such as those created by `spin registry save`.
+    #[clap(
+        name = FROM_OCI_LAYOUT_OPT,
+        long = "from-oci-layout",
+        group = "source",
+    )]
+    pub oci_layout_source: Option<PathBuf>,
+
     /// Ignore server certificate errors from a registry
     #[clap(
         name = INSECURE_OPT,
@@ -114,6 +133,26 @@ pub struct UpCommand {
     #[clap(long, takes_value = false, env = ALWAYS_BUILD_ENV)]
     pub build: bool,
 
+    /// For local apps, the build profile to use. Components with this profile
+    /// are run from the source the profile builds, and are built with the
+    /// profile's commands if `--build` is also specified.
+    #[clap(long = "profile")]
+    pub profile: Option<String>,
+
+    /// The format in which to report startup information. With `json`, build
+    /// steps (if `--build` is specified) and the addresses and routes the
+    /// application is served on are reported as lines of JSON on stdout.
+    /// All other output, including component output, is written to stderr.
+    #[clap(value_enum, long = "output", default_value = "text")]
+    pub output: OutputFormat,
+
+    /// Reload the application each time a line is read from stdin, switching
+    /// the running triggers to it without restarting them. If the triggers
+    /// can't reload in place, exit instead so that the caller can restart
+    /// `spin up`. Used by `spin watch`.
+    #[clap(long = "reload-on-stdin", hide = true, takes_value = false)]
+    pub reload_on_stdin: bool,
+
     /// [Experimental] Component ID to run. This can be specified multiple times. The default is all components.
     #[clap(short = 'c', long = "component-id")]
     pub components: Vec<String>,
@@ -191,55 +230,48 @@ impl UpCommand {
         }
 
         if self.build {
-            app_source.build().await?;
-        }
-        let mut locked_app = self
-            .load_resolved_app_source(resolved_app_source, &working_dir)
-            .await
-            .context("Failed to load application")?;
-
-        if !self.components.is_empty() {
-            locked_app = spin_app::retain_components(
-                locked_app,
-                &self
-                    .components
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<&str>>(),
-                &[&validate_service_chaining_for_components],
-            )
-            .context(
-                "failed to resolve application with only components selected with --component",
-            )?;
+            let build_options = spin_build::BuildOptions {
+                profile: self.profile.clone(),
+                json_output: self.output == OutputFormat::Json,
+                ..Default::default()
+            };
+            app_source.build(&build_options).await?;
         }
+        let locked_app = self
+            .load_locked_app(resolved_app_source, &working_dir)
+            .await?;
 
-        let trigger_types: HashSet<&str> = locked_app
-            .triggers
-            .iter()
-            .map(|t| t.trigger_type.as_ref())
-            .collect();
+        let trigger_types = trigger_types(&locked_app);
 
         ensure!(!trigger_types.is_empty(), "No triggers in app");
 
-        let trigger_cmds = trigger_commands_for_trigger_types(trigger_types.into_iter().collect())
-            .with_context(|| format!("Couldn't find trigger executor for {app_source}"))?;
+        let trigger_cmds =
+            trigger_commands_for_trigger_types(trigger_types.iter().map(|t| t.as_str()).collect())
+                .with_context(|| format!("Couldn't find trigger executor for {app_source}"))?;
         let is_multi = trigger_cmds.len() > 1;
 
-        self.update_locked_app(&mut locked_app);
         let locked_url = self.write_locked_app(&locked_app, &working_dir).await?;
 
         let local_app_dir = app_source.local_app_dir().map(Into::into);
 
         let run_opts = RunTriggerOpts {
             locked_url,
-            working_dir,
+            working_dir: working_dir.clone(),
             local_app_dir,
+            reload_on_stdin: false,
+            prometheus_listen_addr: None,
         };
 
-        let trigger_processes = self.start_trigger_processes(trigger_cmds, run_opts).await?;
+        let (mut trigger_processes, reload_in_place) =
+            self.start_trigger_processes(trigger_cmds, run_opts).await?;
         let pids = get_pids(&trigger_processes);
 
-        set_kill_on_ctrl_c(&pids)?;
+        let shutdown_requested = set_kill_on_ctrl_c(&pids)?;
+
+        let trigger_stdins = trigger_processes
+            .iter_mut()
+            .filter_map(|ch| ch.stdin.take())
+            .collect::<Vec<_>>();
 
         let trigger_tasks = trigger_processes
             .into_iter()
@@ -250,7 +282,29 @@ impl UpCommand {
             tokio::time::sleep(MULTI_TRIGGER_LET_ALL_START).await;
         }
 
-        let (first_to_finish, _index, _rest) = futures::future::select_all(trigger_tasks).await;
+        let mut all_triggers = futures::future::select_all(trigger_tasks);
+        let (first_to_finish, other_triggers) = if self.reload_on_stdin {
+            tokio::select! {
+                (first_to_finish, _index, rest) = &mut all_triggers => (first_to_finish, rest),
+                res = self.reload_when_requested(
+                    &app_source,
+                    &working_dir,
+                    &trigger_types,
+                    reload_in_place.then_some(trigger_stdins),
+                ) => {
+                    // The reloaded app can't be run by the running triggers.
+                    // Ask them to shut down and wait for them to do so (each
+                    // is bounded by its shutdown timeout) rather than killing
+                    // them on drop.
+                    kill_child_processes(&pids);
+                    futures::future::join_all(all_triggers.into_inner()).await;
+                    return res;
+                }
+            }
+        } else {
+            let (first_to_finish, _index, rest) = all_triggers.await;
+            (first_to_finish, rest)
+        };
 
         if let Ok(process_result) = first_to_finish {
             let status = process_result?;
@@ -263,6 +317,20 @@ impl UpCommand {
             }
         }
 
+        // A trigger can exit cleanly without having been asked to shut down
+        // (e.g. because it has nothing left to do). The app is no longer
+        // fully running, so ask the other triggers to shut down too.
+        if !shutdown_requested.load(Ordering::SeqCst) {
+            if is_multi {
+                println!("A trigger exited. Terminating.");
+            }
+            kill_child_processes(&pids);
+        }
+
+        // Let the other triggers finish shutting down (each is bounded by its
+        // shutdown timeout) rather than killing them on drop.
+        futures::future::join_all(other_triggers).await;
+
         Ok(())
     }
 
@@ -301,15 +369,17 @@ impl UpCommand {
         Ok(metas)
     }
 
+    /// Starts the trigger processes. Also returns whether they can all reload
+    /// the app in place, if `--reload-on-stdin` was specified.
     async fn start_trigger_processes(
-        self,
+        &self,
         trigger_cmds: Vec<Vec<String>>,
-        run_opts: RunTriggerOpts,
-    ) -> anyhow::Result<Vec<tokio::process::Child>> {
+        mut run_opts: RunTriggerOpts,
+    ) -> anyhow::Result<(Vec<tokio::process::Child>, bool)> {
         let is_multi = trigger_cmds.len() > 1;
 
         let trigger_args = self.group_trigger_args();
-        let trigger_metas = if is_multi {
+        let trigger_metas = if is_multi || self.reload_on_stdin {
             match self.get_trigger_launch_metas(&trigger_cmds).await {
                 Ok(m) => Some(m),
                 Err(e) => {
@@ -321,6 +391,15 @@ impl UpCommand {
             None
         };
 
+        // Triggers which can't reload in place, including plugin triggers
+        // which predate reloading, are restarted instead
+        run_opts.reload_on_stdin = self.reload_on_stdin
+            && trigger_metas
+                .as_ref()
+                .is_some_and(|ms| ms.values().all(LaunchMetadata::supports_reload));
+
+        // Only multiple triggers need their options distributing
+        let trigger_metas = trigger_metas.filter(|_| is_multi);
         if let Some(trigger_metas) = trigger_metas.as_ref() {
             for group in &trigger_args {
                 let is_accepted = trigger_metas.values().any(|m| m.is_group_match(group));
@@ -330,14 +409,29 @@ impl UpCommand {
             }
         }
 
+        let prometheus_listen_addr = spin_telemetry::prometheus::listen_addr()?;
+
         let mut trigger_processes = Vec::with_capacity(trigger_cmds.len());
 
-        for cmd in trigger_cmds {
+        for (index, cmd) in trigger_cmds.into_iter().enumerate() {
             let meta = trigger_metas.as_ref().and_then(|ms| ms.get(&cmd));
             let trigger_args = match meta {
                 Some(m) => m.matches(&trigger_args),
                 None => self.trigger_args.iter().collect(),
             };
+            // Each trigger process records and serves its own metrics, so
+            // each needs its own address to serve them on
+            run_opts.prometheus_listen_addr = prometheus_listen_addr
+                .map(|addr| trigger_prometheus_listen_addr(addr, index))
+                .transpose()?;
+            if let Some(addr) = run_opts.prometheus_listen_addr.filter(|_| is_multi) {
+                let trigger = cmd.last().map(Path::new).and_then(Path::file_stem);
+                terminal::einfo!(
+                    "Serving metrics:",
+                    "http://{addr}/metrics for {} triggers",
+                    trigger.unwrap_or_default().to_string_lossy()
+                );
+            }
             let child = self
                 .start_trigger(cmd.clone(), Some(run_opts.clone()), &trigger_args)
                 .await
@@ -352,7 +446,7 @@ impl UpCommand {
             }
         }
 
-        Ok(trigger_processes)
+        Ok((trigger_processes, run_opts.reload_on_stdin))
     }
 
     async fn start_trigger(
@@ -370,6 +464,8 @@ impl UpCommand {
             locked_url,
             working_dir,
             local_app_dir,
+            reload_on_stdin,
+            prometheus_listen_addr,
         }) = opts
         {
             cmd.env(SPIN_LOCKED_URL, locked_url)
@@ -380,6 +476,18 @@ impl UpCommand {
                 cmd.env(SPIN_LOCAL_APP_DIR, local_app_dir);
             }
 
+            if self.output == OutputFormat::Json {
+                cmd.env(SPIN_JSON_OUTPUT, "1");
+            }
+
+            if reload_on_stdin {
+                cmd.env(SPIN_RELOAD_ON_STDIN, "1").stdin(Stdio::piped());
+            }
+
+            if let Some(addr) = prometheus_listen_addr {
+                cmd.env(SPIN_PROMETHEUS_LISTEN_ADDR, addr.to_string());
+            }
+
             cmd.kill_on_drop(true);
         } else {
             cmd.env("SPIN_PLUGINS_SUPPRESS_COMPATIBILITY_WARNINGS", "1");
@@ -393,11 +501,17 @@ impl UpCommand {
     }
 
     fn app_source(&self) -> AppSource {
-        match (&self.app_source, &self.file_source, &self.registry_source) {
-            (None, None, None) => self.default_manifest_or_none(),
-            (Some(source), None, None) => AppSource::infer_source(source),
-            (None, Some(file), None) => AppSource::infer_file_source(file.to_owned()),
-            (None, None, Some(reference)) => AppSource::OciRegistry(reference.to_owned()),
+        match (
+            &self.app_source,
+            &self.file_source,
+            &self.registry_source,
+            &self.oci_layout_source,
+        ) {
+            (None, None, None, None) => self.default_manifest_or_none(),
+            (Some(source), None, None, None) => AppSource::infer_source(source),
+            (None, Some(file), None, None) => AppSource::infer_file_source(file.to_owned()),
+            (None, None, Some(reference), None) => AppSource::OciRegistry(reference.to_owned()),
+            (None, None, None, Some(layout)) => AppSource::OciLayout(layout.to_owned()),
             _ => AppSource::unresolvable("More than one application source was specified"),
         }
     }
@@ -472,6 +586,16 @@ impl UpCommand {
                     .await?;
                 ResolvedAppSource::OciRegistry { locked_app }
             }
+            AppSource::OciLayout(path) => {
+                let mut client = spin_oci::Client::new(self.insecure, self.cache_dir.clone())
+                    .await
+                    .context("cannot create registry client")?;
+
+                let locked_app = OciLoader::new(working_dir)
+                    .load_app_from_layout(&mut client, path)
+                    .await?;
+                ResolvedAppSource::OciRegistry { locked_app }
+            }
             AppSource::BareWasm(path) => ResolvedAppSource::BareWasm {
                 wasm_path: path.clone(),
             },
@@ -493,14 +617,28 @@ impl UpCommand {
                 } else {
                     FilesMountStrategy::Copy(working_dir.join("assets"))
                 };
-                spin_loader::from_file(&manifest_path, files_mount_strategy, self.cache_dir.clone())
-                    .await
-                    .with_context(|| {
-                        format!(
-                            "Failed to load manifest from {}",
-                            quoted_path(&manifest_path)
+                let cache_dir = self.cache_dir.clone();
+                let locked_app = match &self.profile {
+                    None => {
+                        spin_loader::from_file(&manifest_path, files_mount_strategy, cache_dir)
+                            .await
+                    }
+                    Some(profile) => {
+                        spin_loader::from_file_with_build_profile(
+                            &manifest_path,
+                            profile,
+                            files_mount_strategy,
+                            cache_dir,
                         )
-                    })
+                        .await
+                    }
+                };
+                locked_app.with_context(|| {
+                    format!(
+                        "Failed to load manifest from {}",
+                        quoted_path(&manifest_path)
+                    )
+                })
             }
             ResolvedAppSource::OciRegistry { locked_app } => Ok(locked_app),
             ResolvedAppSource::BareWasm { wasm_path } => spin_loader::from_wasm_file(&wasm_path)
@@ -511,6 +649,88 @@ impl UpCommand {
         }
     }
 
+    // Load the app, and apply the component selection and environment
+    // options to it.
+    async fn load_locked_app(
+        &self,
+        resolved: ResolvedAppSource,
+        working_dir: &Path,
+    ) -> anyhow::Result<LockedApp> {
+        let mut locked_app = self
+            .load_resolved_app_source(resolved, working_dir)
+            .await
+            .context("Failed to load application")?;
+
+        if !self.components.is_empty() {
+            locked_app = spin_app::retain_components(
+                locked_app,
+                &self
+                    .components
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect::<Vec<&str>>(),
+                &[&validate_service_chaining_for_components],
+            )
+            .context(
+                "failed to resolve application with only components selected with --component",
+            )?;
+        }
+
+        self.update_locked_app(&mut locked_app);
+        Ok(locked_app)
+    }
+
+    // Each time a line is read from stdin, reload the app, rewrite the lock
+    // file, and ask the trigger processes to switch to it. Returns if the
+    // reloaded app needs different triggers, or a trigger can no longer be
+    // asked to reload; the caller must then stop the triggers, and `spin watch`
+    // restarts `spin up`. Without `trigger_stdins`, the triggers can't reload
+    // in place, so this returns on the first request.
+    async fn reload_when_requested(
+        &self,
+        app_source: &AppSource,
+        working_dir: &Path,
+        running_trigger_types: &HashSet<String>,
+        mut trigger_stdins: Option<Vec<tokio::process::ChildStdin>>,
+    ) -> anyhow::Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while lines.next_line().await?.is_some() {
+            let Some(trigger_stdins) = trigger_stdins.as_mut() else {
+                terminal::einfo!(
+                    "Restarting:",
+                    "the application's triggers can't reload in place"
+                );
+                return Ok(());
+            };
+            let reloaded = match self.resolve_app_source(app_source, working_dir).await {
+                Ok(resolved) => self.load_locked_app(resolved, working_dir).await,
+                Err(err) => Err(err),
+            };
+            let locked_app = match reloaded {
+                Ok(locked_app) => locked_app,
+                Err(err) => {
+                    terminal::error!("Failed to reload application: {err:?}");
+                    continue;
+                }
+            };
+            if trigger_types(&locked_app) != *running_trigger_types {
+                terminal::einfo!(
+                    "Restarting:",
+                    "the application's trigger types have changed"
+                );
+                return Ok(());
+            }
+            self.write_locked_app(&locked_app, working_dir).await?;
+            for stdin in trigger_stdins.iter_mut() {
+                if stdin.write_all(b"reload\n").await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        // Without further reloads, run until the triggers finish
+        futures::future::pending().await
+    }
+
     fn update_locked_app(&self, locked_app: &mut LockedApp) {
         // Apply --env to component environments
         if !self.env.is_empty() {
@@ -550,6 +770,14 @@ impl UpCommand {
     }
 }
 
+fn trigger_types(locked_app: &LockedApp) -> HashSet<String> {
+    locked_app
+        .triggers
+        .iter()
+        .map(|t| t.trigger_type.clone())
+        .collect()
+}
+
 fn is_flag_arg(arg: &OsString) -> bool {
     if let Some(s) = arg.to_str() {
         s.starts_with('-')
@@ -559,17 +787,20 @@ fn is_flag_arg(arg: &OsString) -> bool {
 }
 
 #[cfg(windows)]
-fn set_kill_on_ctrl_c(_pids: &[usize]) -> Result<(), anyhow::Error> {
-    Ok(())
+fn set_kill_on_ctrl_c(_pids: &[usize]) -> Result<Arc<AtomicBool>, anyhow::Error> {
+    Ok(Default::default())
 }
 
 #[cfg(not(windows))]
-fn set_kill_on_ctrl_c(pids: &[nix::unistd::Pid]) -> Result<(), anyhow::Error> {
+fn set_kill_on_ctrl_c(pids: &[nix::unistd::Pid]) -> Result<Arc<AtomicBool>, anyhow::Error> {
     let pids = pids.to_owned();
+    let shutdown_requested = Arc::new(AtomicBool::new(false));
+    let requested = shutdown_requested.clone();
     ctrlc::set_handler(move || {
+        requested.store(true, Ordering::SeqCst);
         kill_child_processes(&pids);
     })?;
-    Ok(())
+    Ok(shutdown_requested)
 }
 
 #[cfg(windows)]
@@ -605,6 +836,8 @@ struct RunTriggerOpts {
     locked_url: String,
     working_dir: PathBuf,
     local_app_dir: Option<PathBuf>,
+    reload_on_stdin: bool,
+    prometheus_listen_addr: Option<SocketAddr>,
 }
 
 enum WorkingDirectory {
@@ -663,6 +896,21 @@ fn resolve_trigger_plugin(trigger_type: &str) -> Result<String> {
     }
 }
 
+/// Returns the address on which the trigger process at `index` serves
+/// Prometheus metrics: the configured address for the first trigger, and the
+/// following ports for the others. An ephemeral port is left for each trigger
+/// to choose.
+fn trigger_prometheus_listen_addr(addr: SocketAddr, index: usize) -> Result<SocketAddr> {
+    if addr.port() == 0 {
+        return Ok(addr);
+    }
+    let port = u16::try_from(index)
+        .ok()
+        .and_then(|index| addr.port().checked_add(index))
+        .with_context(|| format!("No port after {addr} to serve Prometheus metrics on"))?;
+    Ok(SocketAddr::new(addr.ip(), port))
+}
+
 fn trigger_command(trigger_type: &str) -> Vec<String> {
     vec!["trigger".to_owned(), trigger_type.to_owned()]
 }
@@ -916,4 +1164,23 @@ mod test {
         assert_eq!("-L", groups[2][0]);
         assert_eq!("/fie", groups[2][1]);
     }
+
+    #[test]
+    fn each_trigger_gets_its_own_prometheus_port() {
+        let addr: SocketAddr = "127.0.0.1:9090".parse().unwrap();
+        assert_eq!(trigger_prometheus_listen_addr(addr, 0).unwrap(), addr);
+        assert_eq!(
+            trigger_prometheus_listen_addr(addr, 2).unwrap(),
+            "127.0.0.1:9092".parse().unwrap()
+        );
+
+        let ephemeral: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        assert_eq!(
+            trigger_prometheus_listen_addr(ephemeral, 1).unwrap(),
+            ephemeral
+        );
+
+        let last: SocketAddr = "127.0.0.1:65535".parse().unwrap();
+        assert!(trigger_prometheus_listen_addr(last, 1).is_err());
+    }
 }
@@ -12,6 +12,7 @@ use spin_manifest::schema::v2::AppManifest;
 pub enum AppSource {
     File(PathBuf),
     OciRegistry(String),
+    OciLayout(PathBuf),
     BareWasm(PathBuf),
     Unresolvable(String),
     None,
@@ -56,9 +57,9 @@ impl AppSource {
         }
     }
 
-    pub async fn build(&self) -> anyhow::Result<()> {
+    pub async fn build(&self, options: &spin_build::BuildOptions) -> anyhow::Result<()> {
         match self {
-            Self::File(path) => spin_build::build(path, &[]).await,
+            Self::File(path) => spin_build::build(path, &[], options).await,
             _ => Ok(()),
         }
     }
@@ -74,6 +75,7 @@ impl std::fmt::Display for AppSource {
         match self {
             Self::File(path) => write!(f, "local app {}", quoted_path(path)),
             Self::OciRegistry(reference) => write!(f, "remote app {reference:?}"),
+            Self::OciLayout(path) => write!(f, "OCI layout {}", quoted_path(path)),
             Self::BareWasm(path) => write!(f, "Wasm file {}", quoted_path(path)),
             Self::Unresolvable(s) => write!(f, "unknown app source: {s:?}"),
             Self::None => write!(f, "<no source>"),
@@ -65,6 +65,10 @@ pub struct WatchCommand {
     #[clap(name = WATCH_SKIP_BUILD_OPT, long = "skip-build")]
     pub skip_build: bool,
 
+    /// The build profile to build and run the application with.
+    #[clap(long = "profile")]
+    pub profile: Option<String>,
+
     /// Arguments to be passed through to spin up.
     #[clap()]
     pub up_args: Vec<String>,
@@ -74,7 +78,8 @@ impl WatchCommand {
     pub async fn run(self) -> Result<()> {
         // Strategy:
         // * The Uppificator runs `spin up`, and watches the manifest artifacts (component.source and component.files)
-        //   (and the manifest if build is not in play). When it detects a change, it restarts `spin up`.
+        //   (and the manifest if build is not in play). When it detects a change, it asks `spin up` to reload
+        //   the app in place (over `spin up`'s stdin), or restarts `spin up` if it can't reload.
         //   THAT'S ALL, THAT'S ALL IT DOES.
         //   * If `spin up` crashes, the Uppificator restarts it.  BUT APART FROM THAT THAT'S ALL IT DOES OKAY.
         // * The Buildifier, if in play, watches the manifest and component.build.watch collections. When it detects a
@@ -112,16 +117,22 @@ impl WatchCommand {
         let mut buildifier = Buildifier {
             spin_bin: spin_bin.clone(),
             manifest: manifest_file.clone(),
+            profile: self.profile.clone(),
             clear_screen: self.clear,
             has_ever_built: false,
             watched_changes: source_code_rx,
             uppificator_pauser: pause_tx,
         };
 
+        let mut up_args = self.up_args.clone();
+        if let Some(profile) = &self.profile {
+            up_args.extend(["--profile".to_owned(), profile.clone()]);
+        }
+
         let mut uppificator = Uppificator {
             spin_bin: spin_bin.clone(),
             manifest: manifest_file.clone(),
-            up_args: self.up_args.clone(),
+            up_args,
             clear_screen: self.clear,
             watched_changes: artifact_rx,
             pause_feed: pause_rx,
@@ -247,6 +258,7 @@ impl WatchCommand {
         let rtf = RuntimeConfigFactory {
             manifest_file: manifest_file.to_owned(),
             manifest_dir: manifest_dir.to_owned(),
+            profile: self.profile.clone(),
             filter_factory,
             notifier,
             impact_description,
@@ -270,6 +282,7 @@ impl WatchCommand {
 pub struct RuntimeConfigFactory {
     manifest_file: PathBuf,
     manifest_dir: PathBuf,
+    profile: Option<String>,
     filter_factory: Box<dyn FilterFactory>,
     notifier: Arc<tokio::sync::watch::Sender<Uuid>>,
     impact_description: &'static str,
@@ -279,7 +292,12 @@ pub struct RuntimeConfigFactory {
 impl RuntimeConfigFactory {
     async fn build_config(&self) -> anyhow::Result<watchexec::config::RuntimeConfig> {
         let manifest_str = tokio::fs::read_to_string(&self.manifest_file).await?;
-        let manifest = spin_manifest::manifest_from_str(&manifest_str)?;
+        let mut manifest = spin_manifest::manifest_from_str(&manifest_str)?;
+        if let Some(profile) = &self.profile {
+            // Watch the sources and inputs of the profile being built.
+            spin_manifest::normalize::normalize_manifest(&mut manifest);
+            spin_manifest::normalize::apply_build_profile(&mut manifest, profile)?;
+        }
         let filterer = self
             .filter_factory
             .build_filter(&self.manifest_file, &self.manifest_dir, &manifest)
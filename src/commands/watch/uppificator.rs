@@ -1,5 +1,6 @@
 use command_group::AsyncCommandGroup;
-use std::path::PathBuf;
+use std::{path::PathBuf, process::Stdio};
+use tokio::{io::AsyncWriteExt, process::ChildStdin};
 use uuid::Uuid;
 
 pub(crate) struct Uppificator {
@@ -20,6 +21,7 @@ pub(crate) enum Pause {
 
 enum UppificatorAction {
     Restart,
+    Reload,
     Resume,
     Stop,
     Wait,
@@ -41,7 +43,9 @@ impl Uppificator {
             cmd.arg("up")
                 .arg("-f")
                 .arg(&self.manifest)
-                .args(&self.up_args);
+                .arg("--reload-on-stdin")
+                .args(&self.up_args)
+                .stdin(Stdio::piped());
             let mut child = match cmd.group_spawn() {
                 Ok(ch) => ch,
                 Err(e) => {
@@ -49,12 +53,23 @@ impl Uppificator {
                     break 'run;
                 }
             };
+            let mut reloader = Reloader {
+                stdin: child.inner().stdin.take(),
+                has_reloaded: false,
+            };
 
             let mut resuming_after_build = false;
 
             loop {
-                match self.next_event(&mut child).await {
+                match self.next_event(&mut child, &mut reloader).await {
                     UppificatorAction::Restart => break,
+                    UppificatorAction::Reload => {
+                        if self.clear_screen && !resuming_after_build {
+                            _ = clearscreen::clear();
+                        }
+                        resuming_after_build = false;
+                        continue;
+                    }
                     UppificatorAction::Resume => {
                         resuming_after_build = true;
                         continue;
@@ -73,14 +88,25 @@ impl Uppificator {
     async fn next_event(
         &mut self,
         child: &mut command_group::AsyncGroupChild,
+        reloader: &mut Reloader,
     ) -> UppificatorAction {
         tokio::select! {
             _ = child.wait() => {
-                UppificatorAction::Wait
+                if reloader.has_reloaded {
+                    // `spin up` exits if the running triggers can't switch
+                    // to the reloaded app, so start it afresh.
+                    UppificatorAction::Restart
+                } else {
+                    UppificatorAction::Wait
+                }
             },
             _ = self.watched_changes.changed() => {
-                stop(child).await;
-                UppificatorAction::Restart
+                if reloader.reload().await {
+                    UppificatorAction::Reload
+                } else {
+                    stop(child).await;
+                    UppificatorAction::Restart
+                }
             },
             p = self.pause_feed.recv() => {
                 if matches!(p, Some(Pause::Pause)) {
@@ -102,6 +128,30 @@ impl Uppificator {
     }
 }
 
+/// Asks a running `spin up` to reload the app in place, by writing a line to
+/// its stdin.
+struct Reloader {
+    stdin: Option<ChildStdin>,
+    has_reloaded: bool,
+}
+
+impl Reloader {
+    /// Returns false if `spin up` could not be asked to reload, in which case
+    /// it must be restarted instead.
+    async fn reload(&mut self) -> bool {
+        let Some(stdin) = self.stdin.as_mut() else {
+            return false;
+        };
+        if let Err(e) = stdin.write_all(b"reload\n").await {
+            tracing::warn!("Could not ask `spin up` to reload: {e:#}");
+            self.stdin = None;
+            return false;
+        }
+        self.has_reloaded = true;
+        true
+    }
+}
+
 #[cfg(unix)]
 async fn stop(child: &mut command_group::AsyncGroupChild) {
     if let Some(child_id) = child.id() {